@@ -5,6 +5,11 @@
 //! - [`RandomRemoval`] — Removes random customers
 //! - [`WorstRemoval`] — Removes customers with highest removal cost savings
 //! - [`ShawRemoval`] — Removes related (nearby) customers
+//! - [`RequestRemoval`] — Removes random pickup-and-delivery requests (both halves)
+//! - [`WorstRequestRemoval`] — Removes the requests whose removal saves the most
+//!
+//! The request-based operators keep paired pickups and deliveries together;
+//! use them for PDP instances so that a pair is never split.
 //!
 //! # Reference
 //!
@@ -16,7 +21,7 @@ use rand::Rng;
 use u_metaheur::alns::DestroyOperator;
 
 use crate::distance::DistanceMatrix;
-use crate::models::Customer;
+use crate::models::{Customer, Load, RequestRole, RouteEnds};
 
 use super::solution_repr::RoutingSolution;
 
@@ -106,21 +111,25 @@ impl WorstRemoval {
         Self { distances }
     }
 
-    /// Computes the cost saving from removing a customer at a given position.
-    fn removal_saving(&self, route: &[usize], pos: usize) -> f64 {
-        let depot = 0;
+    /// Computes the cost saving from removing a customer at a given position
+    /// of a route with the given `ends`.
+    fn removal_saving(&self, route: &[usize], ends: RouteEnds, pos: usize) -> f64 {
         let cid = route[pos];
-        let prev = if pos == 0 { depot } else { route[pos - 1] };
+        let prev = if pos == 0 {
+            ends.start()
+        } else {
+            route[pos - 1]
+        };
         let next = if pos == route.len() - 1 {
-            depot
+            ends.end_node()
         } else {
             route[pos + 1]
         };
 
         // Old: prev → cid → next, New: prev → next
         // Saving = old - new (positive means removing saves distance)
-        self.distances.get(prev, cid) + self.distances.get(cid, next)
-            - self.distances.get(prev, next)
+        let dist = |a, b| ends.leg(a, b, &self.distances);
+        dist(prev, cid) + dist(cid, next) - dist(prev, next)
     }
 }
 
@@ -147,7 +156,7 @@ impl DestroyOperator<RoutingSolution> for WorstRemoval {
 
             for (ri, route) in sol.routes().iter().enumerate() {
                 for pos in 0..route.len() {
-                    let saving = self.removal_saving(route, pos);
+                    let saving = self.removal_saving(route, sol.ends_of(ri), pos);
                     // Add small randomness to break ties
                     let noise = rng.random_range(0.0..0.01f64);
                     if saving + noise > best_saving {
@@ -194,10 +203,13 @@ impl ShawRemoval {
         }
     }
 
-    /// Relatedness: inverse distance + demand similarity.
+    /// Relatedness: inverse distance + demand similarity (summed over dimensions).
     fn relatedness(&self, a: usize, b: usize) -> f64 {
         let dist = self.distances.get(a, b);
-        let demand_diff = (self.customers[a].demand() - self.customers[b].demand()).abs() as f64;
+        let mut diff = Load::new();
+        diff.add(self.customers[a].demands());
+        diff.sub(self.customers[b].demands());
+        let demand_diff: f64 = diff.amounts().iter().map(|d| d.abs() as f64).sum();
         // Higher relatedness = more similar
         1.0 / (dist + 0.1) + 1.0 / (demand_diff + 1.0)
     }
@@ -269,6 +281,176 @@ impl DestroyOperator<RoutingSolution> for ShawRemoval {
     }
 }
 
+/// Removes random pickup-and-delivery requests from the solution.
+///
+/// Picks a random assigned customer and removes it together with its paired
+/// stop (if any). Unpaired customers are removed on their own.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, RequestRole};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::alns::{RoutingSolution, destroy::RequestRemoval};
+/// use u_metaheur::alns::DestroyOperator;
+///
+/// let cust = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0)
+///         .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+///     Customer::new(2, 2.0, 0.0, -10, 0.0)
+///         .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&cust);
+/// let sol = RoutingSolution::new(vec![vec![1, 2]], vec![], &cust, &dm);
+///
+/// let mut rng = u_numflow::random::create_rng(42);
+/// let destroyed = RequestRemoval::new(cust).destroy(&sol, 0.1, &mut rng);
+/// assert_eq!(destroyed.unassigned().len(), 2);
+/// ```
+pub struct RequestRemoval {
+    customers: Vec<Customer>,
+}
+
+impl RequestRemoval {
+    /// Creates a new request removal operator.
+    pub fn new(customers: Vec<Customer>) -> Self {
+        Self { customers }
+    }
+}
+
+impl DestroyOperator<RoutingSolution> for RequestRemoval {
+    fn name(&self) -> &str {
+        "request_removal"
+    }
+
+    fn destroy<R: Rng>(
+        &self,
+        solution: &RoutingSolution,
+        degree: f64,
+        rng: &mut R,
+    ) -> RoutingSolution {
+        let mut sol = solution.clone();
+        let total_customers: usize = sol.routes().iter().map(|r| r.len()).sum();
+        let num_remove = ((total_customers as f64 * degree).round() as usize).max(1);
+
+        let mut removed = 0;
+        while removed < num_remove {
+            let assigned: Vec<usize> = sol
+                .routes()
+                .iter()
+                .flat_map(|r| r.iter().copied())
+                .collect();
+            if assigned.is_empty() {
+                break;
+            }
+
+            let target = assigned[rng.random_range(0..assigned.len() as u64) as usize];
+            removed += remove_request(&mut sol, &self.customers, target);
+        }
+
+        sol.remove_empty_routes();
+        sol
+    }
+}
+
+/// Removes the requests whose removal yields the largest distance saving.
+///
+/// Like [`WorstRemoval`], but the saving of a paired request is measured by
+/// removing both its pickup and its delivery from the route.
+pub struct WorstRequestRemoval {
+    distances: DistanceMatrix,
+    customers: Vec<Customer>,
+}
+
+impl WorstRequestRemoval {
+    /// Creates a new worst request removal operator.
+    pub fn new(distances: DistanceMatrix, customers: Vec<Customer>) -> Self {
+        Self {
+            distances,
+            customers,
+        }
+    }
+
+    /// Distance saved by removing `customer_id` and its paired stop from a
+    /// route with the given `ends`.
+    fn removal_saving(&self, route: &[usize], ends: RouteEnds, customer_id: usize) -> f64 {
+        let partner = self.customers[customer_id].paired_with();
+        let reduced: Vec<usize> = route
+            .iter()
+            .copied()
+            .filter(|&c| c != customer_id && Some(c) != partner)
+            .collect();
+        ends.distance(route, &self.distances) - ends.distance(&reduced, &self.distances)
+    }
+}
+
+impl DestroyOperator<RoutingSolution> for WorstRequestRemoval {
+    fn name(&self) -> &str {
+        "worst_request_removal"
+    }
+
+    fn destroy<R: Rng>(
+        &self,
+        solution: &RoutingSolution,
+        degree: f64,
+        rng: &mut R,
+    ) -> RoutingSolution {
+        let mut sol = solution.clone();
+        let total_customers: usize = sol.routes().iter().map(|r| r.len()).sum();
+        let num_remove = ((total_customers as f64 * degree).round() as usize).max(1);
+
+        let mut removed = 0;
+        while removed < num_remove {
+            let mut best_saving = f64::NEG_INFINITY;
+            let mut best_customer = None;
+
+            for (ri, route) in sol.routes().iter().enumerate() {
+                for &cid in route {
+                    // Evaluate each request once, from its pickup (or lone) stop
+                    if let Some(RequestRole::Delivery { pickup_id }) =
+                        self.customers[cid].request_role()
+                    {
+                        if route.contains(&pickup_id) {
+                            continue;
+                        }
+                    }
+                    let saving = self.removal_saving(route, sol.ends_of(ri), cid);
+                    let noise = rng.random_range(0.0..0.01f64);
+                    if saving + noise > best_saving {
+                        best_saving = saving + noise;
+                        best_customer = Some(cid);
+                    }
+                }
+            }
+
+            let Some(cid) = best_customer else {
+                break;
+            };
+            removed += remove_request(&mut sol, &self.customers, cid);
+        }
+
+        sol.remove_empty_routes();
+        sol
+    }
+}
+
+/// Removes a customer and its paired stop (if routed) into the unassigned list.
+///
+/// Returns the number of stops removed.
+fn remove_request(sol: &mut RoutingSolution, customers: &[Customer], customer_id: usize) -> usize {
+    let mut removed = 0;
+    let stops = std::iter::once(customer_id).chain(customers[customer_id].paired_with());
+    for cid in stops {
+        if sol.routes().iter().any(|r| r.contains(&cid)) {
+            remove_customer(sol, cid);
+            sol.unassigned_mut().push(cid);
+            removed += 1;
+        }
+    }
+    removed
+}
+
 /// Removes a customer from the solution's routes.
 fn remove_customer(sol: &mut RoutingSolution, customer_id: usize) {
     for route in sol.routes_mut() {
@@ -319,6 +501,26 @@ mod tests {
         assert_eq!(destroyed.unassigned().len(), 1);
     }
 
+    #[test]
+    fn test_worst_removal_uses_route_depot() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 2.0, 0.0, 10, 0.0),
+            Customer::new(2, 9.0, 0.0, 10, 0.0),
+            Customer::depot_with_id(3, 10.0, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let sol = RoutingSolution::new(vec![vec![1], vec![2]], vec![], &cust, &dm)
+            .with_depots(vec![0, 3], &dm);
+        let op = WorstRemoval::new(dm.clone());
+        let mut rng = u_numflow::random::create_rng(42);
+        let destroyed = op.destroy(&sol, 0.5, &mut rng);
+        // Customer 2 is only 1 away from its own depot: 1 is costlier
+        assert_eq!(destroyed.unassigned(), &[1]);
+        assert_eq!(destroyed.num_routes(), 1);
+        assert_eq!(destroyed.depot_of(0), 3);
+    }
+
     #[test]
     fn test_shaw_removal() {
         let (cust, dm) = setup();
@@ -333,6 +535,52 @@ mod tests {
         assert!((removed[0] as i32 - removed[1] as i32).unsigned_abs() <= 2);
     }
 
+    fn pdp_setup() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+            Customer::new(2, 2.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+            Customer::new(3, 0.0, 1.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 0.0, 20.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+            Customer::new(5, 1.0, 1.0, 5, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_request_removal_keeps_pairs_together() {
+        let (cust, dm) = pdp_setup();
+        let sol = RoutingSolution::new(vec![vec![1, 3, 2, 4, 5]], vec![], &cust, &dm);
+        let op = RequestRemoval::new(cust.clone());
+        for seed in 0..10 {
+            let mut rng = u_numflow::random::create_rng(seed);
+            let destroyed = op.destroy(&sol, 0.4, &mut rng);
+            for &cid in destroyed.unassigned() {
+                if let Some(partner) = cust[cid].paired_with() {
+                    assert!(destroyed.unassigned().contains(&partner));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_worst_request_removal() {
+        let (cust, dm) = pdp_setup();
+        let sol = RoutingSolution::new(vec![vec![1, 2], vec![3, 4, 5]], vec![], &cust, &dm);
+        let op = WorstRequestRemoval::new(dm.clone(), cust.clone());
+        let mut rng = u_numflow::random::create_rng(42);
+        let destroyed = op.destroy(&sol, 0.2, &mut rng);
+        // The far-away delivery 4 makes request (3, 4) the most expensive
+        let mut removed = destroyed.unassigned().to_vec();
+        removed.sort();
+        assert_eq!(removed, vec![3, 4]);
+    }
+
     #[test]
     fn test_removal_preserves_all_customers() {
         let (cust, dm) = setup();
@@ -2,8 +2,10 @@
 //!
 //! - [`RoutingSolution`] — Lightweight solution representation for ALNS
 //! - [`RoutingAlnsProblem`] — [`AlnsProblem`](u_metaheur::alns::AlnsProblem) implementation
-//! - [`destroy`] — Destroy operators (random, worst, Shaw)
-//! - [`repair`] — Repair operators (greedy insertion, regret insertion)
+//! - [`destroy`] — Destroy operators (random, worst, Shaw, and request-based
+//!   removal for pickup-and-delivery pairs)
+//! - [`repair`] — Repair operators (greedy insertion, regret insertion, and
+//!   paired request insertion)
 
 pub mod destroy;
 mod problem;
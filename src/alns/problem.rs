@@ -2,14 +2,50 @@
 //!
 //! Implements the [`AlnsProblem`](u_metaheur::alns::AlnsProblem) trait
 //! for capacitated VRP, enabling the ALNS runner to optimize routing solutions.
+//!
+//! Instances with paired pickups and deliveries (PDPTW) are supported: the
+//! initial solution is then built by [`RequestInsertion`], and the
+//! request-based destroy/repair operators keep each pair on one route.
+//!
+//! With a heterogeneous fleet ([`RoutingAlnsProblem::with_fleet`]) the cost
+//! of each route is its vehicle's fixed cost plus distance cost; pair it with
+//! repair operators configured with the same fleet. Vehicle types may be
+//! based at different depots (multi-depot VRP); each route is costed from
+//! its own depot, and back to it unless the vehicle runs open routes.
+//!
+//! The cost of a solution is its route cost (including soft time-window
+//! penalties) plus, for each unassigned
+//! customer, a penalty: the prize of an optional customer (prize-collecting
+//! VRP) or a large fixed penalty ([`RoutingAlnsProblem::with_unassigned_penalty`])
+//! for a mandatory one. Routes and unassigned customers are priced by a
+//! [`CostFunction`] ([`RoutingAlnsProblem::with_cost_function`],
+//! [`StandardCost`] by default).
+//!
+//! Customers that [allow split delivery](Customer::with_split_delivery) may
+//! be served by several routes (see [`RoutingSolution::set_quantity`]); one
+//! that is only partly delivered stays unassigned and pays the full penalty.
+//!
+//! The routes of a [multi-trip](crate::models::Vehicle::with_reload_time)
+//! vehicle type are trips, packed onto vehicles
+//! ([`RouteEvaluator::pack_trips`]) with the fixed cost charged once per
+//! vehicle.
+//!
+//! With a route-balancing [`Objective`] ([`RoutingAlnsProblem::with_objective`])
+//! the cost ranks solutions by makespan, spread of route durations or number
+//! of routes before the route cost; unassigned penalties are weighted to rank
+//! above both.
+
+use std::sync::{Arc, OnceLock};
 
 use rand::Rng;
-use u_metaheur::alns::AlnsProblem;
+use u_metaheur::alns::{AlnsProblem, RepairOperator};
 
 use crate::constructive::nearest_neighbor;
 use crate::distance::DistanceMatrix;
-use crate::models::{Customer, Vehicle};
+use crate::evaluation::{primary_weight, CostFunction, Objective, RouteEvaluator, StandardCost};
+use crate::models::{fleet_index, stop_ids, Customer, RouteEnds, Vehicle};
 
+use super::repair::RequestInsertion;
 use super::solution_repr::RoutingSolution;
 
 /// ALNS problem for capacitated vehicle routing.
@@ -46,7 +82,12 @@ use super::solution_repr::RoutingSolution;
 pub struct RoutingAlnsProblem {
     customers: Vec<Customer>,
     distances: DistanceMatrix,
-    capacity: i32,
+    fleet: Vec<Vehicle>,
+    unassigned_penalty: f64,
+    objective: Objective,
+    cost_function: Arc<dyn CostFunction>,
+    /// Weight of the objective's primary measure, computed on first use.
+    primary_weight: OnceLock<f64>,
 }
 
 impl RoutingAlnsProblem {
@@ -55,32 +96,223 @@ impl RoutingAlnsProblem {
         Self {
             customers,
             distances,
-            capacity,
+            fleet: vec![Vehicle::new(0, capacity)],
+            unassigned_penalty: 10_000.0,
+            objective: Objective::TotalCost,
+            cost_function: Arc::new(StandardCost),
+            primary_weight: OnceLock::new(),
         }
     }
+
+    /// Sets a multi-dimensional vehicle capacity, replacing the one given to
+    /// [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.fleet = vec![Vehicle::new(0, 0).with_capacities(capacities)];
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
+    /// Uses a heterogeneous fleet, replacing the capacity given to
+    /// [`new`](Self::new).
+    ///
+    /// Each entry is a vehicle type with its own capacity, costs and
+    /// optional availability limit. Vehicle IDs must be unique.
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.fleet = fleet.to_vec();
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
+    /// Sets the penalty for each mandatory customer left unassigned
+    /// (10,000 by default).
+    ///
+    /// Optional customers are penalized by their prize instead.
+    pub fn with_unassigned_penalty(mut self, penalty: f64) -> Self {
+        self.unassigned_penalty = penalty;
+        self
+    }
+
+    /// Sets what the cost minimizes ([`Objective::TotalCost`] by default).
+    ///
+    /// Pair a route-balancing objective with repair operators that leave
+    /// room to balance, e.g. a fleet with a limited number of vehicles.
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Prices routes and unassigned customers with `cost` instead of
+    /// [`StandardCost`].
+    ///
+    /// Repair operators still insert by the standard cost; the cost function
+    /// decides which solutions are accepted.
+    pub fn with_cost_function(mut self, cost: impl CostFunction + 'static) -> Self {
+        self.cost_function = Arc::new(cost);
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
+    /// Weight of one unit of the objective's primary measure: an upper
+    /// bound on the route cost of any solution (see [`Objective::value`]).
+    fn primary_weight(&self) -> f64 {
+        *self.primary_weight.get_or_init(|| {
+            primary_weight(
+                &self.customers,
+                &self.distances,
+                &self.fleet,
+                &*self.cost_function,
+            )
+        })
+    }
+
+    /// Penalty for leaving `customer_id` unassigned.
+    pub(crate) fn unassigned_cost(&self, customer_id: usize) -> f64 {
+        self.cost_function
+            .unassigned_cost(&self.customers[customer_id], self.unassigned_penalty)
+    }
+
+    /// Cost of the routes of a solution, without unassigned penalties.
+    pub(crate) fn routes_cost(&self, solution: &RoutingSolution) -> f64 {
+        // Trips of multi-trip vehicle types are packed onto vehicles
+        let mut trips: Vec<Vec<Vec<usize>>> = vec![Vec::new(); self.fleet.len()];
+        let mut route_costs = 0.0;
+        for (ri, route) in solution.routes().iter().enumerate() {
+            let k = solution.vehicle_of(ri);
+            if self.fleet[k].is_multi_trip() {
+                if !route.is_empty() {
+                    trips[k].push(route.clone());
+                }
+            } else {
+                route_costs += self.route_cost(solution, ri);
+            }
+        }
+        for (vehicle, trips) in self.fleet.iter().zip(&trips) {
+            if !trips.is_empty() {
+                route_costs += RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                    .with_cost_function(&*self.cost_function)
+                    .packed_trips_cost(trips);
+            }
+        }
+        route_costs
+    }
+
+    /// Duration of each vehicle used by a solution; trips of multi-trip
+    /// vehicle types are packed onto vehicles first.
+    pub(crate) fn route_durations(&self, solution: &RoutingSolution) -> Vec<f64> {
+        let mut trips: Vec<Vec<Vec<usize>>> = vec![Vec::new(); self.fleet.len()];
+        let mut durations = Vec::new();
+        for (ri, route) in solution.routes().iter().enumerate() {
+            if route.is_empty() {
+                continue;
+            }
+            let k = solution.vehicle_of(ri);
+            if self.fleet[k].is_multi_trip() {
+                trips[k].push(route.clone());
+            } else {
+                durations.push(
+                    RouteEvaluator::new(&self.customers, &self.distances, &self.fleet[k])
+                        .duration(route),
+                );
+            }
+        }
+        for (vehicle, trips) in self.fleet.iter().zip(&trips) {
+            if !trips.is_empty() {
+                durations.extend(
+                    RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                        .packed_trips_durations(trips),
+                );
+            }
+        }
+        durations
+    }
+
+    /// Cost of route `route_index` on its vehicle type under the cost
+    /// function, driven between the route's own ends.
+    fn route_cost(&self, solution: &RoutingSolution, route_index: usize) -> f64 {
+        let route = &solution.routes()[route_index];
+        if route.is_empty() {
+            return 0.0;
+        }
+        let vehicle = &self.fleet[solution.vehicle_of(route_index)];
+        let metrics = RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+            .metrics_between(route, solution.ends_of(route_index));
+        self.cost_function.route_cost(vehicle, &metrics)
+    }
 }
 
 impl AlnsProblem for RoutingAlnsProblem {
     type Solution = RoutingSolution;
 
-    fn initial_solution<R: Rng>(&self, _rng: &mut R) -> RoutingSolution {
-        // Use nearest neighbor heuristic for initial solution
-        let vehicles: Vec<Vehicle> = (0..self.customers.len())
-            .map(|i| Vehicle::new(i, self.capacity))
+    fn initial_solution<R: Rng>(&self, rng: &mut R) -> RoutingSolution {
+        // Paired requests: insert pickups and deliveries together
+        if self.customers.iter().any(|c| c.request_role().is_some()) {
+            let unassigned: Vec<usize> = stop_ids(&self.customers).collect();
+            let empty =
+                RoutingSolution::new(Vec::new(), unassigned, &self.customers, &self.distances);
+            let op = RequestInsertion::new(self.distances.clone(), self.customers.clone(), 0)
+                .with_fleet(&self.fleet);
+            return op.repair(&empty, rng);
+        }
+
+        // Use nearest neighbor heuristic for initial solution, with one
+        // vehicle per available unit of each type (at most one per customer)
+        let vehicles: Vec<Vehicle> = self
+            .fleet
+            .iter()
+            .flat_map(|v| {
+                let count = v.available().unwrap_or(self.customers.len());
+                std::iter::repeat_n(v.clone(), count.min(self.customers.len()))
+            })
             .collect();
 
         let nn_sol = nearest_neighbor(&self.customers, &self.distances, &vehicles);
 
         let routes: Vec<Vec<usize>> = nn_sol.routes().iter().map(|r| r.customer_ids()).collect();
+        let route_vehicles: Vec<usize> = nn_sol
+            .routes()
+            .iter()
+            .map(|r| {
+                fleet_index(&self.fleet, r.vehicle_id())
+                    .expect("nearest neighbor routes use fleet vehicles")
+            })
+            .collect();
+        let route_ends: Vec<RouteEnds> = route_vehicles
+            .iter()
+            .map(|&k| self.fleet[k].route_ends())
+            .collect();
         let unassigned: Vec<usize> = nn_sol.unassigned().to_vec();
 
-        RoutingSolution::new(routes, unassigned, &self.customers, &self.distances)
+        let mut solution =
+            RoutingSolution::new(routes, unassigned, &self.customers, &self.distances)
+                .with_vehicles(route_vehicles)
+                .with_route_ends(route_ends, &self.distances);
+        // Keep the quantities of split deliveries
+        for (ri, route) in nn_sol.routes().iter().enumerate() {
+            for (cid, quantity) in route.customer_ids().into_iter().zip(route.quantities()) {
+                if quantity != self.customers[cid].demand() {
+                    solution.set_quantity(ri, cid, quantity);
+                }
+            }
+        }
+        solution
     }
 
     fn cost(&self, solution: &RoutingSolution) -> f64 {
-        // Penalize unassigned customers heavily
-        let unassigned_penalty = solution.unassigned().len() as f64 * 10_000.0;
-        solution.total_distance() + unassigned_penalty
+        // Forfeited prizes, and a heavy penalty for mandatory customers
+        let unassigned_penalty: f64 = solution
+            .unassigned()
+            .iter()
+            .map(|&cid| self.unassigned_cost(cid))
+            .sum();
+        let routes_cost = self.routes_cost(solution);
+        match self.objective {
+            Objective::TotalCost => routes_cost + unassigned_penalty,
+            objective => {
+                let weight = self.primary_weight();
+                objective.value(routes_cost, &self.route_durations(solution), weight)
+                    + objective.penalty_weight(weight) * unassigned_penalty
+            }
+        }
     }
 }
 
@@ -93,8 +325,10 @@ mod tests {
     use super::*;
     use u_metaheur::alns::{AlnsConfig, AlnsRunner};
 
-    use super::super::destroy::{RandomRemoval, ShawRemoval, WorstRemoval};
+    use super::super::destroy::{RandomRemoval, RequestRemoval, ShawRemoval, WorstRemoval};
     use super::super::repair::{GreedyInsertion, RegretInsertion};
+    use crate::evaluation::RouteMetrics;
+    use crate::models::{Load, RequestRole, TimeWindow};
 
     fn setup() -> (Vec<Customer>, DistanceMatrix) {
         let customers = vec![
@@ -127,6 +361,169 @@ mod tests {
         assert!(problem.cost(&sol_partial) > problem.cost(&sol_full));
     }
 
+    #[test]
+    fn test_cost_optional_prize() {
+        let (mut cust, dm) = setup();
+        cust[3] = cust[3].clone().with_prize(5.0).with_optional();
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 30);
+        let sol = RoutingSolution::new(vec![vec![1, 2]], vec![3], &cust, &dm);
+        // 0→1→2→0 = 4, plus the forfeited prize of 5
+        assert!((problem.cost(&sol) - 9.0).abs() < 1e-10);
+
+        let problem = problem.with_unassigned_penalty(100.0);
+        let sol = RoutingSolution::new(vec![vec![1]], vec![2, 3], &cust, &dm);
+        assert!((problem.cost(&sol) - 107.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cost_multi_trip() {
+        let (cust, dm) = setup();
+        let van = Vehicle::new(0, 10)
+            .with_fixed_cost(50.0)
+            .with_reload_time(0.0);
+        let sol = RoutingSolution::new(vec![vec![1], vec![2], vec![3]], vec![], &cust, &dm);
+        // Trips of 2, 4 and 6 run by a single van
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 0)
+            .with_fleet(std::slice::from_ref(&van));
+        assert!((problem.cost(&sol) - 62.0).abs() < 1e-10);
+        // The third trip no longer fits in the shift: a second van is needed
+        let van = van.with_shift(0.0, 10.0);
+        let problem = RoutingAlnsProblem::new(cust, dm, 0).with_fleet(&[van]);
+        assert!((problem.cost(&sol) - 112.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cost_soft_time_window_penalty() {
+        let (mut cust, dm) = setup();
+        let tw = TimeWindow::new(0.0, 0.5)
+            .expect("valid")
+            .with_soft_late(10.0, 2.0);
+        cust[1] = cust[1].clone().with_time_window(tw);
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 30);
+        // Distance 6; customer 1 is reached at 1 (0.5 late) or at 5 (4.5 late)
+        let sol = RoutingSolution::new(vec![vec![1, 2, 3]], vec![], &cust, &dm);
+        assert!((problem.cost(&sol) - 7.0).abs() < 1e-10);
+        let sol = RoutingSolution::new(vec![vec![3, 2, 1]], vec![], &cust, &dm);
+        assert!((problem.cost(&sol) - 15.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cost_objectives() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 0.0, 10, 0.0),
+            Customer::new(2, -5.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        // Both cost 20: one route of 20, or two of 10 each
+        let one = RoutingSolution::new(vec![vec![1, 2]], vec![], &cust, &dm);
+        let two = RoutingSolution::new(vec![vec![1], vec![2]], vec![], &cust, &dm);
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 30);
+        assert_eq!(problem.route_durations(&two), vec![10.0, 10.0]);
+
+        let problem = problem.with_objective(Objective::Makespan);
+        let weight = problem.primary_weight();
+        assert!(weight > 20.0);
+        assert!((problem.cost(&two) - (10.0 * weight + 20.0)).abs() < 1e-6);
+        assert!(problem.cost(&two) < problem.cost(&one));
+        // Dropping a customer never pays off
+        let partial = RoutingSolution::new(vec![vec![1]], vec![2], &cust, &dm);
+        assert!(problem.cost(&partial) > problem.cost(&one));
+
+        let problem = problem.with_objective(Objective::FewestRoutes);
+        assert!(problem.cost(&one) < problem.cost(&two));
+        let problem = problem.with_objective(Objective::DurationSpread);
+        assert!(problem.cost(&two) < problem.cost(&partial));
+
+        // The shorter makespan still wins when a second route costs 1e7
+        let problem = problem
+            .with_objective(Objective::Makespan)
+            .with_fleet(&[Vehicle::new(0, 30).with_fixed_cost(1e7)]);
+        assert!(problem.primary_weight() > 2e7);
+        assert!(problem.cost(&two) < problem.cost(&one));
+    }
+
+    #[test]
+    fn test_cost_function() {
+        struct PerRoute;
+        impl CostFunction for PerRoute {
+            fn route_cost(&self, _vehicle: &Vehicle, metrics: &RouteMetrics) -> f64 {
+                100.0 + metrics.distance
+            }
+            fn unassigned_cost(&self, _customer: &Customer, _penalty: f64) -> f64 {
+                1.0
+            }
+        }
+
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 0.0, 10, 0.0),
+            Customer::new(2, -5.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let one = RoutingSolution::new(vec![vec![1, 2]], vec![], &cust, &dm);
+        let two = RoutingSolution::new(vec![vec![1], vec![2]], vec![], &cust, &dm);
+        let partial = RoutingSolution::new(vec![vec![1]], vec![2], &cust, &dm);
+        let problem = RoutingAlnsProblem::new(cust, dm, 30).with_cost_function(PerRoute);
+        assert!((problem.cost(&one) - 120.0).abs() < 1e-10);
+        assert!((problem.cost(&two) - 220.0).abs() < 1e-10);
+        assert!((problem.cost(&partial) - 111.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_runner_drops_unprofitable_optional() {
+        let mut cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 0.0, 50.0, 10, 0.0)
+                .with_prize(10.0)
+                .with_optional(),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let destroy_ops = vec![RandomRemoval];
+        let config = AlnsConfig::default().with_max_iterations(100).with_seed(42);
+
+        // Serving customer 3 costs about 96 more than its prize of 10
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 30);
+        let repair_ops = vec![GreedyInsertion::new(dm.clone(), cust.clone(), 30)];
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert_eq!(result.best.unassigned(), &[3]);
+        assert!((result.best_cost - 14.0).abs() < 1e-10);
+
+        // A prize of 500 makes it worth the detour
+        cust[3] = cust[3].clone().with_prize(500.0);
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 30);
+        let repair_ops = vec![GreedyInsertion::new(dm, cust, 30)];
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert!(result.best.unassigned().is_empty());
+    }
+
+    #[test]
+    fn test_alns_split_delivery() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 150, 0.0).with_split_delivery(),
+            Customer::new(2, 11.0, 0.0, 30, 0.0),
+            Customer::new(3, 0.0, 10.0, 60, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 100);
+        let destroy_ops = vec![RandomRemoval];
+        let repair_ops = vec![GreedyInsertion::new(dm, cust.clone(), 100)];
+        let config = AlnsConfig::default().with_max_iterations(200).with_seed(42);
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        let best = &result.best;
+        assert!(best.unassigned().is_empty());
+        for cid in 1..=3 {
+            assert_eq!(best.delivered(cid, &cust), cust[cid].demand());
+        }
+        for ri in 0..best.num_routes() {
+            assert!(best.quantities_of(ri, &cust).iter().sum::<i32>() <= 100);
+        }
+    }
+
     #[test]
     fn test_alns_runner_basic() {
         let (cust, dm) = setup();
@@ -168,4 +565,148 @@ mod tests {
         assert!(result.best_cost < f64::INFINITY);
         assert!(result.best.unassigned().is_empty());
     }
+
+    #[test]
+    fn test_alns_runner_pickup_delivery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+            Customer::new(2, 5.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+            Customer::new(3, 0.0, 1.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 0.0, 5.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+            Customer::new(5, 3.0, 3.0, 5, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let capacity = 15;
+        let problem = RoutingAlnsProblem::new(customers.clone(), dm.clone(), capacity);
+        let destroy_ops = vec![RequestRemoval::new(customers.clone())];
+        let repair_ops = vec![RequestInsertion::new(
+            dm.clone(),
+            customers.clone(),
+            capacity,
+        )];
+        let config = AlnsConfig::default().with_max_iterations(200).with_seed(42);
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert!(result.best.unassigned().is_empty());
+        let vehicle = Vehicle::new(0, capacity);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        for route in result.best.routes() {
+            assert!(eval.build_route(route).1.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_cost_uses_route_vehicle() {
+        let (cust, dm) = setup();
+        let fleet = [
+            Vehicle::new(0, 30),
+            Vehicle::new(1, 30)
+                .with_fixed_cost(10.0)
+                .with_cost_per_distance(2.0),
+        ];
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 0).with_fleet(&fleet);
+        let sol = RoutingSolution::new(vec![vec![1], vec![2, 3]], vec![], &cust, &dm)
+            .with_vehicles(vec![0, 1]);
+        // 2 + (10 + 2 × 6)
+        assert!((problem.cost(&sol) - 24.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_runner_heterogeneous_fleet() {
+        let (cust, dm) = setup();
+        // One van that cannot carry everything, plus costly trucks
+        let fleet = [
+            Vehicle::new(0, 20).with_available(1),
+            Vehicle::new(1, 30).with_cost_per_distance(3.0),
+        ];
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let initial = problem.initial_solution(&mut rng);
+        assert!(initial.unassigned().is_empty());
+
+        let destroy_ops = vec![RandomRemoval];
+        let repair_ops = vec![GreedyInsertion::new(dm, cust, 0).with_fleet(&fleet)];
+        let config = AlnsConfig::default().with_max_iterations(200).with_seed(42);
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert!(result.best.unassigned().is_empty());
+        assert!(result.best.vehicles().iter().filter(|&&k| k == 0).count() <= 1);
+        assert!(result.best_cost <= problem.cost(&initial));
+    }
+
+    #[test]
+    fn test_alns_runner_multi_depot() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 1.0, 1.0, 10, 0.0),
+            Customer::depot_with_id(3, 10.0, 0.0),
+            Customer::new(4, 9.0, 0.0, 10, 0.0),
+            Customer::new(5, 9.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let fleet = [Vehicle::new(0, 20), Vehicle::new(1, 20).with_depot(3)];
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let initial = problem.initial_solution(&mut rng);
+        assert!(initial.routes().iter().flatten().all(|&c| c != 3));
+
+        let destroy_ops = vec![RandomRemoval];
+        let repair_ops = vec![GreedyInsertion::new(dm, cust, 0).with_fleet(&fleet)];
+        let config = AlnsConfig::default().with_max_iterations(200).with_seed(42);
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert!(result.best.unassigned().is_empty());
+        // Each depot serves its two neighbours: 2 × (1 + 1 + √2)
+        let expected = 2.0 * (2.0 + 2.0_f64.sqrt());
+        assert!((result.best_cost - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_runner_open_routes() {
+        let (cust, dm) = setup();
+        let fleet = [Vehicle::new(0, 30).with_open_route()];
+        let problem = RoutingAlnsProblem::new(cust.clone(), dm.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let initial = problem.initial_solution(&mut rng);
+        assert!(initial.route_ends().iter().all(|e| e.is_open()));
+
+        let destroy_ops = vec![RandomRemoval];
+        let repair_ops = vec![GreedyInsertion::new(dm, cust, 0).with_fleet(&fleet)];
+        let config = AlnsConfig::default().with_max_iterations(100).with_seed(42);
+
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+        assert!(result.best.unassigned().is_empty());
+        // 0→1→2→3 without the return leg
+        assert!((result.best_cost - 3.0).abs() < 1e-10);
+        assert!((result.best.total_distance() - 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_simultaneous_pickup_delivery() {
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let problem = RoutingAlnsProblem::new(customers.clone(), dm.clone(), 20);
+        let destroy_ops = vec![RandomRemoval];
+        let repair_ops = vec![GreedyInsertion::new(dm, customers.clone(), 20)];
+        let config = AlnsConfig::default().with_max_iterations(300).with_seed(42);
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).expect("valid");
+        assert!(result.best.unassigned().is_empty());
+        for route in result.best.routes() {
+            assert!(Load::peak_of_route(route, &customers).fits(&[20]));
+        }
+    }
 }
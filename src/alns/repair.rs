@@ -4,6 +4,33 @@
 //!
 //! - [`GreedyInsertion`] — Inserts each customer at the cheapest position
 //! - [`RegretInsertion`] — Inserts the customer with highest regret value first
+//! - [`RequestInsertion`] — Inserts pickup-and-delivery pairs together, greedily
+//!
+//! All operators accept a heterogeneous fleet via `with_fleet`: insertion
+//! respects the skills, capacity and shift end of each route's vehicle type
+//! and is costed against the route's depot, and new routes go to the
+//! cheapest type that is still available, starting at that type's depot.
+//! Insertion costs use the matrix of the route's vehicle type (see
+//! [`Vehicle::with_routing_profile`]) and include the change in its
+//! emission cost when the type prices emissions (see
+//! [`Vehicle::with_cost_per_emission`]).
+//!
+//! Charging stops of electric vehicles (see [`Vehicle::with_battery`]) are
+//! re-planned on every repair: they are taken out before insertion, an
+//! insertion is feasible if the route can be covered with charging stops,
+//! and the stops each route needs are put back afterwards (see
+//! [`insert_charging_stops`]).
+//!
+//! Optional customers (see [`Customer::with_optional`]) are only inserted,
+//! or given a new route, when that costs less than their prize; otherwise
+//! they stay unassigned.
+//!
+//! A customer that allows split delivery (see
+//! [`Customer::with_split_delivery`]) may be inserted into a route with only
+//! part of its demand: the route takes as much of the undelivered demand as
+//! its spare capacity holds (see [`RoutingSolution::set_quantity`]) and the
+//! customer is queued again for the rest, so a demand larger than any
+//! vehicle is spread over several routes.
 //!
 //! # Reference
 //!
@@ -15,48 +42,570 @@ use rand::Rng;
 use u_metaheur::alns::RepairOperator;
 
 use crate::distance::DistanceMatrix;
-use crate::models::Customer;
+use crate::evaluation::{insert_charging_stops, RouteEvaluator};
+use crate::models::{has_load_profile, Customer, Load, RequestRole, RouteEnds, Vehicle};
 
 use super::solution_repr::RoutingSolution;
 
+/// Which checks on a candidate route can fail for the customers of a
+/// problem, so that [`fits_at`] only builds the route when it has to.
+#[derive(Debug, Clone, Copy)]
+struct RouteChecks {
+    /// Some customer picks goods up or belongs to a request, so the load
+    /// varies along the route (see [`has_load_profile`]).
+    load_profile: bool,
+    /// Some customer has a time window.
+    time_windows: bool,
+}
+
+impl RouteChecks {
+    fn new(customers: &[Customer]) -> Self {
+        Self {
+            load_profile: has_load_profile(customers),
+            time_windows: customers.iter().any(|c| !c.time_windows().is_empty()),
+        }
+    }
+
+    /// Returns `true` if [`route_feasible`] can fail for `vehicle`: it has a
+    /// shift end, a driver break or a battery, or some customer has a time
+    /// window.
+    fn scheduled(&self, vehicle: &Vehicle) -> bool {
+        self.time_windows
+            || vehicle.shift_end().is_some()
+            || vehicle.driver_break().is_some()
+            || vehicle.is_electric()
+    }
+}
+
+/// Returns `true` if the vehicle provides the skills `customer_id` requires
+/// and inserting it at `pos` keeps the on-board load within capacity at
+/// every point of the route and lets the vehicle serve every customer in a
+/// time window and finish within its shift (recharging on the way, if it is
+/// electric).
+///
+/// The caller must have checked that the route can hold the customer's
+/// demand in total. Without a load profile that check is enough, and an
+/// unscheduled vehicle always finishes in time (see [`RouteChecks`]); only
+/// otherwise is the candidate route built, in O(n).
+fn fits_at(
+    route: &[usize],
+    pos: usize,
+    customer_id: usize,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+    checks: RouteChecks,
+) -> bool {
+    if !vehicle.can_serve(&customers[customer_id]) {
+        return false;
+    }
+    let scheduled = checks.scheduled(vehicle);
+    if !checks.load_profile && !scheduled {
+        return true;
+    }
+    let mut candidate = route.to_vec();
+    candidate.insert(pos, customer_id);
+    (!checks.load_profile || vehicle.can_carry(&Load::peak_of_route(&candidate, customers)))
+        && (!scheduled || route_feasible(&candidate, customers, distances, vehicle))
+}
+
+/// Quantity of `customer_id` that route `route_index` of `sol` takes on
+/// `vehicle`, or `None` if the route cannot hold it.
+///
+/// A customer is inserted with its whole demand, which must fit in the total
+/// load of the route. A splittable customer that the route does not visit yet
+/// is inserted with as much of its undelivered demand as fits between the
+/// route's peak load and the capacity, so the load stays within capacity
+/// wherever it goes.
+fn insertable_quantity(
+    sol: &RoutingSolution,
+    route_index: usize,
+    customer_id: usize,
+    customers: &[Customer],
+    vehicle: &Vehicle,
+    checks: RouteChecks,
+) -> Option<i32> {
+    let route = &sol.routes()[route_index];
+    let customer = &customers[customer_id];
+    let mut load = Load::of_route(route, customers);
+    load.sub(&[sol.shortfall(route_index, customers)]);
+    if !customer.allows_split_delivery() {
+        load.add(customer.demands());
+        return load.fits(vehicle.capacities()).then(|| customer.demand());
+    }
+    if route.contains(&customer_id) {
+        return None;
+    }
+    if checks.load_profile {
+        let quantities = sol.quantities_of(route_index, customers);
+        load = Load::peak_of_deliveries(route, &quantities, customers);
+    }
+    let quantity = residual(sol, customer_id, customers).min(vehicle.capacity() - load.get(0));
+    (quantity > 0).then_some(quantity)
+}
+
+/// Checks [`fits_at`] still has to make when inserting `customer_id` into
+/// route `route_index`, or `None` if the route cannot hold it (see
+/// [`insertable_quantity`]).
+///
+/// A partial quantity is chosen to fit under the route's peak load, so its
+/// load profile needs no check. Otherwise a partial visit already on the
+/// route counts with its whole demand in the profile, which can only reject
+/// an insertion.
+fn insertion_checks(
+    sol: &RoutingSolution,
+    route_index: usize,
+    customer_id: usize,
+    customers: &[Customer],
+    vehicle: &Vehicle,
+    checks: RouteChecks,
+) -> Option<RouteChecks> {
+    insertable_quantity(sol, route_index, customer_id, customers, vehicle, checks)?;
+    Some(RouteChecks {
+        load_profile: checks.load_profile && !customers[customer_id].allows_split_delivery(),
+        ..checks
+    })
+}
+
+/// Demand of `customer_id` that the routes of `sol` do not deliver.
+fn residual(sol: &RoutingSolution, customer_id: usize, customers: &[Customer]) -> i32 {
+    customers[customer_id].demand() - sol.delivered(customer_id, customers)
+}
+
+/// Inserts `customer_id` at `pos` of route `route_index` with the quantity
+/// the route takes (see [`insertable_quantity`]).
+///
+/// Returns `true` if part of a split demand is left to deliver.
+fn insert_visit(
+    sol: &mut RoutingSolution,
+    route_index: usize,
+    pos: usize,
+    customer_id: usize,
+    customers: &[Customer],
+    fleet: &[Vehicle],
+    checks: RouteChecks,
+) -> bool {
+    let vehicle = &fleet[sol.vehicle_of(route_index)];
+    let quantity = insertable_quantity(sol, route_index, customer_id, customers, vehicle, checks)
+        .unwrap_or(customers[customer_id].demand());
+    sol.routes_mut()[route_index].insert(pos, customer_id);
+    record_quantity(sol, route_index, customer_id, quantity, customers)
+}
+
+/// Opens a new route for `customer_id` on the cheapest vehicle type that can
+/// serve it (see [`open_route`]); a splittable customer only needs the
+/// vehicle to carry part of its undelivered demand, and the route delivers
+/// as much of it as the vehicle holds.
+///
+/// Returns `None` if no type qualifies, otherwise whether part of a split
+/// demand is left to deliver.
+fn open_visit(
+    sol: &mut RoutingSolution,
+    customer_id: usize,
+    fleet: &[Vehicle],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+) -> Option<bool> {
+    let customer = &customers[customer_id];
+    let split = customer.allows_split_delivery();
+    let residual = if split {
+        residual(sol, customer_id, customers)
+    } else {
+        customer.demand()
+    };
+    let load = Load::peak_of_route(&[customer_id], customers);
+    let feasible = |v: &Vehicle| {
+        v.can_serve(customer)
+            && if split {
+                v.capacity() > 0
+            } else {
+                v.can_carry(&load)
+            }
+            && route_feasible(&[customer_id], customers, distances, v)
+    };
+    let max_cost = max_insertion_cost(&[customer_id], customers);
+    if !open_route(
+        sol,
+        vec![customer_id],
+        fleet,
+        customers,
+        distances,
+        max_cost,
+        feasible,
+    ) {
+        return None;
+    }
+    let ri = sol.num_routes() - 1;
+    let quantity = if split {
+        residual.min(fleet[sol.vehicle_of(ri)].capacity())
+    } else {
+        residual
+    };
+    Some(record_quantity(sol, ri, customer_id, quantity, customers))
+}
+
+/// Records that route `route_index` delivers `quantity` to `customer_id` if
+/// it is splittable, and returns `true` if part of its demand is left.
+fn record_quantity(
+    sol: &mut RoutingSolution,
+    route_index: usize,
+    customer_id: usize,
+    quantity: i32,
+    customers: &[Customer],
+) -> bool {
+    if !customers[customer_id].allows_split_delivery() {
+        return false;
+    }
+    sol.set_quantity(route_index, customer_id, quantity);
+    residual(sol, customer_id, customers) > 0
+}
+
+/// Takes the unassigned customers out of `sol`, once each, leaving out
+/// splittable customers whose demand the routes already deliver.
+fn take_unassigned(sol: &mut RoutingSolution, customers: &[Customer]) -> Vec<usize> {
+    let mut unassigned = std::mem::take(sol.unassigned_mut());
+    let mut seen = vec![false; customers.len()];
+    unassigned.retain(|&cid| !std::mem::replace(&mut seen[cid], true));
+    unassigned.retain(|&cid| {
+        !customers[cid].allows_split_delivery() || residual(sol, cid, customers) > 0
+    });
+    unassigned
+}
+
+/// Returns `true` if `vehicle` can drive `route` within its shift, reaching
+/// every customer before its last time window closes; an electric vehicle
+/// may stop at charging stations on the way.
+fn route_feasible(
+    route: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> bool {
+    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+    // A missed hard window costs an infinite penalty
+    let in_time =
+        |r: &[usize]| evaluator.within_shift(r) && evaluator.time_window_penalty(r).is_finite();
+    if !vehicle.is_electric() {
+        return in_time(route);
+    }
+    insert_charging_stops(route, customers, distances, vehicle)
+        .is_some_and(|charged| in_time(&charged))
+}
+
+/// Returns `true` unless vehicle type `k` runs several trips per vehicle and
+/// has a limited number of vehicles, and its trips no longer pack onto them
+/// (see [`RouteEvaluator::pack_trips`]) once route `replaced` of `sol` becomes
+/// `trip`, or `trip` is added as a new route if `replaced` is `None`.
+///
+/// `trip` is only built when the type needs the check.
+fn trips_fit(
+    sol: &RoutingSolution,
+    k: usize,
+    replaced: Option<usize>,
+    trip: impl FnOnce() -> Vec<usize>,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> bool {
+    let Some(available) = vehicle.available().filter(|_| vehicle.is_multi_trip()) else {
+        return true;
+    };
+    let mut trips: Vec<Vec<usize>> = (0..sol.num_routes())
+        .filter(|&ri| sol.vehicle_of(ri) == k && Some(ri) != replaced)
+        .map(|ri| sol.routes()[ri].clone())
+        .filter(|route| !route.is_empty())
+        .collect();
+    trips.push(trip());
+    RouteEvaluator::new(customers, distances, vehicle)
+        .pack_trips(&trips)
+        .len()
+        <= available
+}
+
+/// Takes the charging stops out of the routes and the unassigned stops, so
+/// that insertion works on customers only.
+fn strip_charging(sol: &mut RoutingSolution, customers: &[Customer]) {
+    for route in sol.routes_mut() {
+        route.retain(|&cid| !customers[cid].is_charging_station());
+    }
+    sol.unassigned_mut()
+        .retain(|&cid| !customers[cid].is_charging_station());
+}
+
+/// Puts the charging stops each electric route needs back into it.
+///
+/// A route that cannot be covered is left as it is.
+fn plan_charging(
+    sol: &mut RoutingSolution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+) {
+    for ri in 0..sol.num_routes() {
+        let vehicle = &fleet[sol.vehicle_of(ri)];
+        if !vehicle.is_electric() {
+            continue;
+        }
+        let vehicle = vehicle.clone().with_depot(sol.depot_of(ri));
+        if let Some(charged) =
+            insert_charging_stops(&sol.routes()[ri], customers, distances, &vehicle)
+        {
+            sol.routes_mut()[ri] = charged;
+        }
+    }
+}
+
+/// Increase in the emission cost of a route between `ends` when it becomes
+/// `changed`; zero unless the vehicle prices emissions.
+fn emission_increase(
+    route: &[usize],
+    changed: &[usize],
+    ends: RouteEnds,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> f64 {
+    if vehicle.cost_per_emission() == 0.0 {
+        return 0.0;
+    }
+    let distances = distances.for_vehicle(vehicle);
+    vehicle.emission_cost_between(changed, ends, customers, distances)
+        - vehicle.emission_cost_between(route, ends, customers, distances)
+}
+
+/// Increase in the emission cost of a route when `customer_id` is inserted
+/// at `pos` (see [`emission_increase`]).
+fn insertion_emission_cost(
+    route: &[usize],
+    pos: usize,
+    customer_id: usize,
+    ends: RouteEnds,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> f64 {
+    if vehicle.cost_per_emission() == 0.0 {
+        return 0.0;
+    }
+    let changed = inserted(route, pos, customer_id);
+    emission_increase(route, &changed, ends, customers, distances, vehicle)
+}
+
+/// `route` with `customer_id` inserted at `pos`.
+fn inserted(route: &[usize], pos: usize, customer_id: usize) -> Vec<usize> {
+    let mut changed = route.to_vec();
+    changed.insert(pos, customer_id);
+    changed
+}
+
+/// Most that serving `stops` may add to the cost: the sum of their prizes if
+/// they are all optional (leaving them out forfeits no more), unbounded
+/// otherwise.
+fn max_insertion_cost(stops: &[usize], customers: &[Customer]) -> f64 {
+    if stops.iter().all(|&cid| customers[cid].is_optional()) {
+        stops.iter().map(|&cid| customers[cid].prize()).sum()
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Opens a new route for `stops` on the cheapest vehicle type that is still
+/// available and accepted by `feasible`, if its route cost is below `max_cost`.
+/// A multi-trip type is available while its trips still pack onto its
+/// vehicles (see [`trips_fit`]).
+///
+/// Returns `false` (leaving the solution unchanged) if no type qualifies.
+fn open_route(
+    sol: &mut RoutingSolution,
+    stops: Vec<usize>,
+    fleet: &[Vehicle],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    max_cost: f64,
+    feasible: impl Fn(&Vehicle) -> bool,
+) -> bool {
+    let mut used = vec![0; fleet.len()];
+    for ri in 0..sol.num_routes() {
+        if let Some(count) = used.get_mut(sol.vehicle_of(ri)) {
+            *count += 1;
+        }
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for (k, vehicle) in fleet.iter().enumerate() {
+        let available = if vehicle.is_multi_trip() {
+            trips_fit(
+                sol,
+                k,
+                None,
+                || stops.clone(),
+                customers,
+                distances,
+                vehicle,
+            )
+        } else {
+            vehicle.is_available(used[k])
+        };
+        if !available || !feasible(vehicle) {
+            continue;
+        }
+        let cost = vehicle.route_cost(
+            vehicle
+                .route_ends()
+                .distance(&stops, distances.for_vehicle(vehicle)),
+        ) + vehicle.emission_cost(&stops, customers, distances);
+        if cost < max_cost && best.is_none_or(|(_, c)| cost < c) {
+            best = Some((k, cost));
+        }
+    }
+
+    match best {
+        Some((k, _)) => {
+            sol.push_route(stops, k, fleet[k].route_ends());
+            true
+        }
+        None => false,
+    }
+}
+
 /// Finds the best insertion position for a customer across all routes.
 ///
+/// Each route is checked against the capacity of its vehicle type in `fleet`
+/// and costed against its own ends; the cost increase is scaled by that
+/// type's cost per distance. An optional customer is only inserted if the
+/// cost increase is below its prize.
+///
 /// Returns `(route_index, position, cost_increase)`.
 fn best_insertion(
-    routes: &[Vec<usize>],
+    sol: &RoutingSolution,
     customer_id: usize,
     distances: &DistanceMatrix,
     customers: &[Customer],
-    capacity: i32,
+    fleet: &[Vehicle],
+    checks: RouteChecks,
 ) -> Option<(usize, usize, f64)> {
-    let depot = 0;
     let mut best: Option<(usize, usize, f64)> = None;
 
-    for (ri, route) in routes.iter().enumerate() {
-        // Check capacity
-        let load: i32 = route.iter().map(|&c| customers[c].demand()).sum();
-        if load + customers[customer_id].demand() > capacity {
+    for (ri, route) in sol.routes().iter().enumerate() {
+        let k = sol.vehicle_of(ri);
+        let vehicle = &fleet[k];
+        let ends = sol.ends_of(ri);
+
+        let Some(checks) = insertion_checks(sol, ri, customer_id, customers, vehicle, checks)
+        else {
             continue;
-        }
+        };
 
+        let dist = |a, b| ends.leg(a, b, distances.for_vehicle(vehicle));
         for pos in 0..=route.len() {
-            let prev = if pos == 0 { depot } else { route[pos - 1] };
+            let prev = if pos == 0 {
+                ends.start()
+            } else {
+                route[pos - 1]
+            };
             let next = if pos == route.len() {
-                depot
+                ends.end_node()
             } else {
                 route[pos]
             };
 
-            let cost = distances.get(prev, customer_id) + distances.get(customer_id, next)
-                - distances.get(prev, next);
+            let cost = vehicle.cost_per_distance()
+                * (dist(prev, customer_id) + dist(customer_id, next) - dist(prev, next))
+                + insertion_emission_cost(
+                    route,
+                    pos,
+                    customer_id,
+                    ends,
+                    customers,
+                    distances,
+                    vehicle,
+                );
 
-            if best.as_ref().is_none_or(|b| cost < b.2) {
+            if best.as_ref().is_none_or(|b| cost < b.2)
+                && fits_at(
+                    route,
+                    pos,
+                    customer_id,
+                    customers,
+                    distances,
+                    vehicle,
+                    checks,
+                )
+                && trips_fit(
+                    sol,
+                    k,
+                    Some(ri),
+                    || inserted(route, pos, customer_id),
+                    customers,
+                    distances,
+                    vehicle,
+                )
+            {
                 best = Some((ri, pos, cost));
             }
         }
     }
 
-    best
+    best.filter(|b| b.2 < max_insertion_cost(&[customer_id], customers))
+}
+
+/// Inserts each of `unassigned` at its cheapest position, cheapest customer
+/// first, opening a new route when a customer fits nowhere, and returns the
+/// customers left unassigned.
+fn insert_greedily(
+    sol: &mut RoutingSolution,
+    mut unassigned: Vec<usize>,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+) -> Vec<usize> {
+    let mut stranded = Vec::new();
+    let checks = RouteChecks::new(customers);
+
+    while !unassigned.is_empty() {
+        // Find the unassigned customer with the cheapest insertion
+        let mut best_cust_idx = 0;
+        let mut best_route = 0;
+        let mut best_pos = 0;
+        let mut best_cost = f64::INFINITY;
+        let mut found = false;
+
+        for (ui, &cid) in unassigned.iter().enumerate() {
+            if let Some((ri, pos, cost)) =
+                best_insertion(sol, cid, distances, customers, fleet, checks)
+            {
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_cust_idx = ui;
+                    best_route = ri;
+                    best_pos = pos;
+                    found = true;
+                }
+            }
+        }
+
+        let (cid, left) = if !found {
+            // No feasible insertion — open a new route for the first unassigned
+            let cid = unassigned.remove(0);
+            match open_visit(sol, cid, fleet, customers, distances) {
+                Some(left) => (cid, left),
+                None => {
+                    stranded.push(cid);
+                    continue;
+                }
+            }
+        } else {
+            let cid = unassigned.remove(best_cust_idx);
+            let left = insert_visit(sol, best_route, best_pos, cid, customers, fleet, checks);
+            (cid, left)
+        };
+        // Queue the rest of a split demand again
+        if left {
+            unassigned.push(cid);
+        }
+    }
+
+    stranded
 }
 
 /// Greedy insertion: inserts each unassigned customer at its cheapest position.
@@ -88,7 +637,7 @@ fn best_insertion(
 pub struct GreedyInsertion {
     distances: DistanceMatrix,
     customers: Vec<Customer>,
-    capacity: i32,
+    fleet: Vec<Vehicle>,
 }
 
 impl GreedyInsertion {
@@ -97,9 +646,25 @@ impl GreedyInsertion {
         Self {
             distances,
             customers,
-            capacity,
+            fleet: vec![Vehicle::new(0, capacity)],
         }
     }
+
+    /// Sets a multi-dimensional vehicle capacity, replacing the one given to
+    /// [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.fleet = vec![Vehicle::new(0, 0).with_capacities(capacities)];
+        self
+    }
+
+    /// Sets a heterogeneous fleet of vehicle types, replacing the single
+    /// vehicle given to [`new`](Self::new).
+    ///
+    /// Route vehicles in the solution are indices into this fleet.
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.fleet = fleet.to_vec();
+        self
+    }
 }
 
 impl RepairOperator<RoutingSolution> for GreedyInsertion {
@@ -109,44 +674,17 @@ impl RepairOperator<RoutingSolution> for GreedyInsertion {
 
     fn repair<R: Rng>(&self, solution: &RoutingSolution, _rng: &mut R) -> RoutingSolution {
         let mut sol = solution.clone();
-        let mut unassigned = std::mem::take(sol.unassigned_mut());
-
-        while !unassigned.is_empty() {
-            // Find the unassigned customer with the cheapest insertion
-            let mut best_cust_idx = 0;
-            let mut best_route = 0;
-            let mut best_pos = 0;
-            let mut best_cost = f64::INFINITY;
-            let mut found = false;
-
-            for (ui, &cid) in unassigned.iter().enumerate() {
-                if let Some((ri, pos, cost)) = best_insertion(
-                    sol.routes(),
-                    cid,
-                    &self.distances,
-                    &self.customers,
-                    self.capacity,
-                ) {
-                    if cost < best_cost {
-                        best_cost = cost;
-                        best_cust_idx = ui;
-                        best_route = ri;
-                        best_pos = pos;
-                        found = true;
-                    }
-                }
-            }
-
-            if !found {
-                // No feasible insertion — create new route for first unassigned
-                let cid = unassigned.remove(0);
-                sol.routes_mut().push(vec![cid]);
-            } else {
-                let cid = unassigned.remove(best_cust_idx);
-                sol.routes_mut()[best_route].insert(best_pos, cid);
-            }
-        }
-
+        strip_charging(&mut sol, &self.customers);
+        let unassigned = take_unassigned(&mut sol, &self.customers);
+        let stranded = insert_greedily(
+            &mut sol,
+            unassigned,
+            &self.customers,
+            &self.distances,
+            &self.fleet,
+        );
+        *sol.unassigned_mut() = stranded;
+        plan_charging(&mut sol, &self.customers, &self.distances, &self.fleet);
         sol.recalculate_distance(&self.distances);
         sol
     }
@@ -162,7 +700,7 @@ impl RepairOperator<RoutingSolution> for GreedyInsertion {
 pub struct RegretInsertion {
     distances: DistanceMatrix,
     customers: Vec<Customer>,
-    capacity: i32,
+    fleet: Vec<Vehicle>,
     k: usize,
 }
 
@@ -172,11 +710,27 @@ impl RegretInsertion {
         Self {
             distances,
             customers,
-            capacity,
+            fleet: vec![Vehicle::new(0, capacity)],
             k: 2,
         }
     }
 
+    /// Sets a multi-dimensional vehicle capacity, replacing the one given to
+    /// [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.fleet = vec![Vehicle::new(0, 0).with_capacities(capacities)];
+        self
+    }
+
+    /// Sets a heterogeneous fleet of vehicle types, replacing the single
+    /// vehicle given to [`new`](Self::new).
+    ///
+    /// Route vehicles in the solution are indices into this fleet.
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.fleet = fleet.to_vec();
+        self
+    }
+
     /// Creates a regret insertion operator with custom k.
     pub fn with_k(mut self, k: usize) -> Self {
         self.k = k.max(2);
@@ -186,39 +740,80 @@ impl RegretInsertion {
     /// Computes insertion costs for a customer across all routes, sorted ascending.
     fn sorted_insertion_costs(
         &self,
-        routes: &[Vec<usize>],
+        sol: &RoutingSolution,
         customer_id: usize,
+        checks: RouteChecks,
     ) -> Vec<(usize, usize, f64)> {
-        let depot = 0;
         let mut costs = Vec::new();
 
-        for (ri, route) in routes.iter().enumerate() {
-            let load: i32 = route.iter().map(|&c| self.customers[c].demand()).sum();
-            if load + self.customers[customer_id].demand() > self.capacity {
+        for (ri, route) in sol.routes().iter().enumerate() {
+            let k = sol.vehicle_of(ri);
+            let vehicle = &self.fleet[k];
+            let ends = sol.ends_of(ri);
+            let dist = |a, b| ends.leg(a, b, self.distances.for_vehicle(vehicle));
+
+            let Some(checks) =
+                insertion_checks(sol, ri, customer_id, &self.customers, vehicle, checks)
+            else {
                 continue;
-            }
+            };
 
-            // Find best position in this route
+            // Find best load-feasible position in this route
             let mut best_pos = 0;
             let mut best_cost = f64::INFINITY;
             for pos in 0..=route.len() {
-                let prev = if pos == 0 { depot } else { route[pos - 1] };
+                let prev = if pos == 0 {
+                    ends.start()
+                } else {
+                    route[pos - 1]
+                };
                 let next = if pos == route.len() {
-                    depot
+                    ends.end_node()
                 } else {
                     route[pos]
                 };
-                let cost = self.distances.get(prev, customer_id)
-                    + self.distances.get(customer_id, next)
-                    - self.distances.get(prev, next);
-                if cost < best_cost {
+                let cost = vehicle.cost_per_distance()
+                    * (dist(prev, customer_id) + dist(customer_id, next) - dist(prev, next))
+                    + insertion_emission_cost(
+                        route,
+                        pos,
+                        customer_id,
+                        ends,
+                        &self.customers,
+                        &self.distances,
+                        vehicle,
+                    );
+                if cost < best_cost
+                    && fits_at(
+                        route,
+                        pos,
+                        customer_id,
+                        &self.customers,
+                        &self.distances,
+                        vehicle,
+                        checks,
+                    )
+                    && trips_fit(
+                        sol,
+                        k,
+                        Some(ri),
+                        || inserted(route, pos, customer_id),
+                        &self.customers,
+                        &self.distances,
+                        vehicle,
+                    )
+                {
                     best_cost = cost;
                     best_pos = pos;
                 }
             }
-            costs.push((ri, best_pos, best_cost));
+            if best_cost.is_finite() {
+                costs.push((ri, best_pos, best_cost));
+            }
         }
 
+        let max_cost = max_insertion_cost(&[customer_id], &self.customers);
+        costs.retain(|c| c.2 < max_cost);
         costs.sort_by(|a, b| {
             a.2.partial_cmp(&b.2)
                 .expect("insertion costs should not be NaN")
@@ -234,7 +829,10 @@ impl RepairOperator<RoutingSolution> for RegretInsertion {
 
     fn repair<R: Rng>(&self, solution: &RoutingSolution, _rng: &mut R) -> RoutingSolution {
         let mut sol = solution.clone();
-        let mut unassigned = std::mem::take(sol.unassigned_mut());
+        strip_charging(&mut sol, &self.customers);
+        let mut unassigned = take_unassigned(&mut sol, &self.customers);
+        let mut stranded = Vec::new();
+        let checks = RouteChecks::new(&self.customers);
 
         while !unassigned.is_empty() {
             let mut best_regret = f64::NEG_INFINITY;
@@ -244,7 +842,7 @@ impl RepairOperator<RoutingSolution> for RegretInsertion {
             let mut found = false;
 
             for (ui, &cid) in unassigned.iter().enumerate() {
-                let costs = self.sorted_insertion_costs(sol.routes(), cid);
+                let costs = self.sorted_insertion_costs(&sol, cid, checks);
 
                 if costs.is_empty() {
                     continue;
@@ -275,16 +873,302 @@ impl RepairOperator<RoutingSolution> for RegretInsertion {
                 }
             }
 
-            if !found {
-                // Create new route for the first unassigned
+            let (cid, left) = if !found {
+                // Open a new route for the first unassigned
                 let cid = unassigned.remove(0);
-                sol.routes_mut().push(vec![cid]);
+                match open_visit(&mut sol, cid, &self.fleet, &self.customers, &self.distances) {
+                    Some(left) => (cid, left),
+                    None => {
+                        stranded.push(cid);
+                        continue;
+                    }
+                }
             } else {
                 let cid = unassigned.remove(best_cust_idx);
-                sol.routes_mut()[best_route].insert(best_pos, cid);
+                let left = insert_visit(
+                    &mut sol,
+                    best_route,
+                    best_pos,
+                    cid,
+                    &self.customers,
+                    &self.fleet,
+                    checks,
+                );
+                (cid, left)
+            };
+            // Queue the rest of a split demand again
+            if left {
+                unassigned.push(cid);
             }
         }
 
+        *sol.unassigned_mut() = stranded;
+        plan_charging(&mut sol, &self.customers, &self.distances, &self.fleet);
+        sol.recalculate_distance(&self.distances);
+        sol
+    }
+}
+
+/// Greedy request insertion for pickup-and-delivery problems.
+///
+/// Each unassigned request — a pickup with its delivery, or a lone customer —
+/// is inserted at the cheapest pair of positions in one route, with the
+/// pickup before the delivery. A candidate is accepted only if the resulting
+/// route has no violations (peak load, time windows, precedence).
+/// Requests that fit nowhere open a new route on the cheapest available
+/// vehicle type; if none can serve them they stay unassigned.
+///
+/// If only one half of a pair is unassigned, the routed half is pulled out
+/// first so the pair is re-inserted together. Customers that allow split
+/// delivery are inserted afterwards as by [`GreedyInsertion`].
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, RequestRole};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::alns::{RoutingSolution, repair::RequestInsertion};
+/// use u_metaheur::alns::RepairOperator;
+///
+/// let cust = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0)
+///         .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+///     Customer::new(2, 2.0, 0.0, -10, 0.0)
+///         .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&cust);
+/// let sol = RoutingSolution::new(vec![], vec![2, 1], &cust, &dm);
+///
+/// let op = RequestInsertion::new(dm, cust, 10);
+/// let mut rng = u_numflow::random::create_rng(42);
+/// let repaired = op.repair(&sol, &mut rng);
+/// assert_eq!(repaired.routes(), &[vec![1, 2]]);
+/// ```
+pub struct RequestInsertion {
+    distances: DistanceMatrix,
+    customers: Vec<Customer>,
+    fleet: Vec<Vehicle>,
+}
+
+impl RequestInsertion {
+    /// Creates a new request insertion operator.
+    pub fn new(distances: DistanceMatrix, customers: Vec<Customer>, capacity: i32) -> Self {
+        Self {
+            distances,
+            customers,
+            fleet: vec![Vehicle::new(0, capacity)],
+        }
+    }
+
+    /// Sets a multi-dimensional vehicle capacity, replacing the one given to
+    /// [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.fleet = vec![Vehicle::new(0, 0).with_capacities(capacities)];
+        self
+    }
+
+    /// Sets a heterogeneous fleet of vehicle types, replacing the single
+    /// vehicle given to [`new`](Self::new).
+    ///
+    /// Route vehicles in the solution are indices into this fleet.
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.fleet = fleet.to_vec();
+        self
+    }
+
+    /// Splits a customer into its request: `(first, second)` in visiting order.
+    fn request_of(&self, customer_id: usize) -> (usize, Option<usize>) {
+        match self.customers[customer_id].request_role() {
+            Some(RequestRole::Pickup { delivery_id }) => (customer_id, Some(delivery_id)),
+            Some(RequestRole::Delivery { pickup_id }) => (pickup_id, Some(customer_id)),
+            None => (customer_id, None),
+        }
+    }
+
+    fn is_feasible(&self, route: &[usize], vehicle: &Vehicle) -> bool {
+        let evaluator = RouteEvaluator::new(&self.customers, &self.distances, vehicle);
+        if !vehicle.is_electric() {
+            return evaluator.build_route(route).1.is_empty();
+        }
+        insert_charging_stops(route, &self.customers, &self.distances, vehicle)
+            .is_some_and(|charged| evaluator.build_route(&charged).1.is_empty())
+    }
+
+    /// Increase in the emission cost of `route` when it becomes `changed`.
+    fn emission_increase(&self, route: &[usize], changed: &[usize], vehicle: &Vehicle) -> f64 {
+        emission_increase(
+            route,
+            changed,
+            vehicle.route_ends(),
+            &self.customers,
+            &self.distances,
+            vehicle,
+        )
+    }
+
+    /// Cheapest feasible insertion of a request into one route.
+    ///
+    /// Returns `(pickup_pos, delivery_pos, cost_increase)`, where
+    /// `delivery_pos` indexes the route after the pickup has been inserted.
+    fn best_request_insertion(
+        &self,
+        route: &[usize],
+        vehicle: &Vehicle,
+        first: usize,
+        second: Option<usize>,
+    ) -> Option<(usize, usize, f64)> {
+        let ends = vehicle.route_ends();
+        let distances = self.distances.for_vehicle(vehicle);
+        let dist = |a: usize, b: usize| vehicle.cost_per_distance() * ends.leg(a, b, distances);
+        let at = |r: &[usize], pos: usize| {
+            if pos < r.len() {
+                r[pos]
+            } else {
+                ends.end_node()
+            }
+        };
+        let before = |r: &[usize], pos: usize| if pos == 0 { ends.start() } else { r[pos - 1] };
+
+        let mut best: Option<(usize, usize, f64)> = None;
+        for i in 0..=route.len() {
+            let (prev, next) = (before(route, i), at(route, i));
+            let first_cost = dist(prev, first) + dist(first, next) - dist(prev, next);
+
+            let mut candidate = route.to_vec();
+            candidate.insert(i, first);
+
+            let Some(second) = second else {
+                let cost = first_cost + self.emission_increase(route, &candidate, vehicle);
+                if best.as_ref().is_none_or(|b| cost < b.2) && self.is_feasible(&candidate, vehicle)
+                {
+                    best = Some((i, i, cost));
+                }
+                continue;
+            };
+
+            for j in i + 1..=candidate.len() {
+                let (prev, next) = (before(&candidate, j), at(&candidate, j));
+                let mut cost =
+                    first_cost + dist(prev, second) + dist(second, next) - dist(prev, next);
+                let mut full = candidate.clone();
+                full.insert(j, second);
+                cost += self.emission_increase(route, &full, vehicle);
+                if best.as_ref().is_some_and(|b| cost >= b.2) {
+                    continue;
+                }
+                if self.is_feasible(&full, vehicle) {
+                    best = Some((i, j, cost));
+                }
+            }
+        }
+        best
+    }
+}
+
+impl RepairOperator<RoutingSolution> for RequestInsertion {
+    fn name(&self) -> &str {
+        "request_insertion"
+    }
+
+    fn repair<R: Rng>(&self, solution: &RoutingSolution, _rng: &mut R) -> RoutingSolution {
+        let mut sol = solution.clone();
+        strip_charging(&mut sol, &self.customers);
+        let (split, unassigned): (Vec<usize>, Vec<usize>) =
+            take_unassigned(&mut sol, &self.customers)
+                .into_iter()
+                .partition(|&cid| self.customers[cid].allows_split_delivery());
+        let mut stranded = Vec::new();
+
+        // Group unassigned stops into requests, pulling routed partners back out
+        let mut requests: Vec<(usize, Option<usize>)> = Vec::new();
+        for cid in unassigned {
+            let request = self.request_of(cid);
+            if requests.contains(&request) {
+                continue;
+            }
+            if let Some(second) = request.1 {
+                for route in sol.routes_mut() {
+                    route.retain(|&c| c != request.0 && c != second);
+                }
+            }
+            requests.push(request);
+        }
+        sol.remove_empty_routes();
+
+        while !requests.is_empty() {
+            let mut best: Option<(usize, usize, usize, usize, f64)> = None;
+            for (qi, &(first, second)) in requests.iter().enumerate() {
+                let stops: Vec<usize> = std::iter::once(first).chain(second).collect();
+                let max_cost = max_insertion_cost(&stops, &self.customers);
+                for (ri, route) in sol.routes().iter().enumerate() {
+                    let k = sol.vehicle_of(ri);
+                    let vehicle = self.fleet[k].clone().with_depot(sol.depot_of(ri));
+                    if let Some((i, j, cost)) =
+                        self.best_request_insertion(route, &vehicle, first, second)
+                    {
+                        let fits = || {
+                            trips_fit(
+                                &sol,
+                                k,
+                                Some(ri),
+                                || {
+                                    let mut changed = inserted(route, i, first);
+                                    if let Some(second) = second {
+                                        changed.insert(j, second);
+                                    }
+                                    changed
+                                },
+                                &self.customers,
+                                &self.distances,
+                                &self.fleet[k],
+                            )
+                        };
+                        if cost < max_cost && best.as_ref().is_none_or(|b| cost < b.4) && fits() {
+                            best = Some((qi, ri, i, j, cost));
+                        }
+                    }
+                }
+            }
+
+            match best {
+                Some((qi, ri, i, j, _)) => {
+                    let (first, second) = requests.remove(qi);
+                    let route = &mut sol.routes_mut()[ri];
+                    route.insert(i, first);
+                    if let Some(second) = second {
+                        route.insert(j, second);
+                    }
+                }
+                None => {
+                    // No feasible insertion — open a new route for the first request
+                    let (first, second) = requests.remove(0);
+                    let stops: Vec<usize> = std::iter::once(first).chain(second).collect();
+                    let max_cost = max_insertion_cost(&stops, &self.customers);
+                    if !open_route(
+                        &mut sol,
+                        stops.clone(),
+                        &self.fleet,
+                        &self.customers,
+                        &self.distances,
+                        max_cost,
+                        |v| self.is_feasible(&stops, v),
+                    ) {
+                        stranded.extend(stops);
+                    }
+                }
+            }
+        }
+
+        stranded.extend(insert_greedily(
+            &mut sol,
+            split,
+            &self.customers,
+            &self.distances,
+            &self.fleet,
+        ));
+        *sol.unassigned_mut() = stranded;
+        plan_charging(&mut sol, &self.customers, &self.distances, &self.fleet);
         sol.recalculate_distance(&self.distances);
         sol
     }
@@ -293,6 +1177,7 @@ impl RepairOperator<RoutingSolution> for RegretInsertion {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{EmissionModel, TimeWindow};
 
     fn setup() -> (Vec<Customer>, DistanceMatrix) {
         let customers = vec![
@@ -353,15 +1238,385 @@ mod tests {
         assert!(repaired.unassigned().is_empty());
     }
 
+    fn pdp_setup() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+            Customer::new(2, 2.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+            Customer::new(3, 3.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 4.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_request_insertion_respects_precedence_and_load() {
+        let (cust, dm) = pdp_setup();
+        let sol = RoutingSolution::new(vec![], vec![4, 2, 3, 1], &cust, &dm);
+        // Capacity 10: only one request on board at a time
+        let op = RequestInsertion::new(dm.clone(), cust.clone(), 10);
+        let mut rng = u_numflow::random::create_rng(42);
+        let repaired = op.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.routes(), &[vec![1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn test_request_insertion_pulls_routed_partner() {
+        let (cust, dm) = pdp_setup();
+        // Delivery 2 is routed, its pickup is unassigned
+        let sol = RoutingSolution::new(vec![vec![2, 3, 4]], vec![1], &cust, &dm);
+        let op = RequestInsertion::new(dm.clone(), cust.clone(), 10);
+        let mut rng = u_numflow::random::create_rng(42);
+        let repaired = op.repair(&sol, &mut rng);
+        let vehicle = Vehicle::new(0, 10);
+        let eval = RouteEvaluator::new(&cust, &dm, &vehicle);
+        for route in repaired.routes() {
+            assert!(eval.build_route(route).1.is_empty());
+        }
+        let total: usize = repaired.routes().iter().map(|r| r.len()).sum();
+        assert_eq!(total, 4);
+    }
+
     #[test]
     fn test_best_insertion_position() {
         let (cust, dm) = setup();
         // Route [1, 3], insert 2 — best position should be between 1 and 3
-        let routes = vec![vec![1, 3]];
-        let result = best_insertion(&routes, 2, &dm, &cust, 100);
+        let sol = RoutingSolution::new(vec![vec![1, 3]], vec![], &cust, &dm);
+        let result = best_insertion(
+            &sol,
+            2,
+            &dm,
+            &cust,
+            &[Vehicle::new(0, 100)],
+            RouteChecks::new(&cust),
+        );
         assert!(result.is_some());
         let (ri, pos, _cost) = result.expect("should find insertion");
         assert_eq!(ri, 0);
         assert_eq!(pos, 1); // between 1 and 3
     }
+
+    #[test]
+    fn test_best_insertion_respects_load_profile() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 0.5, 0.0, 0, 0.0).with_pickup(10),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        // Collecting 2 before dropping 1 would peak at 20: only after 1 fits
+        let sol = RoutingSolution::new(vec![vec![1]], vec![], &cust, &dm);
+        let checks = RouteChecks::new(&cust);
+        let (_, pos, _) =
+            best_insertion(&sol, 2, &dm, &cust, &[Vehicle::new(0, 10)], checks).expect("fits");
+        assert_eq!(pos, 1);
+
+        let op = RegretInsertion::new(dm.clone(), cust.clone(), 10);
+        let costs = op.sorted_insertion_costs(&sol, 2, checks);
+        assert_eq!(costs[0].1, 1);
+    }
+
+    #[test]
+    fn test_best_insertion_checks_every_dimension() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 6]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_demands(vec![10, 6]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_demands(vec![10, 1]),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let fleet = [Vehicle::new(0, 0).with_capacities(vec![100, 10])];
+        let sol = RoutingSolution::new(vec![vec![1], vec![3]], vec![], &cust, &dm);
+        // Route 0 would exceed the second dimension (12 > 10)
+        let (ri, _, _) = best_insertion(&sol, 2, &dm, &cust, &fleet, RouteChecks::new(&cust))
+            .expect("fits route 1");
+        assert_eq!(ri, 1);
+        let sol = RoutingSolution::new(vec![vec![1]], vec![], &cust, &dm);
+        assert!(best_insertion(&sol, 2, &dm, &cust, &fleet, RouteChecks::new(&cust)).is_none());
+    }
+
+    #[test]
+    fn test_best_insertion_uses_route_vehicle() {
+        let (cust, dm) = setup();
+        let fleet = [Vehicle::new(0, 10), Vehicle::new(1, 30)];
+        // Only the route served by the larger vehicle can take customer 3
+        let sol = RoutingSolution::new(vec![vec![1], vec![4]], vec![], &cust, &dm)
+            .with_vehicles(vec![0, 1]);
+        let (ri, _, _) = best_insertion(&sol, 3, &dm, &cust, &fleet, RouteChecks::new(&cust))
+            .expect("fits route 1");
+        assert_eq!(ri, 1);
+    }
+
+    #[test]
+    fn test_best_insertion_minimizes_emissions() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 0.0, 1.0, 90, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let sol = RoutingSolution::new(vec![vec![2]], vec![], &cust, &dm);
+        // Both positions add the same distance; only emissions tell them apart
+        let (_, pos, _) = best_insertion(
+            &sol,
+            1,
+            &dm,
+            &cust,
+            &[Vehicle::new(0, 100)],
+            RouteChecks::new(&cust),
+        )
+        .expect("fits");
+        assert_eq!(pos, 0);
+        let green = Vehicle::new(0, 100)
+            .with_emissions(EmissionModel::new(1.0, 2.0).expect("valid"))
+            .with_cost_per_distance(0.0)
+            .with_cost_per_emission(1.0);
+        let (_, pos, cost) =
+            best_insertion(&sol, 1, &dm, &cust, &[green], RouteChecks::new(&cust)).expect("fits");
+        assert_eq!(pos, 1);
+        assert!((cost - (0.1 + 1.1 * 2f64.sqrt())).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_greedy_respects_shift_end() {
+        let (cust, dm) = setup();
+        // Appending 3 to the first route would finish at 6, after its shift
+        let fleet = [
+            Vehicle::new(0, 100).with_shift(0.0, 5.0).with_available(1),
+            Vehicle::new(1, 100),
+        ];
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = RoutingSolution::new(vec![vec![1, 2]], vec![3], &cust, &dm);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.routes(), &[vec![1, 2], vec![3]]);
+        assert_eq!(repaired.vehicles(), &[0, 1]);
+    }
+
+    #[test]
+    fn test_greedy_respects_hard_time_window() {
+        let tw = TimeWindow::new(0.0, 5.0).expect("valid");
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 10.0, 0.0, 10, 0.0).with_time_window(tw),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        // Customer 2 is reached at 10 at the earliest, after its window
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 100);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = RoutingSolution::new(vec![], vec![1, 2], &cust, &dm);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.routes(), &[vec![1]]);
+        assert_eq!(repaired.unassigned(), &[2]);
+    }
+
+    #[test]
+    fn test_greedy_respects_skills() {
+        let (mut cust, dm) = setup();
+        cust[3] = cust[3].clone().with_skills(vec![2]);
+        let fleet = [
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_skills(vec![2]),
+        ];
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = RoutingSolution::new(vec![vec![1, 2]], vec![3], &cust, &dm);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.routes(), &[vec![1, 2], vec![3]]);
+        assert_eq!(repaired.vehicles(), &[0, 1]);
+
+        // No vehicle type provides the skill
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.unassigned(), &[3]);
+    }
+
+    #[test]
+    fn test_multi_trip_availability_counts_vehicles() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 40, 0.0),
+            Customer::new(2, 0.0, 10.0, 40, 0.0),
+            Customer::new(3, -10.0, 0.0, 40, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        // Trips take 20; one van fits two in a shift of 45 with a reload of 5
+        let van = Vehicle::new(0, 50)
+            .with_shift(0.0, 45.0)
+            .with_reload_time(5.0)
+            .with_available(1);
+        let sol = RoutingSolution::new(Vec::new(), vec![1, 2, 3], &cust, &dm);
+        let mut rng = u_numflow::random::create_rng(42);
+
+        let greedy = GreedyInsertion::new(dm.clone(), cust.clone(), 0)
+            .with_fleet(std::slice::from_ref(&van));
+        let repaired = greedy.repair(&sol, &mut rng);
+        assert_eq!(repaired.num_routes(), 2);
+        assert_eq!(repaired.unassigned().len(), 1);
+
+        let regret = RegretInsertion::new(dm, cust, 0).with_fleet(&[van]);
+        let repaired = regret.repair(&sol, &mut rng);
+        assert_eq!(repaired.num_routes(), 2);
+        assert_eq!(repaired.unassigned().len(), 1);
+    }
+
+    #[test]
+    fn test_split_delivery_spreads_over_routes() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+            Customer::new(2, 2.0, 0.0, 60, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let sol = RoutingSolution::new(vec![vec![2]], vec![1], &cust, &dm);
+        let mut rng = u_numflow::random::create_rng(42);
+
+        // 40 fit next to customer 2, then 100 and 10 on new routes
+        let greedy = GreedyInsertion::new(dm.clone(), cust.clone(), 100);
+        let repaired = greedy.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.num_routes(), 3);
+        assert_eq!(repaired.quantity_of(0, 1, &cust), Some(40));
+        assert_eq!(repaired.delivered(1, &cust), 150);
+        for ri in 0..repaired.num_routes() {
+            let load: i32 = repaired.quantities_of(ri, &cust).iter().sum();
+            assert!(load <= 100);
+        }
+
+        let regret = RegretInsertion::new(dm.clone(), cust.clone(), 100);
+        let repaired = regret.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.delivered(1, &cust), 150);
+
+        let request = RequestInsertion::new(dm, cust.clone(), 100);
+        let repaired = request.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.delivered(1, &cust), 150);
+    }
+
+    #[test]
+    fn test_split_delivery_reinserts_residual() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        // One route delivers 100; the other visit was removed, twice
+        let mut sol = RoutingSolution::new(vec![vec![1]], vec![1, 1], &cust, &dm);
+        sol.set_quantity(0, 1, 100);
+        let fleet = [Vehicle::new(0, 100).with_available(2)];
+        let greedy = GreedyInsertion::new(dm, cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let repaired = greedy.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.num_routes(), 2);
+        assert_eq!(repaired.quantity_of(1, 1, &cust), Some(50));
+
+        // Without a second vehicle the rest stays unassigned
+        let greedy = GreedyInsertion::new(DistanceMatrix::from_customers(&cust), cust.clone(), 0)
+            .with_fleet(&[Vehicle::new(0, 100).with_available(1)]);
+        let repaired = greedy.repair(&sol, &mut rng);
+        assert_eq!(repaired.unassigned(), &[1]);
+        assert_eq!(repaired.delivered(1, &cust), 100);
+    }
+
+    #[test]
+    fn test_insertion_skips_unprofitable_optional() {
+        let (mut cust, dm) = setup();
+        // Inserting 4 anywhere on 0→1→2→3→0 adds at least 2
+        cust[4] = cust[4].clone().with_prize(1.5).with_optional();
+        let sol = RoutingSolution::new(vec![vec![1, 2, 3]], vec![4], &cust, &dm);
+        let mut rng = u_numflow::random::create_rng(42);
+
+        let greedy = GreedyInsertion::new(dm.clone(), cust.clone(), 100);
+        assert_eq!(greedy.repair(&sol, &mut rng).unassigned(), &[4]);
+        let regret = RegretInsertion::new(dm.clone(), cust.clone(), 100);
+        assert_eq!(regret.repair(&sol, &mut rng).unassigned(), &[4]);
+
+        cust[4] = cust[4].clone().with_prize(2.5);
+        let greedy = GreedyInsertion::new(dm, cust, 100);
+        let repaired = greedy.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.routes()[0].len(), 4);
+    }
+
+    #[test]
+    fn test_greedy_plans_charging_stops() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 10, 0.0),
+            Customer::charging_station(2, 10.0, 1.0, 1.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let fleet = [Vehicle::new(0, 100).with_battery(12.0, 1.0)];
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        // A destroyed station is dropped; the route gets the stop it needs
+        let sol = RoutingSolution::new(vec![], vec![2, 1], &cust, &dm);
+        let repaired = op.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        assert_eq!(repaired.routes(), &[vec![2, 1]]);
+
+        let far = [Vehicle::new(0, 100).with_battery(9.0, 1.0)];
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&far);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.unassigned(), &[1]);
+    }
+
+    #[test]
+    fn test_greedy_multi_depot() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 9.0, 0.0, 10, 0.0),
+            Customer::depot_with_id(3, 10.0, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let fleet = [Vehicle::new(0, 100), Vehicle::new(1, 100).with_depot(3)];
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = RoutingSolution::new(vec![], vec![2], &cust, &dm);
+        let repaired = op.repair(&sol, &mut rng);
+        // The new route starts at depot 3, next to customer 2
+        assert_eq!(repaired.depot_of(0), 3);
+        assert_eq!(repaired.vehicles(), &[1]);
+        assert!((repaired.total_distance() - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_greedy_opens_cheapest_available_vehicle() {
+        let (cust, dm) = setup();
+        let fleet = [
+            Vehicle::new(0, 10).with_fixed_cost(5.0).with_available(1),
+            Vehicle::new(1, 10).with_fixed_cost(50.0),
+        ];
+        let sol = RoutingSolution::new(vec![], vec![1, 2], &cust, &dm);
+        let op = GreedyInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let repaired = op.repair(&sol, &mut rng);
+        assert!(repaired.unassigned().is_empty());
+        // The cheap type is used once, the second route falls back to type 1
+        let mut vehicles = repaired.vehicles().to_vec();
+        vehicles.sort_unstable();
+        assert_eq!(vehicles, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_regret_leaves_unservable_customer_unassigned() {
+        let (cust, dm) = setup();
+        let fleet = [Vehicle::new(0, 10).with_available(1)];
+        let sol = RoutingSolution::new(vec![], vec![1, 2], &cust, &dm);
+        let op = RegretInsertion::new(dm.clone(), cust.clone(), 0).with_fleet(&fleet);
+        let mut rng = u_numflow::random::create_rng(42);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.num_routes(), 1);
+        assert_eq!(repaired.unassigned().len(), 1);
+    }
 }
@@ -3,9 +3,21 @@
 //! Routes are stored as `Vec<Vec<usize>>` (customer ID sequences) with a
 //! separate list of unassigned customers. This allows efficient insertion
 //! and removal without rebuilding full `Route` objects.
+//!
+//! For heterogeneous fleets each route also records the index of its
+//! vehicle type in the fleet (0 by default), and for multi-depot problems
+//! the depot it starts and ends at (0 by default). Distances, insertion and
+//! removal costs are computed against each route's own [`RouteEnds`], so
+//! open routes do not pay for the return leg.
+//!
+//! A customer that [allows split delivery](Customer::allows_split_delivery)
+//! may be visited by several routes. Each visit delivers the customer's whole
+//! demand unless a smaller quantity is recorded for it
+//! ([`RoutingSolution::set_quantity`]); the customer stays unassigned while
+//! part of its demand is undelivered.
 
 use crate::distance::DistanceMatrix;
-use crate::models::Customer;
+use crate::models::{Customer, RouteEnds};
 
 /// Lightweight VRP solution for ALNS manipulation.
 ///
@@ -31,6 +43,10 @@ use crate::models::Customer;
 pub struct RoutingSolution {
     routes: Vec<Vec<usize>>,
     unassigned: Vec<usize>,
+    vehicles: Vec<usize>,
+    ends: Vec<RouteEnds>,
+    /// Partial visits per route: `(customer ID, quantity)`.
+    partial: Vec<Vec<(usize, i32)>>,
     total_distance: f64,
 }
 
@@ -42,14 +58,39 @@ impl RoutingSolution {
         _customers: &[Customer],
         distances: &DistanceMatrix,
     ) -> Self {
-        let total_distance = compute_total_distance(&routes, distances);
+        let ends = vec![RouteEnds::closed(0); routes.len()];
+        let total_distance = compute_total_distance(&routes, &ends, distances);
         Self {
+            vehicles: vec![0; routes.len()],
+            ends,
+            partial: vec![Vec::new(); routes.len()],
             routes,
             unassigned,
             total_distance,
         }
     }
 
+    /// Sets the fleet index of the vehicle type serving each route.
+    pub fn with_vehicles(mut self, vehicles: Vec<usize>) -> Self {
+        self.vehicles = vehicles;
+        self
+    }
+
+    /// Sets the depot each route starts and ends at, and recomputes the
+    /// total distance accordingly.
+    pub fn with_depots(self, depots: Vec<usize>, distances: &DistanceMatrix) -> Self {
+        let ends = depots.into_iter().map(RouteEnds::closed).collect();
+        self.with_route_ends(ends, distances)
+    }
+
+    /// Sets the start and end of each route (e.g. open routes), and
+    /// recomputes the total distance accordingly.
+    pub fn with_route_ends(mut self, ends: Vec<RouteEnds>, distances: &DistanceMatrix) -> Self {
+        self.ends = ends;
+        self.recalculate_distance(distances);
+        self
+    }
+
     /// Returns the routes as customer ID sequences.
     pub fn routes(&self) -> &[Vec<usize>] {
         &self.routes
@@ -60,6 +101,133 @@ impl RoutingSolution {
         &mut self.routes
     }
 
+    /// Returns the fleet index of the vehicle type serving each route.
+    pub fn vehicles(&self) -> &[usize] {
+        &self.vehicles
+    }
+
+    /// Fleet index of the vehicle type serving route `route_index`
+    /// (0 for routes added through [`routes_mut`](Self::routes_mut)).
+    pub fn vehicle_of(&self, route_index: usize) -> usize {
+        self.vehicles.get(route_index).copied().unwrap_or(0)
+    }
+
+    /// Returns the start and end of each route.
+    pub fn route_ends(&self) -> &[RouteEnds] {
+        &self.ends
+    }
+
+    /// Start and end of route `route_index` (closed at depot 0 for routes
+    /// added through [`routes_mut`](Self::routes_mut)).
+    pub fn ends_of(&self, route_index: usize) -> RouteEnds {
+        self.ends
+            .get(route_index)
+            .copied()
+            .unwrap_or(RouteEnds::closed(0))
+    }
+
+    /// Depot that route `route_index` starts at
+    /// (0 for routes added through [`routes_mut`](Self::routes_mut)).
+    pub fn depot_of(&self, route_index: usize) -> usize {
+        self.ends_of(route_index).start()
+    }
+
+    /// Appends a route served by the vehicle type at fleet index `vehicle`,
+    /// starting at `depot` (a depot ID or [`RouteEnds`]).
+    pub fn push_route(&mut self, route: Vec<usize>, vehicle: usize, depot: impl Into<RouteEnds>) {
+        self.vehicles.resize(self.routes.len(), 0);
+        self.ends.resize(self.routes.len(), RouteEnds::closed(0));
+        self.partial.resize(self.routes.len(), Vec::new());
+        self.routes.push(route);
+        self.vehicles.push(vehicle);
+        self.ends.push(depot.into());
+        self.partial.push(Vec::new());
+    }
+
+    /// Quantity route `route_index` delivers to `customer_id`, or `None` if
+    /// it does not visit the customer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::alns::RoutingSolution;
+    /// use u_routing::models::Customer;
+    /// use u_routing::distance::DistanceMatrix;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    ///
+    /// let mut sol = RoutingSolution::new(vec![vec![1], vec![1]], vec![], &customers, &dm);
+    /// assert_eq!(sol.quantity_of(0, 1, &customers), Some(150));
+    /// sol.set_quantity(0, 1, 100);
+    /// sol.set_quantity(1, 1, 50);
+    /// assert_eq!(sol.quantity_of(0, 1, &customers), Some(100));
+    /// assert_eq!(sol.delivered(1, &customers), 150);
+    /// ```
+    pub fn quantity_of(
+        &self,
+        route_index: usize,
+        customer_id: usize,
+        customers: &[Customer],
+    ) -> Option<i32> {
+        self.routes[route_index]
+            .contains(&customer_id)
+            .then(|| self.recorded_quantity(route_index, customer_id, customers))
+    }
+
+    /// Quantity delivered on each visit of route `route_index`, in visit order.
+    pub fn quantities_of(&self, route_index: usize, customers: &[Customer]) -> Vec<i32> {
+        self.routes[route_index]
+            .iter()
+            .map(|&cid| self.recorded_quantity(route_index, cid, customers))
+            .collect()
+    }
+
+    /// Records the quantity route `route_index` delivers to `customer_id`; the
+    /// customer's whole demand makes it a full visit again.
+    pub fn set_quantity(&mut self, route_index: usize, customer_id: usize, quantity: i32) {
+        self.partial.resize(self.routes.len(), Vec::new());
+        let partial = &mut self.partial[route_index];
+        partial.retain(|&(cid, _)| cid != customer_id);
+        partial.push((customer_id, quantity));
+    }
+
+    /// Total quantity delivered to `customer_id` over all routes.
+    pub fn delivered(&self, customer_id: usize, customers: &[Customer]) -> i32 {
+        (0..self.routes.len())
+            .filter_map(|ri| self.quantity_of(ri, customer_id, customers))
+            .sum()
+    }
+
+    /// Demand the visits of route `route_index` leave undelivered because
+    /// they are partial.
+    pub(crate) fn shortfall(&self, route_index: usize, customers: &[Customer]) -> i32 {
+        self.partial.get(route_index).map_or(0, |partial| {
+            partial
+                .iter()
+                .filter(|(cid, _)| self.routes[route_index].contains(cid))
+                .map(|&(cid, quantity)| customers[cid].demand() - quantity)
+                .sum()
+        })
+    }
+
+    /// Quantity recorded for `customer_id` on route `route_index`, or its
+    /// whole demand.
+    fn recorded_quantity(
+        &self,
+        route_index: usize,
+        customer_id: usize,
+        customers: &[Customer],
+    ) -> i32 {
+        self.partial
+            .get(route_index)
+            .and_then(|partial| partial.iter().find(|&&(cid, _)| cid == customer_id))
+            .map_or(customers[customer_id].demand(), |&(_, quantity)| quantity)
+    }
+
     /// Returns unassigned customers.
     pub fn unassigned(&self) -> &[usize] {
         &self.unassigned
@@ -82,30 +250,41 @@ impl RoutingSolution {
 
     /// Recalculates total distance from current routes.
     pub fn recalculate_distance(&mut self, distances: &DistanceMatrix) {
-        self.total_distance = compute_total_distance(&self.routes, distances);
+        self.total_distance = compute_total_distance(&self.routes, &self.ends, distances);
     }
 
     /// Removes empty routes.
     pub fn remove_empty_routes(&mut self) {
+        self.vehicles.resize(self.routes.len(), 0);
+        self.ends.resize(self.routes.len(), RouteEnds::closed(0));
+        self.partial.resize(self.routes.len(), Vec::new());
+        let mut keep = self.routes.iter().map(|r| !r.is_empty());
+        self.vehicles.retain(|_| keep.next().unwrap_or(false));
+        let mut keep = self.routes.iter().map(|r| !r.is_empty());
+        self.ends.retain(|_| keep.next().unwrap_or(false));
+        let mut keep = self.routes.iter().map(|r| !r.is_empty());
+        self.partial.retain(|_| keep.next().unwrap_or(false));
         self.routes.retain(|r| !r.is_empty());
     }
 }
 
-/// Computes total distance for all routes (depot=0).
-fn compute_total_distance(routes: &[Vec<usize>], distances: &DistanceMatrix) -> f64 {
-    let depot = 0;
-    let mut total = 0.0;
-    for route in routes {
-        if route.is_empty() {
-            continue;
-        }
-        total += distances.get(depot, route[0]);
-        for w in route.windows(2) {
-            total += distances.get(w[0], w[1]);
-        }
-        total += distances.get(route[route.len() - 1], depot);
-    }
-    total
+/// Computes total distance for all routes, each between its recorded ends
+/// (closed at depot 0 if none are recorded).
+fn compute_total_distance(
+    routes: &[Vec<usize>],
+    ends: &[RouteEnds],
+    distances: &DistanceMatrix,
+) -> f64 {
+    routes
+        .iter()
+        .enumerate()
+        .map(|(ri, route)| {
+            ends.get(ri)
+                .copied()
+                .unwrap_or(RouteEnds::closed(0))
+                .distance(route, distances)
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -156,4 +335,66 @@ mod tests {
         sol.remove_empty_routes();
         assert_eq!(sol.num_routes(), 2);
     }
+
+    #[test]
+    fn test_remove_empty_routes_keeps_vehicles() {
+        let (cust, dm) = setup();
+        let mut sol = RoutingSolution::new(vec![vec![1], vec![], vec![2]], vec![], &cust, &dm)
+            .with_vehicles(vec![2, 1, 0]);
+        sol.push_route(vec![3], 1, 0);
+        sol.remove_empty_routes();
+        assert_eq!(sol.vehicles(), &[2, 0, 1]);
+        assert_eq!(sol.vehicle_of(2), 1);
+        assert_eq!(sol.vehicle_of(5), 0);
+    }
+
+    #[test]
+    fn test_remove_empty_routes_keeps_quantities() {
+        let (mut cust, dm) = setup();
+        cust[1] = cust[1].clone().with_split_delivery();
+        let mut sol = RoutingSolution::new(vec![vec![], vec![1, 2]], vec![], &cust, &dm);
+        sol.set_quantity(1, 1, 4);
+        sol.remove_empty_routes();
+        assert_eq!(sol.quantities_of(0, &cust), vec![4, 10]);
+        assert_eq!(sol.shortfall(0, &cust), 6);
+
+        // A removed visit no longer counts
+        sol.routes_mut()[0].retain(|&c| c != 1);
+        assert_eq!(sol.quantity_of(0, 1, &cust), None);
+        assert_eq!(sol.delivered(1, &cust), 0);
+        assert_eq!(sol.shortfall(0, &cust), 0);
+    }
+
+    #[test]
+    fn test_solution_distance_per_depot() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::depot_with_id(2, 10.0, 0.0),
+            Customer::new(3, 9.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let mut sol = RoutingSolution::new(vec![vec![1], vec![3]], vec![], &customers, &dm)
+            .with_depots(vec![0, 2], &dm);
+        // (0→1→0) + (2→3→2) = 2 + 2
+        assert!((sol.total_distance() - 4.0).abs() < 1e-10);
+        sol.push_route(vec![], 0, 2);
+        sol.remove_empty_routes();
+        assert_eq!(
+            sol.route_ends(),
+            &[RouteEnds::closed(0), RouteEnds::closed(2)]
+        );
+        assert_eq!(sol.depot_of(1), 2);
+    }
+
+    #[test]
+    fn test_solution_distance_open_routes() {
+        let (cust, dm) = setup();
+        let sol = RoutingSolution::new(vec![vec![1], vec![2, 3]], vec![], &cust, &dm)
+            .with_route_ends(vec![RouteEnds::closed(0), RouteEnds::open(0)], &dm);
+        // (0→1→0) + (0→2→3) = 2 + 3
+        assert!((sol.total_distance() - 5.0).abs() < 1e-10);
+        assert!(sol.ends_of(1).is_open());
+        assert!(!sol.ends_of(7).is_open());
+    }
 }
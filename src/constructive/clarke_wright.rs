@@ -12,11 +12,28 @@
 //! ```
 //!
 //! Routes are merged in decreasing order of savings, subject to capacity
-//! constraints.
+//! constraints. With a heterogeneous fleet, a merge is allowed if some vehicle
+//! type can carry the combined load; afterwards each route is assigned the
+//! cheapest type that carries it and still has a vehicle available, serving
+//! the most constrained routes first. A route left without an available
+//! type (e.g. two routes merged for a single large truck) is split into the
+//! longest consecutive pieces that the remaining vehicles can serve.
+//!
+//! With vehicle types based at several depots, each customer is first
+//! assigned to its nearest depot (among those with a vehicle type providing
+//! its skills); savings are computed against that depot
+//! and routes are only merged within a depot.
+//!
+//! When vehicles have a shift end, a merge is only allowed if some vehicle
+//! type at the depot can also finish the merged route within its shift.
+//! Likewise, customers requiring skills are only merged into routes that
+//! some vehicle type at the depot provides all skills for.
 //!
 //! # Complexity
 //!
-//! O(n² log n) where n = number of customers (dominated by sorting savings).
+//! O(n² log n) where n = number of customers (dominated by sorting savings);
+//! shift checks add O(n) per merge attempt, and splitting a route of L
+//! customers back into pieces O(L²) vehicle checks.
 //!
 //! # Reference
 //!
@@ -25,7 +42,9 @@
 
 use crate::distance::DistanceMatrix;
 use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::models::{
+    cheapest_vehicle, has_load_profile, stop_ids, Customer, Load, Solution, Vehicle,
+};
 
 /// A savings value for merging two customers' routes.
 #[derive(Debug)]
@@ -40,11 +59,16 @@ struct Saving {
 /// Starts with one route per customer, then merges routes in order of
 /// decreasing savings while respecting vehicle capacity.
 ///
+/// A merged route that no available vehicle type can serve is split back
+/// into consecutive pieces that available types can serve; customers that
+/// no available type can serve are reported as unassigned.
+///
 /// # Arguments
 ///
 /// * `customers` — All locations (index 0 = depot)
 /// * `distances` — Distance matrix
-/// * `vehicle` — Vehicle type (homogeneous fleet, unlimited count)
+/// * `fleet` — Vehicle types, each with an optional availability limit
+///   and its own depot
 ///
 /// # Examples
 ///
@@ -60,28 +84,69 @@ struct Saving {
 ///     Customer::new(3, 3.0, 0.0, 10, 0.0),
 /// ];
 /// let dm = DistanceMatrix::from_customers(&customers);
-/// let vehicle = Vehicle::new(0, 30);
+/// let fleet = vec![Vehicle::new(0, 30)];
 ///
-/// let solution = clarke_wright_savings(&customers, &dm, &vehicle);
+/// let solution = clarke_wright_savings(&customers, &dm, &fleet);
 /// assert_eq!(solution.num_served(), 3);
 /// ```
 pub fn clarke_wright_savings(
     customers: &[Customer],
     distances: &DistanceMatrix,
-    vehicle: &Vehicle,
+    fleet: &[Vehicle],
 ) -> Solution {
     let n = customers.len();
-    if n <= 1 {
+    if n <= 1 || fleet.is_empty() {
         return Solution::new();
     }
 
-    let depot = vehicle.depot_id();
-    let num_customers = n - 1;
+    // Each customer is served from its nearest depot with a vehicle type
+    // providing its skills
+    let mut depots: Vec<usize> = fleet.iter().map(|v| v.depot_id()).collect();
+    depots.sort_unstable();
+    depots.dedup();
+    let stops: Vec<usize> = stop_ids(customers).collect();
+    let mut depot_of = vec![depots[0]; n];
+    for &i in &stops {
+        let serving: Vec<usize> = depots
+            .iter()
+            .copied()
+            .filter(|&d| {
+                fleet
+                    .iter()
+                    .any(|v| v.depot_id() == d && v.can_serve(&customers[i]))
+            })
+            .collect();
+        let candidates = if serving.is_empty() {
+            &depots
+        } else {
+            &serving
+        };
+        depot_of[i] = candidates
+            .iter()
+            .copied()
+            .min_by(|&a, &b| {
+                distances
+                    .get(a, i)
+                    .partial_cmp(&distances.get(b, i))
+                    .expect("distances should not be NaN")
+            })
+            .expect("fleet is not empty");
+    }
+    let num_customers = stops.len();
+    let has_shifts = fleet.iter().any(|v| v.shift_end().is_some());
+    let profiled = has_load_profile(customers);
+    let has_skills = stops
+        .iter()
+        .any(|&i| !customers[i].required_skills().is_empty());
 
-    // Compute savings
-    let mut savings = Vec::with_capacity(num_customers * (num_customers - 1) / 2);
-    for i in 1..n {
-        for j in (i + 1)..n {
+    // Compute savings between customers of the same depot
+    let mut savings = Vec::with_capacity(num_customers * num_customers.saturating_sub(1) / 2);
+    for (a, &i) in stops.iter().enumerate() {
+        for &j in &stops[a + 1..] {
+            let depot = depot_of[i];
+            if depot_of[j] != depot {
+                continue;
+            }
             let s = distances.get(depot, i) + distances.get(depot, j) - distances.get(i, j);
             if s > 0.0 {
                 savings.push(Saving { i, j, value: s });
@@ -100,12 +165,12 @@ pub fn clarke_wright_savings(
     // route_of[customer_id] = route index, route_head[r] = first customer,
     // route_tail[r] = last customer
     let mut route_of = vec![0usize; n];
-    let mut route_load = vec![0i32; n];
+    let mut route_load = vec![Load::new(); n];
     let mut route_members: Vec<Vec<usize>> = vec![Vec::new(); n];
 
-    for i in 1..n {
+    for &i in &stops {
         route_of[i] = i;
-        route_load[i] = customers[i].demand();
+        route_load[i].add(customers[i].demands());
         route_members[i].push(i);
     }
 
@@ -119,9 +184,14 @@ pub fn clarke_wright_savings(
             continue;
         }
 
-        // Check capacity
-        let combined_load = route_load[ri] + route_load[rj];
-        if combined_load > vehicle.capacity() {
+        // Check capacity against every vehicle type at this depot; the
+        // delivered load is a lower bound on the peak on-board load
+        let depot = depot_of[saving.i];
+        let combined_load = route_load[ri].plus(route_load[rj].amounts());
+        if !fleet
+            .iter()
+            .any(|v| v.depot_id() == depot && v.can_carry(&combined_load))
+        {
             continue;
         }
 
@@ -143,6 +213,34 @@ pub fn clarke_wright_savings(
             continue;
         };
 
+        // Check some vehicle type at this depot can still serve the merged
+        // route: every customer's skills, its peak on-board load, and within
+        // its shift
+        if has_shifts || has_skills || profiled {
+            let mut candidate = route_members[merge_into].clone();
+            if reverse_into {
+                candidate.reverse();
+            }
+            let mut tail = route_members[merge_from].clone();
+            if reverse_from {
+                tail.reverse();
+            }
+            candidate.append(&mut tail);
+            let load = if profiled {
+                Load::peak_of_route(&candidate, customers)
+            } else {
+                combined_load.clone()
+            };
+            if !fleet.iter().any(|v| {
+                v.depot_id() == depot
+                    && v.can_carry(&load)
+                    && v.can_serve_route(&candidate, customers)
+                    && RouteEvaluator::new(customers, distances, v).within_shift(&candidate)
+            }) {
+                continue;
+            }
+        }
+
         // Merge: append members of merge_from into merge_into
         let mut from_members = std::mem::take(&mut route_members[merge_from]);
         if reverse_from {
@@ -155,7 +253,7 @@ pub fn clarke_wright_savings(
 
         route_members[merge_into].append(&mut from_members);
         route_load[merge_into] = combined_load;
-        route_load[merge_from] = 0;
+        route_load[merge_from] = Load::new();
 
         // Update route assignments
         for &cid in &route_members[merge_into] {
@@ -163,24 +261,64 @@ pub fn clarke_wright_savings(
         }
     }
 
-    // Build solution from merged routes
-    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+    // Assign vehicle types, most constrained (fewest fitting types) first
+    let mut merged: Vec<usize> = (1..n).filter(|&r| !route_members[r].is_empty()).collect();
+    let fitting = |r: usize| {
+        let depot = depot_of[route_members[r][0]];
+        fleet
+            .iter()
+            .filter(|v| {
+                v.depot_id() == depot
+                    && v.can_carry(&Load::peak_of_route(&route_members[r], customers))
+                    && v.can_serve_route(&route_members[r], customers)
+            })
+            .count()
+    };
+    merged.sort_by_key(|&r| fitting(r));
+
+    let mut used = vec![0usize; fleet.len()];
     let mut solution = Solution::new();
     let mut visited = vec![false; n];
 
-    for members in &route_members {
-        if members.is_empty() {
-            continue;
-        }
-        let (route, _) = evaluator.build_route(members);
-        for &cid in members {
-            visited[cid] = true;
+    for r in merged {
+        let members = &route_members[r];
+        let depot = depot_of[members[0]];
+        // Cheapest available vehicle type serving a consecutive piece
+        let vehicle_for = |piece: &[usize], used: &[usize]| {
+            let serves = |v: &Vehicle| {
+                let in_shift = RouteEvaluator::new(customers, distances, v).within_shift(piece);
+                let fits = v.depot_id() == depot && in_shift && v.can_serve_route(piece, customers);
+                fits.then(|| v.route_ends().distance(piece, distances.for_vehicle(v)))
+            };
+            cheapest_vehicle(fleet, used, &Load::peak_of_route(piece, customers), serves)
+        };
+
+        // Serve the route whole or, once the types it was merged for are
+        // used up, in the longest consecutive pieces still servable
+        let mut start = 0;
+        while start < members.len() {
+            let served = (start + 1..=members.len())
+                .rev()
+                .find_map(|end| vehicle_for(&members[start..end], &used).map(|k| (end, k)));
+            let Some((end, k)) = served else {
+                // No available vehicle type serves this customer alone
+                start += 1;
+                continue;
+            };
+            used[k] += 1;
+            let piece = &members[start..end];
+            let (route, _) =
+                RouteEvaluator::new(customers, distances, &fleet[k]).build_route(piece);
+            for &cid in piece {
+                visited[cid] = true;
+            }
+            solution.add_route(route);
+            start = end;
         }
-        solution.add_route(route);
     }
 
-    for (i, &is_visited) in visited.iter().enumerate().skip(1) {
-        if !is_visited {
+    for &i in &stops {
+        if !visited[i] {
             solution.add_unassigned(i);
         }
     }
@@ -193,6 +331,7 @@ pub fn clarke_wright_savings(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::ViolationType;
 
     #[test]
     fn test_cw_line() {
@@ -203,8 +342,8 @@ mod tests {
             Customer::new(3, 3.0, 0.0, 10, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 30);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 30)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_served(), 3);
         assert_eq!(sol.num_unassigned(), 0);
         // With savings, should merge all into one route
@@ -222,19 +361,177 @@ mod tests {
             Customer::new(3, 3.0, 0.0, 15, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 25);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 25)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_served(), 3);
         // Can't fit all in one route (45 > 25), needs at least 2 routes
         assert!(sol.num_routes() >= 2);
     }
 
+    #[test]
+    fn test_cw_multi_dimensional_capacity() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 2]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_demands(vec![10, 2]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_demands(vec![10, 2]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 0).with_capacities(vec![100, 4])];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_served(), 3);
+        assert_eq!(sol.num_routes(), 2);
+    }
+
+    #[test]
+    fn test_cw_heterogeneous_fleet() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+            Customer::new(4, 0.0, 5.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(7, 10).with_available(1),
+            Vehicle::new(9, 30).with_available(1),
+        ];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_served(), 4);
+        let mut ids: Vec<usize> = sol.routes().iter().map(|r| r.vehicle_id()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![7, 9]);
+        for route in sol.routes() {
+            let vehicle = fleet.iter().find(|v| v.id() == route.vehicle_id());
+            assert!(route.total_load() <= vehicle.expect("in fleet").capacity());
+        }
+    }
+
+    #[test]
+    fn test_cw_availability_limit() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 0.0, 5.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // A single vehicle of capacity 20 cannot serve all three
+        let fleet = vec![Vehicle::new(0, 20).with_available(1)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_routes(), 1);
+        assert_eq!(sol.num_served(), 2);
+        assert_eq!(sol.num_unassigned(), 1);
+    }
+
+    #[test]
+    fn test_cw_splits_routes_without_available_type() {
+        // Merges are allowed for the single truck, but only one route can
+        // get it; the other is served by vans instead of being dropped
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain((1..=6).map(|i| {
+                let side = if i <= 3 { 1.0 } else { -1.0 };
+                Customer::new(i, side * (i as f64), 0.0, 10, 0.0)
+            }))
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 10), Vehicle::new(1, 30).with_available(1)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_unassigned(), 0);
+        assert_eq!(sol.num_served(), 6);
+        let mut ids: Vec<usize> = sol.routes().iter().map(|r| r.vehicle_id()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_cw_multi_depot() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::depot_with_id(1, 10.0, 0.0),
+            Customer::new(2, 1.0, 0.0, 10, 0.0),
+            Customer::new(3, 1.0, 1.0, 10, 0.0),
+            Customer::new(4, 9.0, 0.0, 10, 0.0),
+            Customer::new(5, 9.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 100), Vehicle::new(1, 100).with_depot(1)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.num_unassigned(), 0);
+        for route in sol.routes() {
+            let mut ids = route.customer_ids();
+            ids.sort_unstable();
+            // Each depot serves the customers next to it
+            match route.vehicle_id() {
+                0 => assert_eq!(ids, vec![2, 3]),
+                _ => assert_eq!(ids, vec![4, 5]),
+            }
+        }
+        // Both routes: 1 + 1 + √2
+        let expected = 2.0 * (2.0 + 2.0_f64.sqrt());
+        assert!((sol.total_distance() - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cw_shift_end() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 1.0),
+            Customer::new(2, 2.0, 0.0, 10, 1.0),
+            Customer::new(3, 3.0, 0.0, 10, 1.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Serving all three takes 9; [1, 2] takes 6 and [3] alone 7
+        let fleet = [Vehicle::new(0, 100).with_shift(0.0, 7.0)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.num_served(), 3);
+        let eval = RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        for route in sol.routes() {
+            assert!(eval.build_route(&route.customer_ids()).1.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_cw_skills() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_skills(vec![1]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Only the small refrigerated van can serve customer 3
+        let fleet = [
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 10).with_skills(vec![1]),
+        ];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.num_served(), 3);
+        for route in sol.routes() {
+            let expected = if route.customer_ids() == vec![3] {
+                1
+            } else {
+                0
+            };
+            assert_eq!(route.vehicle_id(), expected);
+        }
+
+        // Nobody can serve customer 3
+        let sol = clarke_wright_savings(&customers, &dm, &fleet[..1]);
+        assert_eq!(sol.num_served(), 2);
+        assert_eq!(sol.num_unassigned(), 1);
+    }
+
     #[test]
     fn test_cw_empty() {
         let customers = vec![Customer::depot(0.0, 0.0)];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 100);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 100)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_routes(), 0);
     }
 
@@ -245,8 +542,8 @@ mod tests {
             Customer::new(1, 5.0, 0.0, 10, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 100);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 100)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_routes(), 1);
         assert_eq!(sol.num_served(), 1);
         assert!((sol.total_distance() - 10.0).abs() < 1e-10);
@@ -262,8 +559,8 @@ mod tests {
             Customer::new(3, 5.0, 5.0, 10, 0.0), // northeast
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 100);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 100)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_served(), 3);
         // Clarke-Wright should produce a reasonably good tour
         assert!(sol.total_distance() < 25.0);
@@ -280,10 +577,32 @@ mod tests {
         ];
         let dm = DistanceMatrix::from_customers(&customers);
         // s(1,2) = 3 + 4 - 1 = 6 > 0, should merge
-        let vehicle = Vehicle::new(0, 100);
-        let sol = clarke_wright_savings(&customers, &dm, &vehicle);
+        let fleet = vec![Vehicle::new(0, 100)];
+        let sol = clarke_wright_savings(&customers, &dm, &fleet);
         assert_eq!(sol.num_routes(), 1);
         // 0→1→2→0 = 3 + 1 + 4 = 8, vs separate = 6 + 8 = 14
         assert!((sol.total_distance() - 8.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_cw_simultaneous_pickup_delivery() {
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 20);
+        let sol = clarke_wright_savings(&customers, &dm, std::slice::from_ref(&vehicle));
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::CapacityExceeded { .. })));
+        assert_eq!(sol.num_served(), 4);
+    }
 }
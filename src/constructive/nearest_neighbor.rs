@@ -3,6 +3,18 @@
 //! Builds routes greedily: starting from the depot, always visit the nearest
 //! unvisited customer. When capacity is exhausted, start a new route.
 //!
+//! With vehicles based at several depots, each new route is opened by the
+//! next unused vehicle of the depot closest to an unvisited customer, so
+//! customers tend to be served from their nearest depot.
+//!
+//! A customer is only added if the vehicle can still reach its end location
+//! before its shift ends.
+//!
+//! A customer allowing [split delivery](crate::models::Customer::with_split_delivery)
+//! that no longer fits is served with whatever capacity is left, and the
+//! rest of its demand is delivered by later routes. If the vehicles run out
+//! first, it is reported unassigned along with the customers never visited.
+//!
 //! # Complexity
 //!
 //! O(n²) where n = number of customers.
@@ -13,19 +25,22 @@
 //! quality is typically 15-25% above optimal, it provides a fast baseline.
 
 use crate::distance::DistanceMatrix;
-use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::evaluation::{RouteClock, RouteEvaluator};
+use crate::models::{stop_ids, Customer, LoadProfile, Solution, Vehicle};
 
 /// Constructs a VRP solution using the nearest-neighbor heuristic.
 ///
 /// Starting from the depot, greedily visits the nearest unvisited customer.
-/// Opens a new route when adding the next customer would violate capacity.
+/// Opens a new route when adding the next customer would raise the peak
+/// on-board load above capacity (see
+/// [`Load::peak_of_route`](crate::models::Load::peak_of_route)) or violate
+/// the vehicle's shift end.
 ///
 /// # Arguments
 ///
 /// * `customers` — All locations (index 0 = depot)
 /// * `distances` — Distance matrix
-/// * `vehicles` — Available vehicles (homogeneous fleet assumed)
+/// * `vehicles` — Available vehicles, used in order within each depot
 ///
 /// # Examples
 ///
@@ -57,52 +72,73 @@ pub fn nearest_neighbor(
         return Solution::new();
     }
 
-    let mut visited = vec![false; n];
-    visited[0] = true; // depot
+    // Depots and charging stations are never visited as stops
+    let mut visited = vec![true; n];
+    for i in stop_ids(customers) {
+        visited[i] = false;
+    }
+
+    // Demand still to be delivered to each customer
+    let mut remaining: Vec<i32> = customers.iter().map(Customer::demand).collect();
 
     let mut solution = Solution::new();
-    let mut vehicle_idx = 0;
+    let mut used = vec![false; vehicles.len()];
 
     loop {
-        if vehicle_idx >= vehicles.len() {
+        let Some(vehicle_idx) = next_vehicle(vehicles, &used, &visited, distances) else {
             // No more vehicles — mark remaining as unassigned
             for (i, &v) in visited.iter().enumerate() {
-                if !v && i > 0 {
+                if !v {
                     solution.add_unassigned(i);
                 }
             }
             break;
-        }
+        };
+        used[vehicle_idx] = true;
 
         let vehicle = &vehicles[vehicle_idx];
         let evaluator = RouteEvaluator::new(customers, distances, vehicle);
-        let depot = vehicle.depot_id();
-        let mut current = depot;
+        let matrix = distances.for_vehicle(vehicle);
+        let ends = vehicle.route_ends();
+        let mut current = ends.start();
+        let mut clock = RouteClock::new(vehicle);
         let mut route_customers = Vec::new();
-        let mut current_load: i32 = 0;
+        let mut route_quantities = Vec::new();
+        let mut current_load = LoadProfile::new();
 
         loop {
-            // Find nearest unvisited customer that fits capacity
-            let mut best: Option<(usize, f64)> = None;
+            // Find nearest unvisited customer that fits skills, capacity and shift
+            let mut best: Option<(usize, f64, i32, RouteClock)> = None;
             for i in 1..n {
-                if visited[i] {
+                if visited[i] || !vehicle.can_serve(&customers[i]) {
                     continue;
                 }
-                let demand = customers[i].demand();
-                if current_load + demand > vehicle.capacity() {
+                let Some(quantity) =
+                    deliverable(&customers[i], remaining[i], &current_load, vehicle)
+                else {
+                    continue;
+                };
+                let d = matrix.get(current, i);
+                let mut after = clock.clone();
+                after.drive(current, i, matrix);
+                after.serve(&customers[i]);
+                if !after.can_finish(i, ends, matrix) {
                     continue;
                 }
-                let d = distances.get(current, i);
-                if best.is_none_or(|(_, best_d)| d < best_d) {
-                    best = Some((i, d));
+                if best.as_ref().is_none_or(|&(_, best_d, _, _)| d < best_d) {
+                    best = Some((i, d, quantity, after));
                 }
             }
 
             match best {
-                Some((next, _)) => {
-                    visited[next] = true;
+                Some((next, _, quantity, after)) => {
+                    remaining[next] -= quantity;
+                    visited[next] = remaining[next] <= 0;
                     route_customers.push(next);
-                    current_load += customers[next].demand();
+                    route_quantities.push(quantity);
+                    current_load
+                        .push_delivery(&customers[next], &delivery(&customers[next], quantity));
+                    clock = after;
                     current = next;
                 }
                 None => break,
@@ -110,14 +146,13 @@ pub fn nearest_neighbor(
         }
 
         if !route_customers.is_empty() {
-            let (route, _) = evaluator.build_route(&route_customers);
+            let (route, _) =
+                evaluator.build_route_with_quantities(&route_customers, &route_quantities);
             solution.add_route(route);
         }
 
-        vehicle_idx += 1;
-
         // Check if all customers are visited
-        if visited.iter().skip(1).all(|&v| v) {
+        if visited.iter().all(|&v| v) {
             break;
         }
     }
@@ -129,9 +164,73 @@ pub fn nearest_neighbor(
     solution
 }
 
+/// Quantity of `customer` the vehicle can take when appended to a route
+/// with on-board `load`: all of the `remaining` demand if the peak load
+/// still fits, else, for a customer allowing split delivery, the spare
+/// capacity in the first dimension.
+fn deliverable(
+    customer: &Customer,
+    remaining: i32,
+    load: &LoadProfile,
+    vehicle: &Vehicle,
+) -> Option<i32> {
+    let fits = |quantity: i32| {
+        let mut after = load.clone();
+        after.push_delivery(customer, &delivery(customer, quantity));
+        vehicle.can_carry(after.peak())
+    };
+    if fits(remaining) {
+        return Some(remaining);
+    }
+    if !customer.allows_split_delivery() {
+        return None;
+    }
+    let spare = (vehicle.capacity() - load.peak().get(0)).min(remaining);
+    (spare > 0 && fits(spare)).then_some(spare)
+}
+
+/// Demands of `customer` with `quantity` in the first dimension.
+fn delivery(customer: &Customer, quantity: i32) -> Vec<i32> {
+    let mut demands = customer.demands().to_vec();
+    if let Some(first) = demands.first_mut() {
+        *first = quantity;
+    }
+    demands
+}
+
+/// Picks the vehicle for the next route: the first unused vehicle of the
+/// depot closest to an unvisited customer.
+///
+/// With a single depot this is simply the first unused vehicle.
+pub(crate) fn next_vehicle(
+    vehicles: &[Vehicle],
+    used: &[bool],
+    visited: &[bool],
+    distances: &DistanceMatrix,
+) -> Option<usize> {
+    let mut seen_depots = Vec::new();
+    let mut best: Option<(usize, f64)> = None;
+    for (k, vehicle) in vehicles.iter().enumerate() {
+        let depot = vehicle.depot_id();
+        if used[k] || seen_depots.contains(&depot) {
+            continue;
+        }
+        seen_depots.push(depot);
+        let nearest = (0..visited.len())
+            .filter(|&i| !visited[i])
+            .map(|i| distances.get(depot, i))
+            .fold(f64::INFINITY, f64::min);
+        if best.is_none_or(|(_, d)| nearest < d) {
+            best = Some((k, nearest));
+        }
+    }
+    best.map(|(k, _)| k)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::ViolationType;
 
     fn line_customers() -> (Vec<Customer>, DistanceMatrix, Vec<Vehicle>) {
         let customers = vec![
@@ -158,6 +257,22 @@ mod tests {
         assert!((sol.routes()[0].total_distance() - 6.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_nn_skills() {
+        let (mut customers, dm, _) = line_customers();
+        customers[2] = customers[2].clone().with_skills(vec![1]);
+        let vehicles = vec![
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_skills(vec![1]),
+        ];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_served(), 3);
+        // The plain vehicle skips customer 2, left to the equipped one
+        assert_eq!(sol.routes()[0].customer_ids(), vec![1, 3]);
+        assert_eq!(sol.routes()[1].customer_ids(), vec![2]);
+        assert_eq!(sol.routes()[1].vehicle_id(), 1);
+    }
+
     #[test]
     fn test_nn_split_routes() {
         let (customers, dm, _) = line_customers();
@@ -169,6 +284,26 @@ mod tests {
         assert_eq!(sol.num_unassigned(), 0);
     }
 
+    #[test]
+    fn test_nn_multi_dimensional_capacity() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 3]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_demands(vec![10, 3]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_demands(vec![10, 3]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Weight allows all three, volume only two per route
+        let vehicles = vec![
+            Vehicle::new(0, 0).with_capacities(vec![100, 6]),
+            Vehicle::new(1, 0).with_capacities(vec![100, 6]),
+        ];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.routes()[0].customer_ids(), vec![1, 2]);
+        assert_eq!(sol.num_unassigned(), 0);
+    }
+
     #[test]
     fn test_nn_insufficient_vehicles() {
         let (customers, dm, _) = line_customers();
@@ -178,6 +313,75 @@ mod tests {
         assert!(sol.num_unassigned() > 0);
     }
 
+    #[test]
+    fn test_nn_multi_depot() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 9.0, 0.0, 10, 0.0),
+            Customer::depot_with_id(2, 10.0, 0.0),
+            Customer::new(3, 1.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicles = vec![
+            Vehicle::new(0, 10),
+            Vehicle::new(1, 10),
+            Vehicle::new(2, 10).with_depot(2),
+        ];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_unassigned(), 0);
+        // Customer 1 is served from depot 2, customer 3 from depot 0
+        assert_eq!(sol.num_routes(), 2);
+        assert!((sol.total_distance() - 4.0).abs() < 1e-10);
+        for route in sol.routes() {
+            assert_ne!(route.customer_ids(), vec![2]);
+        }
+    }
+
+    #[test]
+    fn test_nn_shift_end() {
+        let (customers, dm, _) = line_customers();
+        // The first driver must be back by 5: 0→1→2→0 ends at 4, adding 3 at 6
+        let vehicles = vec![
+            Vehicle::new(0, 100).with_shift(0.0, 5.0),
+            Vehicle::new(1, 100).with_shift(0.0, 7.0),
+        ];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.routes()[0].customer_ids(), vec![1, 2]);
+        assert_eq!(sol.routes()[1].customer_ids(), vec![3]);
+        assert_eq!(sol.num_unassigned(), 0);
+    }
+
+    #[test]
+    fn test_nn_split_delivery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 60, 0.0),
+            Customer::new(2, 2.0, 0.0, 80, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicles = vec![Vehicle::new(0, 100), Vehicle::new(1, 100)];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_unassigned(), 0);
+        // The first route fills up with 40 of customer 2, the second brings the rest
+        assert_eq!(sol.routes()[0].quantities(), vec![60, 40]);
+        assert_eq!(sol.routes()[1].quantities(), vec![40]);
+        assert_eq!(sol.delivered(2), 80);
+    }
+
+    #[test]
+    fn test_nn_split_delivery_out_of_vehicles() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicles = vec![Vehicle::new(0, 100)];
+        let sol = nearest_neighbor(&customers, &dm, &vehicles);
+        assert_eq!(sol.delivered(1), 100);
+        assert_eq!(sol.num_unassigned(), 1);
+    }
+
     #[test]
     fn test_nn_empty() {
         let customers = vec![Customer::depot(0.0, 0.0)];
@@ -208,4 +412,26 @@ mod tests {
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
         assert!((sol.total_cost() - sol.total_distance()).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_nn_simultaneous_pickup_delivery() {
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 20);
+        let sol = nearest_neighbor(&customers, &dm, &vec![vehicle.clone(); 4]);
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::CapacityExceeded { .. })));
+        assert_eq!(sol.num_served(), 4);
+    }
 }
@@ -3,8 +3,11 @@
 //! # Algorithm
 //!
 //! Extension of the greedy nearest-neighbor that also checks time window
-//! feasibility before inserting a customer. Only customers whose time window
-//! can still be satisfied (arrival ≤ due) are considered as candidates.
+//! feasibility before inserting a customer. Only customers with a time window
+//! that can still be satisfied (arrival ≤ due) are considered as candidates,
+//! and only if the vehicle can still reach its end location before its shift
+//! ends. Driver breaks are scheduled along the way as the evaluator does,
+//! and travel times follow the matrix's speed profiles at each departure.
 //! Among feasible candidates, the nearest one is selected.
 //!
 //! # Complexity
@@ -17,8 +20,10 @@
 //! Problems with Time Window Constraints", *Operations Research* 35(2), 254-265.
 
 use crate::distance::DistanceMatrix;
-use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::evaluation::{RouteClock, RouteEvaluator};
+use crate::models::{stop_ids, Customer, LoadProfile, Solution, Vehicle};
+
+use super::nearest_neighbor::next_vehicle;
 
 /// Constructs a VRPTW solution using a time-window-aware nearest-neighbor.
 ///
@@ -64,78 +69,76 @@ pub fn nearest_neighbor_tw(
         return Solution::new();
     }
 
-    let mut visited = vec![false; n];
-    visited[0] = true; // depot
+    // Depots and charging stations are never visited as stops
+    let mut visited = vec![true; n];
+    for i in stop_ids(customers) {
+        visited[i] = false;
+    }
 
     let mut solution = Solution::new();
-    let mut vehicle_idx = 0;
+    let mut used = vec![false; vehicles.len()];
 
     loop {
-        if vehicle_idx >= vehicles.len() {
+        let Some(vehicle_idx) = next_vehicle(vehicles, &used, &visited, distances) else {
+            // No more vehicles — mark remaining as unassigned
             for (i, &v) in visited.iter().enumerate() {
-                if !v && i > 0 {
+                if !v {
                     solution.add_unassigned(i);
                 }
             }
             break;
-        }
+        };
+        used[vehicle_idx] = true;
 
         let vehicle = &vehicles[vehicle_idx];
         let evaluator = RouteEvaluator::new(customers, distances, vehicle);
-        let depot = vehicle.depot_id();
-        let mut current = depot;
-        let mut current_time = 0.0;
-        let mut current_load: i32 = 0;
+        let matrix = distances.for_vehicle(vehicle);
+        let ends = vehicle.route_ends();
+        let mut current = ends.start();
+        let mut clock = RouteClock::new(vehicle);
+        let mut current_load = LoadProfile::new();
         let mut route_customers = Vec::new();
 
         loop {
-            let mut best: Option<(usize, f64)> = None;
+            let mut best: Option<(usize, f64, RouteClock)> = None;
 
             for i in 1..n {
-                if visited[i] {
+                if visited[i] || !vehicle.can_serve(&customers[i]) {
                     continue;
                 }
 
                 // Check capacity
-                let demand = customers[i].demand();
-                if current_load + demand > vehicle.capacity() {
+                if !vehicle.can_carry(current_load.with(&customers[i]).peak()) {
                     continue;
                 }
 
-                // Check time window feasibility
-                let travel = distances.get(current, i);
-                let arrival = current_time + travel;
+                // Check time window feasibility, taking any break due on the way
+                let d = matrix.get(current, i);
+                let mut after = clock.clone();
+                after.drive(current, i, matrix);
+                if after.serve(&customers[i]).is_none() || after.missed_break() {
+                    continue; // every window closes before we can arrive
+                }
 
-                if let Some(tw) = customers[i].time_window() {
-                    if arrival > tw.due() {
-                        continue; // cannot arrive before window closes
-                    }
+                // Check the vehicle can still finish its shift
+                if !after.can_finish(i, ends, matrix) {
+                    continue;
                 }
 
                 // Among feasible customers, pick nearest
-                let d = distances.get(current, i);
-                if best.is_none_or(|(_, best_d)| d < best_d) {
-                    best = Some((i, d));
+                if best.as_ref().is_none_or(|&(_, best_d, _)| d < best_d) {
+                    best = Some((i, d, after));
                 }
             }
 
             match best {
-                Some((next, _)) => {
+                Some((next, _, after)) => {
                     visited[next] = true;
                     route_customers.push(next);
 
-                    let travel = distances.get(current, next);
-                    let arrival = current_time + travel;
-
-                    // Update time considering waiting
-                    let service_start = if let Some(tw) = customers[next].time_window() {
-                        arrival + tw.waiting_time(arrival)
-                    } else {
-                        arrival
-                    };
-
-                    current_time = service_start + customers[next].service_duration();
-                    current_load += customers[next].demand();
+                    // Advance past any break, waiting and service
+                    clock = after;
+                    current_load.push(&customers[next]);
                     current = next;
                 }
                 None => break,
@@ -147,9 +150,7 @@ pub fn nearest_neighbor_tw(
             solution.add_route(route);
         }
 
-        vehicle_idx += 1;
-
-        if visited.iter().skip(1).all(|&v| v) {
+        if visited.iter().all(|&v| v) {
             break;
         }
     }
@@ -162,7 +163,7 @@ pub fn nearest_neighbor_tw(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::TimeWindow;
+    use crate::models::{DriverBreak, TimeWindow};
 
     #[test]
     fn test_nn_tw_all_feasible() {
@@ -225,6 +226,29 @@ mod tests {
         assert_eq!(sol.num_unassigned(), 1);
     }
 
+    #[test]
+    fn test_nn_tw_rush_hour() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 2.0, 0.0, 10, 1.0)
+                .with_time_window(TimeWindow::new(0.0, 10.0).expect("valid")),
+            Customer::new(2, 3.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 5.0).expect("valid")),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicles = vec![Vehicle::new(0, 100), Vehicle::new(1, 100)];
+        // Free flow: 1 served at 2-3, 2 reached at 4
+        let sol = nearest_neighbor_tw(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_routes(), 1);
+
+        // Quarter speed from 2.5: leaving 1 at 3 reaches 2 at 7, too late
+        let rush =
+            crate::distance::SpeedProfile::new(vec![(0.0, 1.0), (2.5, 0.25)]).expect("valid");
+        let sol = nearest_neighbor_tw(&customers, &dm.with_speed_profile(rush), &vehicles);
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.num_unassigned(), 0);
+    }
+
     #[test]
     fn test_nn_tw_waiting_time() {
         // Customer has a late window — vehicle arrives early and waits
@@ -249,6 +273,28 @@ mod tests {
         assert!((visits[1].departure_time - 21.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_nn_tw_multiple_windows() {
+        // Customer 2's first window is missed, the second is reachable by waiting
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 5.0)
+                .with_time_window(TimeWindow::new(0.0, 20.0).expect("valid")),
+            Customer::new(2, 2.0, 0.0, 10, 5.0).with_time_windows(vec![
+                TimeWindow::new(0.0, 4.0).expect("valid"),
+                TimeWindow::new(12.0, 20.0).expect("valid"),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicles = vec![Vehicle::new(0, 100)];
+        let sol = nearest_neighbor_tw(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_routes(), 1);
+        assert_eq!(sol.routes()[0].customer_ids(), vec![1, 2]);
+        // Customer 2: arrive=7, wait until 12, service 5, depart=17
+        let visits = sol.routes()[0].visits();
+        assert!((visits[1].departure_time - 17.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_nn_tw_no_time_windows() {
         // Falls back to standard nearest-neighbor behavior
@@ -291,4 +337,29 @@ mod tests {
         assert_eq!(sol.num_served(), 1);
         assert_eq!(sol.routes()[0].customer_ids(), vec![1]);
     }
+
+    #[test]
+    fn test_nn_tw_driver_break_forces_split() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 4.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 8.0).expect("valid")),
+            Customer::new(2, 4.0, 3.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 8.0).expect("valid")),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let rest = DriverBreak::after_driving(6.0, 2.0);
+        let vehicles = vec![
+            Vehicle::new(0, 100).with_break(rest),
+            Vehicle::new(1, 100).with_break(rest),
+        ];
+        let sol = nearest_neighbor_tw(&customers, &dm, &vehicles);
+        assert_eq!(sol.num_served(), 2);
+        // 0->1->2 drives 4 + 3 > 6: the rest delays customer 2 to time 9
+        assert_eq!(sol.num_routes(), 2);
+        assert_eq!(sol.routes()[1].breaks().len(), 1);
+
+        let sol = nearest_neighbor_tw(&customers, &dm, &[Vehicle::new(0, 100)]);
+        assert_eq!(sol.num_routes(), 1);
+    }
 }
@@ -11,7 +11,12 @@
 //! where (i,j) is the edge being broken and u is the customer to insert.
 //! The customer with the best (lowest) insertion cost is chosen.
 //!
-//! When no more customers can be feasibly inserted, a new route is opened.
+//! When no more customers can be feasibly inserted (time windows including
+//! driver breaks, capacity, or the vehicle's shift end), a new route is opened.
+//!
+//! Every route is served by copies of a single vehicle and starts at its
+//! depot. For several vehicle types or depots, or a limited number of
+//! vehicles, use [`nearest_neighbor_tw`](super::nearest_neighbor_tw).
 //!
 //! # Complexity
 //!
@@ -23,21 +28,23 @@
 //! Problems with Time Window Constraints", *Operations Research* 35(2), 254-265.
 
 use crate::distance::DistanceMatrix;
-use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::evaluation::{RouteClock, RouteEvaluator};
+use crate::models::{has_load_profile, stop_ids, Customer, Load, Solution, Vehicle};
 
 /// Constructs a VRPTW solution using Solomon's I1 insertion heuristic.
 ///
 /// Builds routes one at a time. For each unrouted customer, evaluates
 /// all feasible insertion positions, selecting the customer-position pair
 /// with the lowest cost increase. Opens a new route when no feasible
-/// insertion remains.
+/// insertion remains. Customers requiring skills the vehicle lacks are
+/// reported as unassigned.
 ///
 /// # Arguments
 ///
 /// * `customers` — All locations (index 0 = depot, with time windows)
 /// * `distances` — Distance matrix
-/// * `vehicle` — Vehicle type (homogeneous fleet, unlimited count)
+/// * `vehicle` — Vehicle type (homogeneous fleet, unlimited count, all
+///   routes from its depot)
 ///
 /// # Examples
 ///
@@ -69,11 +76,18 @@ pub fn solomon_i1(
         return Solution::new();
     }
 
-    let depot = vehicle.depot_id();
+    let ends = vehicle.route_ends();
+    let depot = ends.start();
     let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+    let distances = distances.for_vehicle(vehicle);
+    let profiled = has_load_profile(customers);
 
-    let mut unrouted: Vec<usize> = (1..n).collect();
+    let (mut unrouted, incompatible): (Vec<usize>, Vec<usize>) =
+        stop_ids(customers).partition(|&i| vehicle.can_serve(&customers[i]));
     let mut solution = Solution::new();
+    for cid in incompatible {
+        solution.add_unassigned(cid);
+    }
 
     while !unrouted.is_empty() {
         // Start a new route: pick the farthest unrouted customer as seed
@@ -87,9 +101,10 @@ pub fn solomon_i1(
 
             for (ui, &cid) in unrouted.iter().enumerate() {
                 // Check capacity
-                let current_load: i32 =
-                    route_customers.iter().map(|&c| customers[c].demand()).sum();
-                if current_load + customers[cid].demand() > vehicle.capacity() {
+                // Quick reject: the route must at least hold the extra demand
+                let mut load = Load::of_route(&route_customers, customers);
+                load.add(customers[cid].demands());
+                if !vehicle.can_carry(&load) {
                     continue;
                 }
 
@@ -101,19 +116,22 @@ pub fn solomon_i1(
                         route_customers[pos - 1]
                     };
                     let next = if pos == route_customers.len() {
-                        depot
+                        ends.end_node()
                     } else {
                         route_customers[pos]
                     };
 
                     // Distance cost
-                    let cost = distances.get(prev, cid) + distances.get(cid, next)
-                        - distances.get(prev, next);
+                    let dist = |a, b| ends.leg(a, b, distances);
+                    let cost = dist(prev, cid) + dist(cid, next) - dist(prev, next);
 
-                    // Check time window feasibility
+                    // Check the peak on-board load, time windows and shift
                     let mut test_route = route_customers.clone();
                     test_route.insert(pos, cid);
-                    if !is_tw_feasible(&test_route, depot, customers, distances) {
+                    if (profiled
+                        && !vehicle.can_carry(&Load::peak_of_route(&test_route, customers)))
+                        || !is_tw_feasible(&test_route, vehicle, customers, distances)
+                    {
                         continue;
                     }
 
@@ -155,38 +173,34 @@ fn farthest_from_depot(unrouted: &[usize], depot: usize, distances: &DistanceMat
     best_idx
 }
 
-/// Checks whether a route is feasible with respect to time windows.
+/// Checks whether a route is feasible with respect to time windows, driver
+/// breaks and the vehicle's shift end.
 fn is_tw_feasible(
     route: &[usize],
-    depot: usize,
+    vehicle: &Vehicle,
     customers: &[Customer],
     distances: &DistanceMatrix,
 ) -> bool {
-    let mut time = 0.0;
-    let mut prev = depot;
+    let ends = vehicle.route_ends();
+    let mut clock = RouteClock::new(vehicle);
+    let mut prev = ends.start();
 
     for &cid in route {
-        let travel = distances.get(prev, cid);
-        let arrival = time + travel;
-
-        if let Some(tw) = customers[cid].time_window() {
-            if arrival > tw.due() {
-                return false;
-            }
-            time = arrival + tw.waiting_time(arrival) + customers[cid].service_duration();
-        } else {
-            time = arrival + customers[cid].service_duration();
+        clock.drive(prev, cid, distances);
+        if clock.serve(&customers[cid]).is_none() || clock.missed_break() {
+            return false;
         }
         prev = cid;
     }
 
-    true
+    clock.can_finish(prev, ends, distances)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::TimeWindow;
+    use crate::models::ViolationType;
+    use crate::models::{DriverBreak, TimeWindow};
 
     #[test]
     fn test_solomon_all_one_route() {
@@ -239,6 +253,26 @@ mod tests {
         assert_eq!(sol.num_routes(), 2);
     }
 
+    #[test]
+    fn test_solomon_multiple_windows_share_route() {
+        // Customer 2 can only be reached after its first window, but the
+        // second window keeps both customers on one route
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 0.0, 10, 5.0)
+                .with_time_window(TimeWindow::new(0.0, 6.0).expect("valid")),
+            Customer::new(2, -5.0, 0.0, 10, 5.0).with_time_windows(vec![
+                TimeWindow::new(0.0, 6.0).expect("valid"),
+                TimeWindow::new(15.0, 30.0).expect("valid"),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let sol = solomon_i1(&customers, &dm, &vehicle);
+        assert_eq!(sol.num_served(), 2);
+        assert_eq!(sol.num_routes(), 1);
+    }
+
     #[test]
     fn test_solomon_empty() {
         let customers = vec![Customer::depot(0.0, 0.0)];
@@ -289,4 +323,46 @@ mod tests {
         let idx = farthest_from_depot(&unrouted, 0, &dm);
         assert_eq!(unrouted[idx], 2);
     }
+
+    #[test]
+    fn test_solomon_driver_break_forces_split() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 4.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 8.0).expect("valid")),
+            Customer::new(2, 4.0, 3.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 8.0).expect("valid")),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::after_driving(6.0, 2.0));
+        let sol = solomon_i1(&customers, &dm, &vehicle);
+        assert_eq!(sol.num_served(), 2);
+        // Either order needs a rest between the two customers, arriving at 9 or 10
+        assert_eq!(sol.num_routes(), 2);
+
+        let sol = solomon_i1(&customers, &dm, &Vehicle::new(0, 100));
+        assert_eq!(sol.num_routes(), 1);
+    }
+
+    #[test]
+    fn test_solomon_simultaneous_pickup_delivery() {
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 20);
+        let sol = solomon_i1(&customers, &dm, &vehicle);
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::CapacityExceeded { .. })));
+        assert_eq!(sol.num_served(), 4);
+    }
 }
@@ -3,13 +3,21 @@
 //! # Algorithm
 //!
 //! Sorts customers by polar angle relative to the depot, then groups them
-//! into routes by sweeping through angles while respecting capacity. This
+//! into routes by sweeping through angles while respecting capacity and the
+//! vehicle's shift end. This
 //! exploits geographic clustering: nearby customers tend to have similar
 //! angles and are placed on the same route.
 //!
+//! Every route is served by copies of a single vehicle and starts at its
+//! depot, with angles measured around it. For several vehicle types or
+//! depots, or a limited number of vehicles, use
+//! [`nearest_neighbor`](super::nearest_neighbor) or
+//! [`clarke_wright_savings`](super::clarke_wright_savings).
+//!
 //! # Complexity
 //!
-//! O(n log n) where n = number of customers (dominated by angle sorting).
+//! O(n log n) where n = number of customers (dominated by angle sorting);
+//! O(n²) when the vehicle has a shift end.
 //!
 //! # Reference
 //!
@@ -18,19 +26,22 @@
 
 use crate::distance::DistanceMatrix;
 use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::models::{stop_ids, Customer, LoadProfile, Solution, Vehicle};
 
 /// Constructs a VRP solution using the sweep heuristic.
 ///
 /// Sorts customers by polar angle from the depot, then packs them into
-/// routes sequentially until capacity is reached. Each full route starts
-/// a new vehicle.
+/// routes sequentially until the peak on-board load would exceed capacity
+/// or the shift end is reached. Each full route starts
+/// a new vehicle. Customers requiring skills the vehicle lacks are reported
+/// as unassigned.
 ///
 /// # Arguments
 ///
 /// * `customers` — All locations (index 0 = depot)
 /// * `distances` — Distance matrix
-/// * `vehicle` — Vehicle type (homogeneous fleet, unlimited count)
+/// * `vehicle` — Vehicle type (homogeneous fleet, unlimited count, all
+///   routes from its depot)
 ///
 /// # Examples
 ///
@@ -63,7 +74,7 @@ pub fn sweep(customers: &[Customer], distances: &DistanceMatrix, vehicle: &Vehic
     let depot_y = depot.y();
 
     // Compute polar angle for each non-depot customer
-    let mut angle_order: Vec<(usize, f64)> = (1..n)
+    let mut angle_order: Vec<(usize, f64)> = stop_ids(customers)
         .map(|i| {
             let dx = customers[i].x() - depot_x;
             let dy = customers[i].y() - depot_y;
@@ -78,25 +89,32 @@ pub fn sweep(customers: &[Customer], distances: &DistanceMatrix, vehicle: &Vehic
     // Build routes by sweeping through sorted customers
     let evaluator = RouteEvaluator::new(customers, distances, vehicle);
     let mut solution = Solution::new();
-    let mut current_load: i32 = 0;
+    let mut current_load = LoadProfile::new();
     let mut current_route: Vec<usize> = Vec::new();
 
     for &(cid, _) in &angle_order {
-        let demand = customers[cid].demand();
-
-        if current_load + demand > vehicle.capacity() && !current_route.is_empty() {
+        if !vehicle.can_serve(&customers[cid]) {
+            // Requires a skill this vehicle lacks
+            solution.add_unassigned(cid);
+            continue;
+        }
+        let fits = vehicle.can_carry(current_load.with(&customers[cid]).peak())
+            && (vehicle.shift_end().is_none()
+                || evaluator.within_shift(&[current_route.as_slice(), &[cid]].concat()));
+        if !fits && !current_route.is_empty() {
             // Finalize current route
             let (route, _) = evaluator.build_route(&current_route);
             solution.add_route(route);
             current_route.clear();
-            current_load = 0;
+            current_load = LoadProfile::new();
         }
 
-        if demand <= vehicle.capacity() {
+        let alone = LoadProfile::new().with(&customers[cid]);
+        if vehicle.can_carry(alone.peak()) && evaluator.within_shift(&[cid]) {
             current_route.push(cid);
-            current_load += demand;
+            current_load.push(&customers[cid]);
         } else {
-            // Single customer exceeds capacity — mark unassigned
+            // Single customer exceeds capacity or shift — mark unassigned
             solution.add_unassigned(cid);
         }
     }
@@ -115,6 +133,8 @@ pub fn sweep(customers: &[Customer], distances: &DistanceMatrix, vehicle: &Vehic
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::evaluation::RouteEvaluator;
+    use crate::models::ViolationType;
 
     #[test]
     fn test_sweep_all_one_route() {
@@ -211,6 +231,24 @@ mod tests {
         assert_eq!(sol.num_unassigned(), 1);
     }
 
+    #[test]
+    fn test_sweep_skills() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_skills(vec![4]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let sol = sweep(&customers, &dm, &Vehicle::new(0, 100));
+        assert_eq!(sol.num_routes(), 1);
+        assert_eq!(sol.num_served(), 2);
+        assert_eq!(sol.num_unassigned(), 1);
+
+        let sol = sweep(&customers, &dm, &Vehicle::new(0, 100).with_skills(vec![4]));
+        assert_eq!(sol.num_served(), 3);
+    }
+
     #[test]
     fn test_sweep_angular_ordering() {
         // Customers at known angles: 0°, 90°, 180°, 270°
@@ -230,4 +268,26 @@ mod tests {
         let ids = sol.routes()[0].customer_ids();
         assert_eq!(ids, vec![4, 1, 2, 3]);
     }
+
+    #[test]
+    fn test_sweep_simultaneous_pickup_delivery() {
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 20);
+        let sol = sweep(&customers, &dm, &vehicle);
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::CapacityExceeded { .. })));
+        assert_eq!(sol.num_served(), 4);
+    }
 }
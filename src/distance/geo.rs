@@ -0,0 +1,206 @@
+//! Great-circle distances from latitude and longitude.
+//!
+//! # Algorithm
+//!
+//! The Haversine formula gives the great-circle distance on a sphere of
+//! the Earth's mean radius ([`EARTH_RADIUS_KM`]), accurate to about 0.5%.
+//! Vincenty's inverse formula gives the geodesic distance on the WGS-84
+//! ellipsoid to within a millimetre, iterating on the longitude difference
+//! on the auxiliary sphere until it changes by less than 1e-12 rad; it does
+//! not converge for nearly antipodal points.
+//!
+//! # Complexity
+//!
+//! O(1) per pair of points (Vincenty: at most 200 iterations).
+//!
+//! # Reference
+//!
+//! Sinnott, R. W. (1984). "Virtues of the Haversine", *Sky and Telescope*
+//! 68(2), 159.
+//!
+//! Vincenty, T. (1975). "Direct and inverse solutions of geodesics on the
+//! ellipsoid with application of nested equations", *Survey Review* 23(176),
+//! 88-93.
+
+/// Mean radius of the Earth in kilometres (IUGG).
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// WGS-84 semi-major axis in kilometres.
+const WGS84_A: f64 = 6378.137;
+/// WGS-84 flattening.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// Maximum number of Vincenty iterations.
+const MAX_ITERATIONS: usize = 200;
+
+/// Formula for the distance between two points given by latitude and
+/// longitude.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::distance::GeoFormula;
+///
+/// // Seoul City Hall to Busan Station
+/// let seoul = (37.5663, 126.9779);
+/// let busan = (35.1152, 129.0422);
+/// let sphere = GeoFormula::Haversine.distance_km(seoul, busan);
+/// let ellipsoid = GeoFormula::Vincenty.distance_km(seoul, busan);
+/// assert!((sphere - 329.3).abs() < 0.1);
+/// assert!((sphere - ellipsoid).abs() < 0.005 * ellipsoid);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GeoFormula {
+    /// Great circle on a sphere ([`haversine_km`]).
+    #[default]
+    Haversine,
+    /// Geodesic on the WGS-84 ellipsoid ([`vincenty_km`]), falling back to
+    /// Haversine where it does not converge.
+    Vincenty,
+}
+
+impl GeoFormula {
+    /// Distance in kilometres between two `(latitude, longitude)` points
+    /// in degrees.
+    pub fn distance_km(&self, from: (f64, f64), to: (f64, f64)) -> f64 {
+        let haversine = || haversine_km(from.0, from.1, to.0, to.1);
+        match self {
+            Self::Haversine => haversine(),
+            Self::Vincenty => vincenty_km(from.0, from.1, to.0, to.1).unwrap_or_else(haversine),
+        }
+    }
+}
+
+/// Great-circle distance in kilometres between two points given by
+/// latitude and longitude in degrees.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::distance::haversine_km;
+///
+/// // A quarter of the equator
+/// let d = haversine_km(0.0, 0.0, 0.0, 90.0);
+/// assert!((d - 10_007.5).abs() < 0.1);
+/// ```
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let h = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+/// Geodesic distance in kilometres on the WGS-84 ellipsoid between two
+/// points given by latitude and longitude in degrees.
+///
+/// Returns `None` if the iteration does not converge (nearly antipodal
+/// points).
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::distance::vincenty_km;
+///
+/// // Flinders Peak to Buninyong (Vincenty 1975 test line)
+/// let d = vincenty_km(-37.951_033_42, 144.424_867_89, -37.652_821_14, 143.926_495_54).unwrap();
+/// assert!((d - 54.972_271).abs() < 1e-6);
+/// ```
+pub fn vincenty_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> Option<f64> {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = (lon2 - lon1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // Coincident points
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Zero on the equator
+        let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (WGS84_A * WGS84_A - b * b) / (b * b);
+            let big_a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - big_b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(b * big_a * (sigma - delta_sigma));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haversine() {
+        assert_eq!(haversine_km(10.0, 20.0, 10.0, 20.0), 0.0);
+        // One degree of latitude
+        let d = haversine_km(0.0, 0.0, 1.0, 0.0);
+        assert!((d - EARTH_RADIUS_KM.to_radians()).abs() < 1e-9);
+        // Symmetric, and across the antimeridian
+        let a = haversine_km(51.5007, -0.1246, 40.6892, -74.0445);
+        let b = haversine_km(40.6892, -74.0445, 51.5007, -0.1246);
+        assert!((a - b).abs() < 1e-9);
+        assert!((a - 5574.8).abs() < 1.0);
+        let d = haversine_km(0.0, 179.5, 0.0, -179.5);
+        assert!((d - EARTH_RADIUS_KM.to_radians()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_vincenty() {
+        assert_eq!(vincenty_km(10.0, 20.0, 10.0, 20.0), Some(0.0));
+        // A quarter of the equator on the ellipsoid
+        let d = vincenty_km(0.0, 0.0, 0.0, 90.0).expect("converges");
+        assert!((d - WGS84_A * std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+        // Pole to pole along a meridian: twice the meridian quadrant
+        let d = vincenty_km(-90.0, 0.0, 90.0, 0.0).expect("converges");
+        assert!((d - 2.0 * 10_001.965_729).abs() < 1e-3);
+        // Nearly antipodal points
+        assert_eq!(vincenty_km(0.0, 0.0, 0.5, 179.7), None);
+    }
+
+    #[test]
+    fn test_vincenty_falls_back_to_haversine() {
+        let (from, to) = ((0.0, 0.0), (0.5, 179.7));
+        assert_eq!(
+            GeoFormula::Vincenty.distance_km(from, to),
+            GeoFormula::Haversine.distance_km(from, to)
+        );
+    }
+}
@@ -1,11 +1,26 @@
 //! Dense distance matrix.
 
-use crate::models::Customer;
+use crate::models::{Customer, Vehicle};
+
+use super::{GeoFormula, Metric, SpeedProfile};
 
 /// A dense n×n distance matrix stored in row-major order.
 ///
-/// Supports both Euclidean distance computation from customer coordinates
-/// and explicit distance specification.
+/// Supports distance computation from customer coordinates under a
+/// [`Metric`] (Euclidean by default) or from latitude and longitude, and
+/// explicit distance specification.
+///
+/// Costs are computed from distances ([`get`](Self::get)) and schedules
+/// from travel times ([`travel_time`](Self::travel_time)). Distances double
+/// as travel times unless a separate
+/// [travel-time matrix](Self::with_travel_times) is given, e.g. meters and
+/// minutes from road data. With [speed profiles](Self::with_speed_profiles),
+/// travel times also depend on the departure time (e.g. slower during rush
+/// hours); each arc follows the profile of its [class](Self::set_arc_class).
+///
+/// Vehicles using different roads (bikes, vans, trucks) get their own
+/// matrices through [routing profiles](Self::with_routing_profile);
+/// [`for_vehicle`](Self::for_vehicle) picks the matrix of a vehicle.
 ///
 /// # Examples
 ///
@@ -26,6 +41,15 @@ use crate::models::Customer;
 pub struct DistanceMatrix {
     data: Vec<f64>,
     size: usize,
+    /// Free-flow travel times in row-major order; empty when equal to the
+    /// distances.
+    times: Vec<f64>,
+    profiles: Vec<SpeedProfile>,
+    /// Profile index of each arc in row-major order; empty when all arcs
+    /// use the first profile.
+    arc_classes: Vec<usize>,
+    /// Matrices of each routing profile, by profile ID.
+    routing_profiles: Vec<(usize, DistanceMatrix)>,
 }
 
 impl DistanceMatrix {
@@ -34,16 +58,46 @@ impl DistanceMatrix {
         Self {
             data: vec![0.0; size * size],
             size,
+            times: Vec::new(),
+            profiles: Vec::new(),
+            arc_classes: Vec::new(),
+            routing_profiles: Vec::new(),
         }
     }
 
     /// Computes a Euclidean distance matrix from customer coordinates.
     pub fn from_customers(customers: &[Customer]) -> Self {
+        Self::from_customers_with(customers, Metric::Euclidean)
+    }
+
+    /// Computes a distance matrix from customer coordinates under `metric`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    /// use u_routing::distance::{DistanceMatrix, Metric};
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 3.0, 4.5, 10, 0.0),
+    /// ];
+    /// // Warehouse aisles
+    /// let dm = DistanceMatrix::from_customers_with(&customers, Metric::Manhattan);
+    /// assert!((dm.get(0, 1) - 7.5).abs() < 1e-10);
+    /// // CVRPLIB benchmark rounding
+    /// let dm = DistanceMatrix::from_customers_with(&customers, Metric::RoundedEuclidean);
+    /// assert_eq!(dm.get(1, 0), 5.0);
+    /// ```
+    pub fn from_customers_with(customers: &[Customer], metric: Metric) -> Self {
         let n = customers.len();
         let mut dm = Self::new(n);
         for i in 0..n {
             for j in (i + 1)..n {
-                let d = customers[i].distance_to(&customers[j]);
+                let d = metric.distance(
+                    (customers[i].x(), customers[i].y()),
+                    (customers[j].x(), customers[j].y()),
+                );
                 dm.set(i, j, d);
                 dm.set(j, i, d);
             }
@@ -51,6 +105,74 @@ impl DistanceMatrix {
         dm
     }
 
+    /// Computes a matrix of distances in kilometres between customers
+    /// located by [latitude and longitude](Customer::with_lat_lon).
+    ///
+    /// Road distances can be estimated with a
+    /// [circuity factor](Self::with_circuity_factor) and travel times with
+    /// an [average speed](Self::with_average_speed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    /// use u_routing::distance::{DistanceMatrix, GeoFormula};
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0).with_lat_lon(37.5663, 126.9779),
+    ///     Customer::new(1, 0.0, 0.0, 10, 5.0).with_lat_lon(37.5512, 126.9882),
+    /// ];
+    /// // Roads 30% longer than the straight line, driven at 30 km/h (0.5 km/min)
+    /// let dm = DistanceMatrix::from_lat_lon(&customers, GeoFormula::Haversine)
+    ///     .with_circuity_factor(1.3)
+    ///     .and_then(|dm| dm.with_average_speed(0.5))
+    ///     .expect("valid");
+    /// assert!((dm.get(0, 1) - 2.48).abs() < 0.01);
+    /// assert!((dm.travel_time(0, 1, 0.0) - 4.96).abs() < 0.01);
+    /// ```
+    pub fn from_lat_lon(customers: &[Customer], formula: GeoFormula) -> Self {
+        let n = customers.len();
+        let mut dm = Self::new(n);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let d = formula.distance_km(
+                    (customers[i].latitude(), customers[i].longitude()),
+                    (customers[j].latitude(), customers[j].longitude()),
+                );
+                dm.set(i, j, d);
+                dm.set(j, i, d);
+            }
+        }
+        dm
+    }
+
+    /// Multiplies every distance by `factor`, e.g. the ratio of road to
+    /// straight-line distance (typically 1.2–1.4).
+    ///
+    /// Separate travel times, if any, are left unchanged. Returns `None` if
+    /// `factor` is not positive and finite.
+    pub fn with_circuity_factor(mut self, factor: f64) -> Option<Self> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return None;
+        }
+        for d in &mut self.data {
+            *d *= factor;
+        }
+        Some(self)
+    }
+
+    /// Derives the travel times from the distances driven at `speed`
+    /// (distance units per time unit), replacing any separate travel times.
+    ///
+    /// Returns `None` if `speed` is not positive and finite.
+    pub fn with_average_speed(mut self, speed: f64) -> Option<Self> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return None;
+        }
+        self.times = self.data.iter().map(|d| d / speed).collect();
+        Some(self)
+    }
+
     /// Creates a distance matrix from an explicit n×n grid.
     ///
     /// Returns `None` if the data length doesn't match `size * size`.
@@ -58,7 +180,226 @@ impl DistanceMatrix {
         if data.len() != size * size {
             return None;
         }
-        Some(Self { data, size })
+        Some(Self {
+            data,
+            size,
+            times: Vec::new(),
+            profiles: Vec::new(),
+            arc_classes: Vec::new(),
+            routing_profiles: Vec::new(),
+        })
+    }
+
+    /// Adds the matrices of routing profile `profile`, used by vehicles
+    /// with [`Vehicle::with_routing_profile`] instead of this matrix.
+    ///
+    /// The profile matrix carries its own travel times and speed profiles.
+    /// Returns `None` if it does not cover the same locations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::models::Vehicle;
+    ///
+    /// const TRUCK: usize = 1;
+    /// // Trucks take a 3 km detour around a low bridge
+    /// let truck_roads = DistanceMatrix::from_data(2, vec![0.0, 5.0, 5.0, 0.0]).expect("valid");
+    /// let dm = DistanceMatrix::from_data(2, vec![0.0, 2.0, 2.0, 0.0])
+    ///     .expect("valid")
+    ///     .with_routing_profile(TRUCK, truck_roads)
+    ///     .expect("same size");
+    ///
+    /// let van = Vehicle::new(0, 50);
+    /// let truck = Vehicle::new(1, 200).with_routing_profile(TRUCK);
+    /// assert_eq!(dm.for_vehicle(&van).get(0, 1), 2.0);
+    /// assert_eq!(dm.for_vehicle(&truck).get(0, 1), 5.0);
+    /// ```
+    pub fn with_routing_profile(mut self, profile: usize, matrix: DistanceMatrix) -> Option<Self> {
+        if matrix.size != self.size {
+            return None;
+        }
+        self.routing_profiles.retain(|(id, _)| *id != profile);
+        self.routing_profiles.push((profile, matrix));
+        Some(self)
+    }
+
+    /// Matrix of routing profile `profile`, or this matrix if there is none.
+    pub fn for_profile(&self, profile: Option<usize>) -> &DistanceMatrix {
+        profile
+            .and_then(|p| self.routing_profiles.iter().find(|(id, _)| *id == p))
+            .map_or(self, |(_, matrix)| matrix)
+    }
+
+    /// Matrix that `vehicle` travels with (see [`for_profile`](Self::for_profile)).
+    pub fn for_vehicle(&self, vehicle: &Vehicle) -> &DistanceMatrix {
+        self.for_profile(vehicle.routing_profile())
+    }
+
+    /// Uses a separate n×n grid of travel times (row-major) instead of the
+    /// distances for scheduling.
+    ///
+    /// Returns `None` if the data length doesn't match `size * size`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::distance::DistanceMatrix;
+    ///
+    /// // 1200 m taking 3 minutes, 900 m taking 4 minutes back
+    /// let dm = DistanceMatrix::from_data(2, vec![0.0, 1200.0, 900.0, 0.0])
+    ///     .expect("valid")
+    ///     .with_travel_times(vec![0.0, 3.0, 4.0, 0.0])
+    ///     .expect("valid");
+    /// assert_eq!(dm.get(0, 1), 1200.0);
+    /// assert_eq!(dm.travel_time(0, 1, 0.0), 3.0);
+    /// assert_eq!(dm.travel_time(1, 0, 0.0), 4.0);
+    /// ```
+    pub fn with_travel_times(mut self, times: Vec<f64>) -> Option<Self> {
+        if times.len() != self.size * self.size {
+            return None;
+        }
+        self.times = times;
+        Some(self)
+    }
+
+    /// Sets the free-flow travel time from location `from` to location `to`.
+    ///
+    /// The other arcs keep their current travel time. Does nothing if
+    /// either index is out of bounds.
+    pub fn set_travel_time(&mut self, from: usize, to: usize, time: f64) {
+        if from < self.size && to < self.size {
+            if self.times.is_empty() {
+                self.times = self.data.clone();
+            }
+            self.times[from * self.size + to] = time;
+        }
+    }
+
+    /// Returns `true` if travel times are given separately from distances.
+    pub fn has_travel_times(&self) -> bool {
+        !self.times.is_empty()
+    }
+
+    /// Free-flow travel time from `from` to `to`, ignoring speed profiles.
+    ///
+    /// Returns `f64::INFINITY` if either index is out of bounds.
+    pub fn base_travel_time(&self, from: usize, to: usize) -> f64 {
+        if from >= self.size || to >= self.size {
+            return f64::INFINITY;
+        }
+        self.times
+            .get(from * self.size + to)
+            .copied()
+            .unwrap_or(self.data[from * self.size + to])
+    }
+
+    /// Makes travel times on every arc follow `profile`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::distance::{DistanceMatrix, SpeedProfile};
+    ///
+    /// let rush = SpeedProfile::new(vec![(0.0, 1.0), (7.0, 0.5), (9.0, 1.0)]).expect("valid");
+    /// let dm = DistanceMatrix::from_data(2, vec![0.0, 2.0, 2.0, 0.0])
+    ///     .expect("valid")
+    ///     .with_speed_profile(rush);
+    /// assert!((dm.travel_time(0, 1, 3.0) - 2.0).abs() < 1e-10);
+    /// // Half speed until 9, then free flow
+    /// assert!((dm.travel_time(0, 1, 7.0) - 3.0).abs() < 1e-10);
+    /// // Distances are unaffected
+    /// assert_eq!(dm.get(0, 1), 2.0);
+    /// ```
+    pub fn with_speed_profile(self, profile: SpeedProfile) -> Self {
+        self.with_speed_profiles(vec![profile])
+    }
+
+    /// Sets one speed profile per arc class; arcs are in class 0 until
+    /// assigned another with [`set_arc_class`](Self::set_arc_class).
+    ///
+    /// Arcs whose class has no profile travel at free flow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::distance::{DistanceMatrix, SpeedProfile};
+    ///
+    /// let city = SpeedProfile::new(vec![(0.0, 1.0), (7.0, 0.5), (9.0, 1.0)]).expect("valid");
+    /// let highway = SpeedProfile::constant(1.0).expect("valid");
+    /// let mut dm = DistanceMatrix::from_data(2, vec![0.0, 2.0, 2.0, 0.0])
+    ///     .expect("valid")
+    ///     .with_speed_profiles(vec![city, highway]);
+    /// dm.set_arc_class(0, 1, 1);
+    /// assert!((dm.travel_time(0, 1, 7.0) - 2.0).abs() < 1e-10);
+    /// assert!((dm.travel_time(1, 0, 7.0) - 3.0).abs() < 1e-10);
+    /// ```
+    pub fn with_speed_profiles(mut self, profiles: Vec<SpeedProfile>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Assigns the arc from `from` to `to` to the speed profile `class`.
+    ///
+    /// Does nothing if either index is out of bounds.
+    pub fn set_arc_class(&mut self, from: usize, to: usize, class: usize) {
+        if from < self.size && to < self.size {
+            if self.arc_classes.is_empty() {
+                self.arc_classes = vec![0; self.size * self.size];
+            }
+            self.arc_classes[from * self.size + to] = class;
+        }
+    }
+
+    /// Speed profile class of the arc from `from` to `to`.
+    pub fn arc_class(&self, from: usize, to: usize) -> usize {
+        self.arc_classes
+            .get(from * self.size + to)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if travel times depend on the departure time.
+    pub fn is_time_dependent(&self) -> bool {
+        !self.profiles.is_empty()
+    }
+
+    /// Longest finite distance and longest travel time over all arcs; a leg
+    /// driven in the slowest period of a speed profile takes its free-flow
+    /// time divided by that speed.
+    pub(crate) fn longest_arc(&self) -> (f64, f64) {
+        let longest = |values: &[f64]| {
+            values
+                .iter()
+                .copied()
+                .filter(|v| v.is_finite())
+                .fold(0.0, f64::max)
+        };
+        let slowest = self
+            .profiles
+            .iter()
+            .flat_map(SpeedProfile::periods)
+            .fold(1.0, |slowest: f64, &(_, speed)| slowest.min(speed));
+        let times = if self.times.is_empty() {
+            &self.data
+        } else {
+            &self.times
+        };
+        (longest(&self.data), longest(times) / slowest)
+    }
+
+    /// Travel time from `from` to `to` leaving at `departure`.
+    ///
+    /// Equals [`base_travel_time`](Self::base_travel_time) unless the arc
+    /// follows a [speed profile](Self::with_speed_profiles).
+    pub fn travel_time(&self, from: usize, to: usize, departure: f64) -> f64 {
+        let base = self.base_travel_time(from, to);
+        match self.profiles.get(self.arc_class(from, to)) {
+            Some(profile) if from < self.size && to < self.size => {
+                profile.travel_time(base, departure)
+            }
+            _ => base,
+        }
     }
 
     /// Returns the distance from location `from` to location `to`.
@@ -182,6 +523,94 @@ mod tests {
         assert_eq!(dm.get(10, 10), f64::INFINITY);
     }
 
+    #[test]
+    fn test_travel_time_follows_arc_class() {
+        let slow = SpeedProfile::constant(0.5).expect("valid");
+        let mut dm =
+            DistanceMatrix::from_customers(&sample_customers()).with_speed_profiles(vec![slow]);
+        dm.set_arc_class(0, 1, 3);
+        assert!(dm.is_time_dependent());
+        // Class 0 at half speed, class 3 has no profile
+        assert!((dm.travel_time(1, 0, 0.0) - 10.0).abs() < 1e-10);
+        assert!((dm.travel_time(0, 1, 0.0) - 5.0).abs() < 1e-10);
+        assert_eq!(dm.arc_class(0, 1), 3);
+        assert_eq!(dm.travel_time(0, 5, 0.0), f64::INFINITY);
+        assert!(!DistanceMatrix::new(2).is_time_dependent());
+    }
+
+    #[test]
+    fn test_separate_travel_times() {
+        let mut dm = DistanceMatrix::from_customers(&sample_customers());
+        assert!(!dm.has_travel_times());
+        assert!((dm.base_travel_time(0, 1) - 5.0).abs() < 1e-10);
+
+        dm.set_travel_time(0, 1, 2.0);
+        assert!(dm.has_travel_times());
+        assert_eq!(dm.travel_time(0, 1, 0.0), 2.0);
+        // Other arcs keep their distance as travel time
+        assert!((dm.travel_time(1, 0, 0.0) - 5.0).abs() < 1e-10);
+        assert!((dm.get(0, 1) - 5.0).abs() < 1e-10);
+        assert_eq!(dm.base_travel_time(0, 3), f64::INFINITY);
+
+        let slow = SpeedProfile::constant(0.5).expect("valid");
+        assert_eq!(dm.with_speed_profile(slow).travel_time(0, 1, 0.0), 4.0);
+        assert!(DistanceMatrix::new(2)
+            .with_travel_times(vec![0.0])
+            .is_none());
+    }
+
+    #[test]
+    fn test_from_lat_lon() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0).with_lat_lon(0.0, 0.0),
+            Customer::new(1, 0.0, 0.0, 10, 0.0).with_lat_lon(1.0, 0.0),
+            Customer::new(2, 0.0, 0.0, 10, 0.0).with_lat_lon(0.0, 1.0),
+        ];
+        let dm = DistanceMatrix::from_lat_lon(&customers, GeoFormula::Haversine);
+        let degree = crate::distance::EARTH_RADIUS_KM.to_radians();
+        assert!((dm.get(0, 1) - degree).abs() < 1e-9);
+        assert!((dm.get(2, 0) - degree).abs() < 1e-9);
+        assert!(dm.is_symmetric(1e-12));
+
+        // On the ellipsoid a degree of latitude is shorter at the equator
+        let dm = DistanceMatrix::from_lat_lon(&customers, GeoFormula::Vincenty);
+        assert!((dm.get(0, 1) - 110.574).abs() < 1e-3);
+        assert!((dm.get(0, 2) - 111.319).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_circuity_and_average_speed() {
+        let dm = DistanceMatrix::from_customers(&sample_customers())
+            .with_circuity_factor(1.2)
+            .expect("valid");
+        assert!((dm.get(0, 1) - 6.0).abs() < 1e-10);
+        assert!((dm.travel_time(0, 1, 0.0) - 6.0).abs() < 1e-10);
+
+        let dm = dm.with_average_speed(2.0).expect("valid");
+        assert!(dm.has_travel_times());
+        assert!((dm.get(0, 1) - 6.0).abs() < 1e-10);
+        assert!((dm.travel_time(0, 1, 0.0) - 3.0).abs() < 1e-10);
+
+        let dm = DistanceMatrix::new(2);
+        assert!(dm.clone().with_circuity_factor(0.0).is_none());
+        assert!(dm.clone().with_average_speed(-1.0).is_none());
+        assert!(dm.with_average_speed(f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_routing_profiles() {
+        let mut bike = DistanceMatrix::from_customers(&sample_customers());
+        bike.set(0, 1, 3.0);
+        let dm = DistanceMatrix::from_customers(&sample_customers())
+            .with_routing_profile(7, bike)
+            .expect("same size");
+        assert_eq!(dm.for_profile(Some(7)).get(0, 1), 3.0);
+        assert!((dm.for_profile(None).get(0, 1) - 5.0).abs() < 1e-10);
+        // Unknown profiles fall back to the main matrix
+        assert!((dm.for_profile(Some(2)).get(0, 1) - 5.0).abs() < 1e-10);
+        assert!(dm.with_routing_profile(8, DistanceMatrix::new(2)).is_none());
+    }
+
     #[test]
     fn test_set_out_of_bounds_is_noop() {
         let mut dm = DistanceMatrix::new(3);
@@ -0,0 +1,260 @@
+//! Distance metrics on planar coordinates.
+//!
+//! # Algorithm
+//!
+//! Besides the Euclidean, Manhattan (rectilinear, e.g. warehouse aisles) and
+//! Chebyshev distances, a [`Metric`] reproduces the TSPLIB rounding rules
+//! used by the TSPLIB and CVRPLIB benchmark instances, so that objective
+//! values match the published ones exactly:
+//!
+//! - `EUC_2D`: Euclidean distance rounded to the nearest integer,
+//!   `(int) (d + 0.5)`.
+//! - `CEIL_2D`: Euclidean distance rounded up.
+//! - `GEO`: coordinates are latitude and longitude in `DDD.MM` form
+//!   (degrees and minutes), converted to radians with π = 3.141592 and the
+//!   degrees truncated; the great-circle distance on a sphere of radius
+//!   6378.388 km is truncated after adding 1.
+//!
+//! # Complexity
+//!
+//! O(1) per pair of points.
+//!
+//! # Reference
+//!
+//! Reinelt, G. (1991). "TSPLIB — A traveling salesman problem library",
+//! *ORSA Journal on Computing* 3(4), 376-384.
+
+use std::str::FromStr;
+
+/// Value of π prescribed by TSPLIB for `GEO` coordinates.
+#[allow(clippy::approx_constant)]
+const TSPLIB_PI: f64 = 3.141592;
+/// Earth radius in kilometres prescribed by TSPLIB for `GEO` distances.
+const TSPLIB_RADIUS_KM: f64 = 6378.388;
+
+/// How the distance between two points `(x, y)` is measured.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::distance::Metric;
+///
+/// let (a, b) = ((0.0, 0.0), (3.0, 4.5));
+/// assert!((Metric::Euclidean.distance(a, b) - 5.408).abs() < 1e-3);
+/// assert_eq!(Metric::Manhattan.distance(a, b), 7.5);
+/// assert_eq!(Metric::Chebyshev.distance(a, b), 4.5);
+/// assert_eq!(Metric::RoundedEuclidean.distance(a, b), 5.0);
+/// assert_eq!(Metric::CeilEuclidean.distance(a, b), 6.0);
+///
+/// // Parsed from the names of the WASM and FFI inputs
+/// assert_eq!("euc_2d".parse(), Ok(Metric::RoundedEuclidean));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Straight-line distance.
+    #[default]
+    Euclidean,
+    /// Sum of the absolute coordinate differences.
+    Manhattan,
+    /// Largest absolute coordinate difference.
+    Chebyshev,
+    /// Euclidean distance rounded to the nearest integer (TSPLIB `EUC_2D`,
+    /// used by CVRPLIB).
+    RoundedEuclidean,
+    /// Euclidean distance rounded up (TSPLIB `CEIL_2D`).
+    CeilEuclidean,
+    /// Integer great-circle distance in kilometres (TSPLIB `GEO`), where x
+    /// is the latitude and y the longitude in `DDD.MM` form, in the order of
+    /// TSPLIB files (unlike
+    /// [`Customer::with_lat_lon`](crate::models::Customer::with_lat_lon)).
+    Geo,
+}
+
+impl Metric {
+    /// Distance between two points `(x, y)`.
+    pub fn distance(&self, from: (f64, f64), to: (f64, f64)) -> f64 {
+        let dx = from.0 - to.0;
+        let dy = from.1 - to.1;
+        let euclidean = || (dx * dx + dy * dy).sqrt();
+        match self {
+            Self::Euclidean => euclidean(),
+            Self::Manhattan => dx.abs() + dy.abs(),
+            Self::Chebyshev => dx.abs().max(dy.abs()),
+            Self::RoundedEuclidean => (euclidean() + 0.5).trunc(),
+            Self::CeilEuclidean => euclidean().ceil(),
+            Self::Geo => geo_distance(from, to),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    /// Parses `"euclidean"`, `"manhattan"`, `"chebyshev"`, `"euc_2d"`,
+    /// `"ceil_2d"` or `"geo"`; names are case-sensitive.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "euclidean" => Ok(Self::Euclidean),
+            "manhattan" => Ok(Self::Manhattan),
+            "chebyshev" => Ok(Self::Chebyshev),
+            "euc_2d" => Ok(Self::RoundedEuclidean),
+            "ceil_2d" => Ok(Self::CeilEuclidean),
+            "geo" => Ok(Self::Geo),
+            other => Err(format!(
+                "unknown metric '{}'. Supported: \"euclidean\", \"manhattan\", \"chebyshev\", \"euc_2d\", \"ceil_2d\", \"geo\"",
+                other
+            )),
+        }
+    }
+}
+
+/// TSPLIB `DDD.MM` coordinate in radians.
+fn geo_radians(coordinate: f64) -> f64 {
+    let degrees = coordinate.trunc();
+    let minutes = coordinate - degrees;
+    TSPLIB_PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+/// TSPLIB `GEO` distance between two `(latitude, longitude)` points.
+fn geo_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (geo_radians(from.0), geo_radians(from.1));
+    let (lat2, lon2) = (geo_radians(to.0), geo_radians(to.1));
+    let q1 = (lon1 - lon2).cos();
+    let q2 = (lat1 - lat2).cos();
+    let q3 = (lat1 + lat2).cos();
+    let cos_angle = 0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3);
+    (TSPLIB_RADIUS_KM * cos_angle.clamp(-1.0, 1.0).acos() + 1.0).trunc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::DistanceMatrix;
+    use crate::models::Customer;
+
+    /// Length of a closed tour of 1-based node numbers.
+    fn tour_length(dm: &DistanceMatrix, tour: &[usize]) -> f64 {
+        (0..tour.len())
+            .map(|i| dm.get(tour[i] - 1, tour[(i + 1) % tour.len()] - 1))
+            .sum()
+    }
+
+    fn points(coordinates: &[(f64, f64)]) -> Vec<Customer> {
+        coordinates
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| Customer::new(i, x, y, 0, 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_planar_metrics() {
+        let (a, b) = ((1.0, -2.0), (-2.0, 2.0));
+        assert!((Metric::Euclidean.distance(a, b) - 5.0).abs() < 1e-10);
+        assert_eq!(Metric::Manhattan.distance(a, b), 7.0);
+        assert_eq!(Metric::Chebyshev.distance(a, b), 4.0);
+        assert_eq!(Metric::default(), Metric::Euclidean);
+        // Halves round up; exact integers are not rounded up
+        assert_eq!(
+            Metric::RoundedEuclidean.distance((0.0, 0.0), (2.5, 0.0)),
+            3.0
+        );
+        assert_eq!(
+            Metric::RoundedEuclidean.distance((0.0, 0.0), (2.49, 0.0)),
+            2.0
+        );
+        assert_eq!(Metric::CeilEuclidean.distance(a, b), 5.0);
+        assert_eq!(Metric::CeilEuclidean.distance((0.0, 0.0), (1.0, 1.0)), 2.0);
+    }
+
+    #[test]
+    fn test_metric_names() {
+        assert_eq!("euclidean".parse(), Ok(Metric::Euclidean));
+        assert_eq!("manhattan".parse(), Ok(Metric::Manhattan));
+        assert_eq!("chebyshev".parse(), Ok(Metric::Chebyshev));
+        assert_eq!("euc_2d".parse(), Ok(Metric::RoundedEuclidean));
+        assert_eq!("ceil_2d".parse(), Ok(Metric::CeilEuclidean));
+        assert_eq!("geo".parse(), Ok(Metric::Geo));
+        assert!("EUC_2D".parse::<Metric>().is_err());
+        assert!("".parse::<Metric>().is_err());
+    }
+
+    #[test]
+    fn test_cvrplib_e_n22_k4() {
+        // CVRPLIB E-n22-k4, optimal cost 375
+        let customers = points(&[
+            (145.0, 215.0),
+            (151.0, 264.0),
+            (159.0, 261.0),
+            (130.0, 254.0),
+            (128.0, 252.0),
+            (163.0, 247.0),
+            (146.0, 246.0),
+            (161.0, 242.0),
+            (142.0, 239.0),
+            (163.0, 236.0),
+            (148.0, 232.0),
+            (128.0, 231.0),
+            (156.0, 217.0),
+            (129.0, 214.0),
+            (146.0, 208.0),
+            (164.0, 208.0),
+            (141.0, 206.0),
+            (147.0, 193.0),
+            (164.0, 193.0),
+            (129.0, 189.0),
+            (155.0, 185.0),
+            (139.0, 182.0),
+        ]);
+        let routes: [&[usize]; 4] = [
+            &[17, 20, 18, 15, 12],
+            &[16, 19, 21, 14],
+            &[13, 11, 4, 3, 8, 10],
+            &[9, 7, 5, 2, 1, 6],
+        ];
+        let cost = |dm: &DistanceMatrix| -> f64 {
+            routes
+                .iter()
+                .map(|route| {
+                    let tour: Vec<usize> = std::iter::once(1)
+                        .chain(route.iter().map(|c| c + 1))
+                        .collect();
+                    tour_length(dm, &tour)
+                })
+                .sum()
+        };
+
+        let dm = DistanceMatrix::from_customers_with(&customers, Metric::RoundedEuclidean);
+        assert_eq!(cost(&dm), 375.0);
+        let exact = cost(&DistanceMatrix::from_customers(&customers));
+        // Unrounded, the same routes are longer
+        assert!((exact - 375.28).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tsplib_geo() {
+        // TSPLIB ulysses16, optimal tour length 6859
+        let cities = points(&[
+            (38.24, 20.42),
+            (39.57, 26.15),
+            (40.56, 25.32),
+            (36.26, 23.12),
+            (33.48, 10.54),
+            (37.56, 12.19),
+            (38.42, 13.11),
+            (37.52, 20.44),
+            (41.23, 9.10),
+            (41.17, 13.05),
+            (36.08, -5.21),
+            (38.47, 15.13),
+            (38.15, 15.35),
+            (37.51, 15.17),
+            (35.49, 14.32),
+            (39.36, 19.56),
+        ]);
+        let dm = DistanceMatrix::from_customers_with(&cities, Metric::Geo);
+        let tour = [1, 14, 13, 12, 7, 6, 15, 5, 11, 9, 10, 16, 3, 2, 4, 8];
+        assert_eq!(tour_length(&dm, &tour), 6859.0);
+        assert!(dm.is_symmetric(0.0));
+    }
+}
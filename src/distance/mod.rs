@@ -1,7 +1,22 @@
 //! Distance and travel time matrices.
 //!
-//! Provides a dense distance matrix for routing problems.
+//! Provides a dense distance matrix for routing problems. The same matrix
+//! carries the travel times used for scheduling: the distances themselves,
+//! a separate travel-time matrix, and optional time-dependent speed profiles.
+//! Every component that takes a [`DistanceMatrix`] computes costs from its
+//! distances and feasibility from its travel times.
+//!
+//! Matrices are built from planar coordinates under a [`Metric`]
+//! (Euclidean, Manhattan, Chebyshev or the TSPLIB benchmark rounding rules),
+//! from latitude and longitude (great-circle distances, see [`GeoFormula`]),
+//! or from explicit data.
 
+mod geo;
 mod matrix;
+mod metric;
+mod speed_profile;
 
+pub use geo::{haversine_km, vincenty_km, GeoFormula, EARTH_RADIUS_KM};
 pub use matrix::DistanceMatrix;
+pub use metric::Metric;
+pub use speed_profile::SpeedProfile;
@@ -0,0 +1,157 @@
+//! Time-dependent travel times from piecewise-constant speed profiles.
+//!
+//! # Algorithm
+//!
+//! A profile splits the day into periods, each with a speed factor relative
+//! to the free-flow travel time stored in the matrix (1.0 = free flow, 0.5 =
+//! twice as slow). A vehicle crossing a period boundary continues at the new
+//! speed, so travel time is obtained by integrating speed over time rather
+//! than by looking up the departure period alone. This guarantees the FIFO
+//! property: leaving later never means arriving earlier.
+//!
+//! # Complexity
+//!
+//! O(p) per travel time, where p = number of periods in the profile.
+//!
+//! # Reference
+//!
+//! Ichoua, S., Gendreau, M. & Potvin, J.-Y. (2003). "Vehicle dispatching with
+//! time-dependent travel times", *European Journal of Operational Research*
+//! 144(2), 379-396.
+
+/// Piecewise-constant speed factors over time.
+///
+/// Each period starts at the given time and lasts until the next one; the
+/// first speed also applies before the first start and the last one
+/// indefinitely after it.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::distance::SpeedProfile;
+///
+/// // Half speed during the morning rush from 7 to 9
+/// let profile = SpeedProfile::new(vec![(0.0, 1.0), (7.0, 0.5), (9.0, 1.0)]).expect("valid");
+///
+/// // A 2-unit leg at free flow
+/// assert!((profile.travel_time(2.0, 3.0) - 2.0).abs() < 1e-10);
+/// // Leaving at 6: 1 unit by 7, the other at half speed until 9
+/// assert!((profile.travel_time(2.0, 6.0) - 3.0).abs() < 1e-10);
+/// // Leaving at 8: 0.5 unit by 9, then 1.5 at free flow
+/// assert!((profile.travel_time(2.0, 8.0) - 2.5).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedProfile {
+    /// `(start, speed)` of each period, by increasing start.
+    periods: Vec<(f64, f64)>,
+}
+
+impl SpeedProfile {
+    /// Creates a profile from `(start, speed)` periods.
+    ///
+    /// Returns `None` if there are no periods, a speed is not positive and
+    /// finite, or the starts are not finite and strictly increasing.
+    pub fn new(periods: Vec<(f64, f64)>) -> Option<Self> {
+        if periods.is_empty()
+            || periods
+                .iter()
+                .any(|&(start, speed)| !start.is_finite() || !speed.is_finite() || speed <= 0.0)
+            || periods.windows(2).any(|w| w[0].0 >= w[1].0)
+        {
+            return None;
+        }
+        Some(Self { periods })
+    }
+
+    /// A profile with the same speed factor at all times.
+    ///
+    /// Returns `None` if `speed` is not positive and finite.
+    pub fn constant(speed: f64) -> Option<Self> {
+        Self::new(vec![(0.0, speed)])
+    }
+
+    /// Returns the `(start, speed)` periods.
+    pub fn periods(&self) -> &[(f64, f64)] {
+        &self.periods
+    }
+
+    /// Speed factor at `time`.
+    pub fn speed_at(&self, time: f64) -> f64 {
+        self.periods[self.period_at(time)].1
+    }
+
+    /// Time to cover a leg of free-flow travel time `base` leaving at
+    /// `departure`.
+    pub fn travel_time(&self, base: f64, departure: f64) -> f64 {
+        let mut time = departure;
+        let mut left = base;
+        let mut k = self.period_at(departure);
+        loop {
+            let speed = self.periods[k].1;
+            let end = self.periods.get(k + 1).map_or(f64::INFINITY, |p| p.0);
+            let reach = time + left / speed;
+            if reach <= end {
+                return reach - departure;
+            }
+            left -= (end - time) * speed;
+            time = end;
+            k += 1;
+        }
+    }
+
+    /// Index of the period containing `time`.
+    fn period_at(&self, time: f64) -> usize {
+        self.periods
+            .partition_point(|&(start, _)| start <= time)
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rush_hour() -> SpeedProfile {
+        SpeedProfile::new(vec![(0.0, 1.0), (7.0, 0.5), (9.0, 1.0)]).expect("valid")
+    }
+
+    #[test]
+    fn test_speed_profile_validation() {
+        assert!(SpeedProfile::new(vec![]).is_none());
+        assert!(SpeedProfile::new(vec![(0.0, 0.0)]).is_none());
+        assert!(SpeedProfile::new(vec![(0.0, 1.0), (0.0, 2.0)]).is_none());
+        assert!(SpeedProfile::new(vec![(f64::NAN, 1.0)]).is_none());
+        assert!(SpeedProfile::constant(2.0).is_some());
+    }
+
+    #[test]
+    fn test_speed_profile_speed_at() {
+        let p = rush_hour();
+        assert_eq!(p.speed_at(-1.0), 1.0);
+        assert_eq!(p.speed_at(7.0), 0.5);
+        assert_eq!(p.speed_at(8.9), 0.5);
+        assert_eq!(p.speed_at(100.0), 1.0);
+    }
+
+    #[test]
+    fn test_speed_profile_spans_several_periods() {
+        // Leaving at 6 with 4 units: 1 by 7, 1 by 9, 2 more by 11
+        let p = rush_hour();
+        assert!((p.travel_time(4.0, 6.0) - 5.0).abs() < 1e-10);
+        assert!((p.travel_time(0.0, 8.0)).abs() < 1e-10);
+        let fast = SpeedProfile::constant(2.0).expect("valid");
+        assert!((fast.travel_time(4.0, 0.0) - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_speed_profile_fifo() {
+        let p = rush_hour();
+        let mut last_arrival = f64::NEG_INFINITY;
+        for step in 0..120 {
+            let departure = step as f64 * 0.1;
+            let arrival = departure + p.travel_time(3.0, departure);
+            assert!(arrival >= last_arrival - 1e-10);
+            last_arrival = arrival;
+        }
+    }
+}
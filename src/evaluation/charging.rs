@@ -0,0 +1,192 @@
+//! Charging-stop planning for electric vehicles.
+//!
+//! # Algorithm
+//!
+//! Existing charging stops are removed and the route is driven leg by leg,
+//! recharging fully at every charging stop. At the first leg the battery
+//! cannot cover, a charging station is inserted on one of the legs since
+//! the last recharge: the one with the smallest detour among those that the
+//! vehicle can reach and that leave enough energy to get past the failing
+//! leg. This repeats until the whole route is covered, or fails when no
+//! station helps.
+//!
+//! Stations are chosen by distance alone; the time spent recharging is
+//! charged by the evaluator, which checks the resulting schedule.
+//!
+//! # Complexity
+//!
+//! O(n² × S) per route, where n = stops and S = charging stations.
+//!
+//! # Reference
+//!
+//! Schneider, M., Stenger, A. & Goeke, D. (2014). "The Electric
+//! Vehicle-Routing Problem with Time Windows and Recharging Stations",
+//! *Transportation Science* 48(4), 500-520.
+
+use crate::distance::DistanceMatrix;
+use crate::models::{Customer, Vehicle};
+
+/// Removes every charging station from `route`.
+pub fn remove_charging_stops(route: &[usize], customers: &[Customer]) -> Vec<usize> {
+    route
+        .iter()
+        .copied()
+        .filter(|&cid| !customers[cid].is_charging_station())
+        .collect()
+}
+
+/// Re-plans the charging stops of `route` for `vehicle`.
+///
+/// Removes the charging stations already on the route and inserts the
+/// cheapest ones needed for the vehicle to reach its end location on its
+/// battery. A vehicle that is not electric gets the route without stations.
+///
+/// Returns `None` if the route cannot be covered, e.g. because some leg is
+/// longer than the vehicle's range or no station is within reach.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::insert_charging_stops;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 10.0, 0.0, 10, 0.0),
+///     Customer::charging_station(2, 10.0, 1.0, 1.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// // 15 units of range: not enough for the 20-unit round trip
+/// let van = Vehicle::new(0, 100).with_battery(15.0, 1.0);
+///
+/// let route = insert_charging_stops(&[1], &customers, &dm, &van).expect("reachable");
+/// assert_eq!(route, vec![2, 1]);
+/// ```
+pub fn insert_charging_stops(
+    route: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> Option<Vec<usize>> {
+    let mut route = remove_charging_stops(route, customers);
+    let Some(capacity) = vehicle.battery_capacity() else {
+        return Some(route);
+    };
+    if route.is_empty() {
+        return Some(route);
+    }
+    let distances = distances.for_vehicle(vehicle);
+    let ends = vehicle.route_ends();
+    let per_distance = vehicle.energy_per_distance();
+    let stations: Vec<usize> = (0..customers.len())
+        .filter(|&i| customers[i].is_charging_station())
+        .collect();
+
+    loop {
+        let nodes: Vec<usize> = std::iter::once(ends.start())
+            .chain(route.iter().copied())
+            .chain(std::iter::once(ends.end_node()))
+            .collect();
+        let energy = |a: usize, b: usize| per_distance * ends.leg(a, b, distances);
+
+        // Find the first leg the battery cannot cover
+        let mut level = capacity;
+        let mut charged_at = 0;
+        let mut levels = vec![capacity; nodes.len()];
+        let mut failing = None;
+        for k in 0..nodes.len() - 1 {
+            level -= energy(nodes[k], nodes[k + 1]);
+            if level < -1e-9 {
+                failing = Some(k);
+                break;
+            }
+            if customers
+                .get(nodes[k + 1])
+                .is_some_and(Customer::is_charging_station)
+            {
+                level = capacity;
+                charged_at = k + 1;
+            }
+            levels[k + 1] = level;
+        }
+        let Some(failing) = failing else {
+            return Some(route);
+        };
+
+        // Cheapest station on a leg since the last recharge that gets the
+        // vehicle past the failing leg
+        let mut best: Option<(usize, usize, f64)> = None;
+        for g in charged_at..=failing {
+            let (from, to) = (nodes[g], nodes[g + 1]);
+            let onward: f64 = (g + 1..=failing)
+                .map(|k| energy(nodes[k], nodes[k + 1]))
+                .sum();
+            for &s in &stations {
+                if s == from || s == to {
+                    continue;
+                }
+                if levels[g] - energy(from, s) < -1e-9 || capacity - energy(s, to) - onward < -1e-9
+                {
+                    continue;
+                }
+                let detour = ends.leg(from, s, distances) + ends.leg(s, to, distances)
+                    - ends.leg(from, to, distances);
+                if best.is_none_or(|(_, _, d)| detour < d) {
+                    best = Some((g, s, detour));
+                }
+            }
+        }
+        let (g, station, _) = best?;
+        route.insert(g, station);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> Vec<Customer> {
+        vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 10, 0.0),
+            Customer::new(2, 20.0, 0.0, 10, 0.0),
+            Customer::charging_station(3, 5.0, 1.0, 1.0),
+            Customer::charging_station(4, 15.0, 0.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn test_charging_not_needed() {
+        let customers = line();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_battery(40.0, 1.0);
+        let route = insert_charging_stops(&[1, 3, 2], &customers, &dm, &van).expect("covered");
+        // The stop at 3 is not needed and is removed
+        assert_eq!(route, vec![1, 2]);
+        let truck = Vehicle::new(1, 100);
+        assert_eq!(
+            insert_charging_stops(&[4, 1], &customers, &dm, &truck),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_charging_inserts_cheapest_station() {
+        let customers = line();
+        let dm = DistanceMatrix::from_customers(&customers);
+        // 25 units: the 40-unit round trip needs one recharge, best on the
+        // way between 1 and 2 where station 4 lies on the line
+        let van = Vehicle::new(0, 100).with_battery(25.0, 1.0);
+        let route = insert_charging_stops(&[1, 2], &customers, &dm, &van).expect("covered");
+        assert_eq!(route, vec![1, 4, 2]);
+    }
+
+    #[test]
+    fn test_charging_unreachable() {
+        let customers = line();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_battery(9.0, 1.0);
+        assert!(insert_charging_stops(&[1], &customers, &dm, &van).is_none());
+    }
+}
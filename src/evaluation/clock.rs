@@ -0,0 +1,282 @@
+//! Route timing with driver breaks.
+//!
+//! Shared by the evaluator and the time-aware heuristics so that they all
+//! place breaks the same way: a break is taken at the current stop, right
+//! before the leg that would run into its window or past the driving limit.
+//!
+//! The clock also tracks the battery of an electric vehicle, recharging it
+//! fully at charging stations (taking time at the station's charge rate)
+//! and between trips, so every feasibility check that runs a clock rejects
+//! routes that run out of energy.
+//!
+//! Legs take the travel time of the matrix at the moment the vehicle
+//! leaves (see [`DistanceMatrix::travel_time`]), so time-dependent speed
+//! profiles apply everywhere the clock is used.
+
+use crate::distance::DistanceMatrix;
+use crate::models::{Customer, DriverBreak, RouteEnds, Vehicle};
+
+/// Tracks the time along a route and inserts the vehicle's driver breaks.
+#[derive(Debug, Clone)]
+pub(crate) struct RouteClock {
+    time: f64,
+    shift_end: Option<f64>,
+    driver_break: Option<DriverBreak>,
+    driven: f64,
+    window_done: bool,
+    missed: bool,
+    penalty: f64,
+    battery_capacity: Option<f64>,
+    energy_per_distance: f64,
+    energy: f64,
+    depleted: bool,
+}
+
+impl RouteClock {
+    /// Starts at the vehicle's shift start, fully rested and charged.
+    pub(crate) fn new(vehicle: &Vehicle) -> Self {
+        Self {
+            time: vehicle.shift_start(),
+            shift_end: vehicle.shift_end(),
+            driver_break: vehicle.driver_break().copied(),
+            driven: 0.0,
+            window_done: false,
+            missed: false,
+            penalty: 0.0,
+            battery_capacity: vehicle.battery_capacity(),
+            energy_per_distance: vehicle.energy_per_distance(),
+            energy: vehicle.battery_capacity().unwrap_or(0.0),
+            depleted: false,
+        }
+    }
+
+    /// Current time.
+    pub(crate) fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Soft time-window penalty accumulated so far.
+    pub(crate) fn penalty(&self) -> f64 {
+        self.penalty
+    }
+
+    /// Returns `true` if a break could not be taken as required.
+    pub(crate) fn missed_break(&self) -> bool {
+        self.missed
+    }
+
+    /// Returns `true` if an electric vehicle ran out of energy on some leg.
+    pub(crate) fn battery_depleted(&self) -> bool {
+        self.depleted
+    }
+
+    /// Returns `true` if an electric vehicle's battery is currently below
+    /// empty, i.e. it could not have driven the last leg since charging.
+    pub(crate) fn out_of_energy(&self) -> bool {
+        self.battery_capacity.is_some() && self.energy < -1e-9
+    }
+
+    /// Drives from `from` to `to`, first taking a break if one is due.
+    ///
+    /// The travel time depends on the departure time, after any break.
+    /// Returns the start and end of the break taken, if any.
+    pub(crate) fn drive(
+        &mut self,
+        from: usize,
+        to: usize,
+        distances: &DistanceMatrix,
+    ) -> Option<(f64, f64)> {
+        let taken = self.rest_before(distances.travel_time(from, to, self.time));
+        let travel = distances.travel_time(from, to, self.time);
+        self.driven += travel;
+        self.time += travel;
+        if self.battery_capacity.is_some() {
+            self.energy -= self.energy_per_distance * distances.get(from, to);
+            self.depleted |= self.energy < -1e-9;
+        }
+        taken
+    }
+
+    /// Takes a break if one is due before a leg of `travel` time.
+    fn rest_before(&mut self, travel: f64) -> Option<(f64, f64)> {
+        match self.driver_break {
+            Some(DriverBreak::Window { window, duration })
+                if !self.window_done && self.time + travel > window.ready() =>
+            {
+                self.window_done = true;
+                let start = self.time.max(window.ready());
+                if start > window.due() {
+                    self.missed = true;
+                    None
+                } else {
+                    self.time = start + duration;
+                    Some((start, self.time))
+                }
+            }
+            Some(DriverBreak::AfterDriving {
+                max_driving,
+                duration,
+            }) => {
+                // A single leg longer than the limit cannot be driven legally
+                if travel > max_driving {
+                    self.missed = true;
+                }
+                if self.driven > 0.0 && self.driven + travel > max_driving {
+                    let start = self.time;
+                    self.time += duration;
+                    self.driven = 0.0;
+                    Some((start, self.time))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Spends `duration` reloading (and recharging) at the depot between
+    /// two trips.
+    pub(crate) fn reload(&mut self, duration: f64) {
+        self.time += duration;
+        self.energy = self.battery_capacity.unwrap_or(0.0);
+    }
+
+    /// Serves `customer` on arrival, waiting for its earliest open time
+    /// window, and advances past the service. Any soft time-window penalty
+    /// is added to [`penalty`](Self::penalty).
+    ///
+    /// At a charging station an electric vehicle then recharges fully.
+    ///
+    /// Returns the service start, or `None` if every window had closed (the
+    /// customer is then served on arrival and the penalty becomes infinite).
+    pub(crate) fn serve(&mut self, customer: &Customer) -> Option<f64> {
+        let start = customer.service_start(self.time);
+        self.penalty += customer.time_window_penalty(self.time);
+        self.time = start.unwrap_or(self.time) + customer.service_duration();
+        if let (Some(capacity), Some(rate)) = (self.battery_capacity, customer.charge_rate()) {
+            self.time += (capacity - self.energy).max(0.0) / rate;
+            self.energy = capacity;
+        }
+        start
+    }
+
+    /// Time the route would finish if it ended after the stop `last`,
+    /// including the return leg (and any break it needs) for closed routes.
+    ///
+    /// Returns `None` if a break is missed or the battery runs out along the
+    /// way.
+    pub(crate) fn finish_time(
+        &self,
+        last: usize,
+        ends: RouteEnds,
+        distances: &DistanceMatrix,
+    ) -> Option<f64> {
+        let mut clock = self.clone();
+        if let Some(end) = ends.end() {
+            clock.drive(last, end, distances);
+        }
+        (!clock.missed && !clock.depleted).then_some(clock.time)
+    }
+
+    /// Returns `true` if the route can end after the stop `last` without
+    /// missing a break or running out of energy, and within the vehicle's
+    /// shift.
+    pub(crate) fn can_finish(
+        &self,
+        last: usize,
+        ends: RouteEnds,
+        distances: &DistanceMatrix,
+    ) -> bool {
+        self.finish_time(last, ends, distances)
+            .is_some_and(|finish| self.shift_end.is_none_or(|end| finish <= end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::SpeedProfile;
+    use crate::models::TimeWindow;
+
+    /// Matrix whose leg `i → i + 1` has the given length.
+    fn legs(lengths: &[f64]) -> DistanceMatrix {
+        let mut dm = DistanceMatrix::new(lengths.len() + 1);
+        for (i, &d) in lengths.iter().enumerate() {
+            dm.set(i, i + 1, d);
+        }
+        dm
+    }
+
+    #[test]
+    fn test_clock_after_driving_break() {
+        let dm = legs(&[6.0, 6.0, 11.0]);
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::after_driving(10.0, 3.0));
+        let mut clock = RouteClock::new(&vehicle);
+        assert_eq!(clock.drive(0, 1, &dm), None);
+        // 6 + 6 would exceed the driving limit of 10: rest first
+        assert_eq!(clock.drive(1, 2, &dm), Some((6.0, 9.0)));
+        assert!((clock.time() - 15.0).abs() < 1e-10);
+        assert!(!clock.missed_break());
+        clock.drive(2, 3, &dm);
+        assert!(clock.missed_break());
+    }
+
+    #[test]
+    fn test_clock_window_break() {
+        let dm = legs(&[5.0, 6.0, 5.0]);
+        let tw = TimeWindow::new(10.0, 12.0).expect("valid");
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::in_window(tw, 2.0));
+        let mut clock = RouteClock::new(&vehicle);
+        assert_eq!(clock.drive(0, 1, &dm), None);
+        // Would still be driving at 10: wait for the window and rest
+        assert_eq!(clock.drive(1, 2, &dm), Some((10.0, 12.0)));
+        assert!((clock.time() - 18.0).abs() < 1e-10);
+        // Taken once only
+        assert_eq!(clock.drive(2, 3, &dm), None);
+
+        // A long service runs through the whole window
+        let mut late = RouteClock::new(&vehicle);
+        late.drive(0, 1, &dm);
+        assert_eq!(late.serve(&Customer::new(1, 0.0, 0.0, 0, 10.0)), Some(5.0));
+        assert_eq!(late.drive(2, 3, &dm), None);
+        assert!(late.missed_break());
+    }
+
+    #[test]
+    fn test_clock_time_dependent_leg() {
+        // Half speed from 10; the leg after the break leaves in the slow period
+        let slow = SpeedProfile::new(vec![(0.0, 1.0), (10.0, 0.5)]).expect("valid");
+        let dm = legs(&[8.0, 4.0]).with_speed_profile(slow);
+        let tw = TimeWindow::new(8.0, 9.0).expect("valid");
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::in_window(tw, 3.0));
+        let mut clock = RouteClock::new(&vehicle);
+        assert_eq!(clock.drive(0, 1, &dm), None);
+        assert_eq!(clock.drive(1, 2, &dm), Some((8.0, 11.0)));
+        assert!((clock.time() - 19.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_clock_battery() {
+        let dm = legs(&[4.0, 4.0, 4.0]);
+        let vehicle = Vehicle::new(0, 100).with_battery(10.0, 1.0);
+        let station = Customer::charging_station(2, 0.0, 0.0, 2.0);
+        let mut clock = RouteClock::new(&vehicle);
+        clock.drive(0, 1, &dm);
+        clock.drive(1, 2, &dm);
+        // 8 used: recharging the missing 8 at rate 2 takes 4
+        clock.serve(&station);
+        assert!((clock.time() - 12.0).abs() < 1e-10);
+        assert!(clock.can_finish(2, RouteEnds::new(0, 3), &dm));
+        clock.drive(2, 3, &dm);
+        assert!(!clock.battery_depleted());
+
+        let mut flat = RouteClock::new(&vehicle);
+        flat.drive(0, 1, &dm);
+        flat.drive(1, 2, &dm);
+        assert!(!flat.can_finish(2, RouteEnds::new(0, 3), &dm));
+        flat.drive(2, 3, &dm);
+        assert!(flat.battery_depleted());
+        flat.reload(0.0);
+        assert!((flat.energy - 10.0).abs() < 1e-10);
+    }
+}
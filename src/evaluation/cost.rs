@@ -0,0 +1,222 @@
+//! Cost functions pricing routes and unassigned customers.
+//!
+//! # Algorithm
+//!
+//! The evaluator measures each vehicle's route (or sequence of trips) as a
+//! [`RouteMetrics`]: distance, duration, emissions and soft time-window
+//! penalty. A [`CostFunction`] turns these into a cost, and prices each
+//! customer left unassigned. [`StandardCost`] charges the vehicle's fixed
+//! cost, cost per distance, cost per emission, cost per time and overtime,
+//! plus the penalty; the prize of an optional customer, or the solver's
+//! penalty for a mandatory one, is charged when it is left out.
+//!
+//! The GA fitness, the ALNS cost, the evaluator and the cost-aware local
+//! search ([`relocate_improve_with`](crate::local_search::relocate_improve_with),
+//! [`exchange_improve_with`](crate::local_search::exchange_improve_with))
+//! all price solutions through a cost function. The construction
+//! heuristics, the GA split and the ALNS repair operators do not: the split
+//! builds routes of least standard cost, and construction and repair also
+//! leave out the cost of time. A custom cost function therefore ranks the
+//! solutions they build without guiding how routes are built.
+//!
+//! # Complexity
+//!
+//! O(n) per route of n customers to measure it; pricing is O(1).
+
+use crate::distance::DistanceMatrix;
+use crate::models::{Customer, Vehicle};
+
+use super::RouteEvaluator;
+
+/// Measured quantities of one vehicle's route, or of all the trips of a
+/// multi-trip vehicle.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RouteMetrics {
+    /// Distance driven.
+    pub distance: f64,
+    /// Time from the vehicle's shift start until it is back at its end
+    /// location (or at its last customer, for an open route).
+    pub duration: f64,
+    /// Emissions under the vehicle's emission model.
+    pub emissions: f64,
+    /// Soft time-window penalty; infinite if a hard window is missed.
+    pub penalty: f64,
+}
+
+/// Prices routes and unassigned customers.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::{CostFunction, RouteEvaluator, RouteMetrics};
+///
+/// /// Pays drivers only, at 30 per time unit
+/// struct Wages;
+///
+/// impl CostFunction for Wages {
+///     fn route_cost(&self, _vehicle: &Vehicle, metrics: &RouteMetrics) -> f64 {
+///         30.0 * metrics.duration
+///     }
+/// }
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 3.0, 4.0, 10, 2.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let van = Vehicle::new(0, 100);
+/// let evaluator = RouteEvaluator::new(&customers, &dm, &van).with_cost_function(&Wages);
+/// // 5 there, 2 of service and 5 back
+/// assert!((evaluator.route_cost(&[1]) - 360.0).abs() < 1e-10);
+/// ```
+pub trait CostFunction: Send + Sync {
+    /// Cost of `vehicle` driving a route with the given `metrics`.
+    fn route_cost(&self, vehicle: &Vehicle, metrics: &RouteMetrics) -> f64;
+
+    /// Cost of leaving `customer` unassigned, where `penalty` is the
+    /// solver's penalty for a mandatory customer.
+    ///
+    /// Defaults to the prize of an optional customer and `penalty`
+    /// otherwise.
+    fn unassigned_cost(&self, customer: &Customer, penalty: f64) -> f64 {
+        if customer.is_optional() {
+            customer.prize()
+        } else {
+            penalty
+        }
+    }
+}
+
+/// The cost every solver uses by default.
+///
+/// A route costs its vehicle's [fixed cost](Vehicle::with_fixed_cost), its
+/// [cost per distance](Vehicle::with_cost_per_distance),
+/// [per emission](Vehicle::with_cost_per_emission) and
+/// [per time](Vehicle::with_cost_per_time) including
+/// [overtime](Vehicle::with_overtime), plus its soft time-window penalty.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Vehicle;
+/// use u_routing::evaluation::{CostFunction, RouteMetrics, StandardCost};
+///
+/// let van = Vehicle::new(0, 100)
+///     .with_fixed_cost(50.0)
+///     .with_cost_per_time(10.0);
+/// let metrics = RouteMetrics {
+///     distance: 30.0,
+///     duration: 2.0,
+///     ..RouteMetrics::default()
+/// };
+/// assert!((StandardCost.route_cost(&van, &metrics) - 100.0).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StandardCost;
+
+impl CostFunction for StandardCost {
+    fn route_cost(&self, vehicle: &Vehicle, metrics: &RouteMetrics) -> f64 {
+        vehicle.route_cost(metrics.distance)
+            + vehicle.cost_per_emission() * metrics.emissions
+            + vehicle.time_cost(metrics.duration)
+            + metrics.penalty
+    }
+}
+
+/// Cost of `route` driven by `vehicle` under `cost`, and its duration;
+/// an empty route costs nothing and has no duration.
+pub(crate) fn priced_route(
+    route: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+    cost: &dyn CostFunction,
+) -> (f64, Option<f64>) {
+    if route.is_empty() {
+        return (0.0, None);
+    }
+    let metrics = RouteEvaluator::new(customers, distances, vehicle).route_metrics(route);
+    (cost.route_cost(vehicle, &metrics), Some(metrics.duration))
+}
+
+/// Returns `true` if the [`StandardCost`] of any route is its vehicle's
+/// [`route_cost`](Vehicle::route_cost) of the distance driven: no type in
+/// `fleet` prices time or emissions and no customer has a time window, so
+/// a move can be priced by the change in distance alone.
+pub(crate) fn priced_by_distance(fleet: &[Vehicle], customers: &[Customer]) -> bool {
+    fleet
+        .iter()
+        .all(|v| v.cost_per_time() == 0.0 && v.overtime().is_none() && v.cost_per_emission() == 0.0)
+        && customers.iter().all(|c| c.time_windows().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimeWindow;
+
+    #[test]
+    fn test_standard_cost() {
+        let van = Vehicle::new(0, 100)
+            .with_fixed_cost(10.0)
+            .with_cost_per_distance(2.0)
+            .with_cost_per_emission(3.0)
+            .with_cost_per_time(1.0)
+            .with_overtime(4.0, 5.0);
+        let metrics = RouteMetrics {
+            distance: 6.0,
+            duration: 5.0,
+            emissions: 1.0,
+            penalty: 0.5,
+        };
+        // 10 + 12 + 3 + (5 + 5) + 0.5
+        assert!((StandardCost.route_cost(&van, &metrics) - 35.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_unassigned_cost() {
+        let mandatory = Customer::new(1, 0.0, 0.0, 10, 0.0);
+        let optional = mandatory.clone().with_prize(7.0).with_optional();
+        assert_eq!(StandardCost.unassigned_cost(&mandatory, 100.0), 100.0);
+        assert_eq!(StandardCost.unassigned_cost(&optional, 100.0), 7.0);
+    }
+
+    #[test]
+    fn test_priced_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 1.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_cost_per_time(1.0);
+        let (cost, duration) = priced_route(&[1], &customers, &dm, &van, &StandardCost);
+        assert!((cost - 21.0).abs() < 1e-10);
+        assert_eq!(duration, Some(11.0));
+        assert_eq!(
+            priced_route(&[], &customers, &dm, &van, &StandardCost),
+            (0.0, None)
+        );
+    }
+
+    #[test]
+    fn test_priced_by_distance() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 1.0),
+        ];
+        let van = Vehicle::new(0, 100)
+            .with_fixed_cost(10.0)
+            .with_cost_per_distance(2.0);
+        assert!(priced_by_distance(std::slice::from_ref(&van), &customers));
+        let paid = van.clone().with_cost_per_time(1.0);
+        assert!(!priced_by_distance(&[van.clone(), paid], &customers));
+
+        let mut timed = customers;
+        timed[1] = timed[1]
+            .clone()
+            .with_time_window(TimeWindow::new(0.0, 10.0).expect("valid"));
+        assert!(!priced_by_distance(&[van], &timed));
+    }
+}
@@ -1,9 +1,45 @@
 //! Route evaluator that computes timing, load, and feasibility.
 
-use crate::models::{Customer, Route, Solution, Vehicle, Violation, ViolationType, Visit};
+use crate::models::{
+    fleet_index, Customer, Load, RequestRole, Route, RouteEnds, ScheduledBreak, Solution, Vehicle,
+    Violation, ViolationType, Visit,
+};
+
+use super::{CostFunction, RouteClock, RouteMetrics, StandardCost};
 
 /// Evaluates routes by computing visit timing, cumulative load, total distance,
-/// and checking constraints (capacity, time windows, max distance/duration).
+/// and checking constraints (capacity, time windows, pickup-and-delivery
+/// precedence, skills, max distance/duration, shift end).
+///
+/// A route leaves the vehicle's depot at its shift start and returns to its
+/// end location (see [`Vehicle::with_shift`] and [`Vehicle::with_end_location`]).
+/// Route duration is measured from the shift start, or from the start of the
+/// trip for the later trips of a multi-trip vehicle
+/// ([`build_trips`](Self::build_trips)). Driver breaks (see
+/// [`Vehicle::with_break`]) are placed automatically and listed in
+/// [`Route::breaks`]; a break that cannot be taken in time is reported as
+/// [`ViolationType::BreakMissed`].
+///
+/// Arrival times use the travel time of each leg at the moment the vehicle
+/// leaves (see [`DistanceMatrix::travel_time`](crate::distance::DistanceMatrix::travel_time)),
+/// honouring a separate travel-time matrix and time-dependent speed
+/// profiles, while route distance and cost use the distances.
+///
+/// Route emissions follow the vehicle's [emission model](Vehicle::with_emissions)
+/// with the load carried along each leg, and are priced at its
+/// [cost per emission](Vehicle::with_cost_per_emission).
+///
+/// An [electric](Vehicle::with_battery) vehicle recharges at the charging
+/// stations on its route; the first location it cannot reach on its
+/// remaining energy is reported as [`ViolationType::BatteryDepleted`].
+///
+/// Capacity is checked against the on-board load at every point of the route
+/// (see [`Load::profile`]), so routes whose load rises and falls — paired
+/// pickups and deliveries, or simultaneous pickup and delivery — are handled
+/// correctly.
+///
+/// Costs follow [`StandardCost`] unless another [`CostFunction`] is set with
+/// [`with_cost_function`](Self::with_cost_function).
 ///
 /// # Examples
 ///
@@ -27,12 +63,20 @@ use crate::models::{Customer, Route, Solution, Vehicle, Violation, ViolationType
 /// ```
 pub struct RouteEvaluator<'a> {
     customers: &'a [Customer],
+    /// Matrix of the vehicle's routing profile.
     distances: &'a crate::distance::DistanceMatrix,
+    /// Matrix holding every routing profile.
+    network: &'a crate::distance::DistanceMatrix,
     vehicle: &'a Vehicle,
+    fleet: &'a [Vehicle],
+    cost: &'a dyn CostFunction,
 }
 
 impl<'a> RouteEvaluator<'a> {
     /// Creates a new evaluator for the given problem data.
+    ///
+    /// Routes are evaluated with the matrices of the vehicle's
+    /// [routing profile](Vehicle::with_routing_profile).
     pub fn new(
         customers: &'a [Customer],
         distances: &'a crate::distance::DistanceMatrix,
@@ -40,8 +84,61 @@ impl<'a> RouteEvaluator<'a> {
     ) -> Self {
         Self {
             customers,
-            distances,
+            distances: distances.for_vehicle(vehicle),
+            network: distances,
             vehicle,
+            fleet: std::slice::from_ref(vehicle),
+            cost: &StandardCost,
+        }
+    }
+
+    /// Prices routes with `cost` instead of [`StandardCost`].
+    pub fn with_cost_function(mut self, cost: &'a dyn CostFunction) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Evaluates each route of a solution with the vehicle type of its
+    /// [`Route::vehicle_id`] in `fleet` (see
+    /// [`evaluate_solution`](Self::evaluate_solution)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Solution, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::evaluation::RouteEvaluator;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 3.0, 4.0, 10, 0.0),
+    ///     Customer::new(2, -3.0, 4.0, 10, 0.0),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let fleet = [Vehicle::new(0, 50), Vehicle::new(1, 50).with_fixed_cost(100.0)];
+    ///
+    /// let mut solution = Solution::new();
+    /// solution.add_route(RouteEvaluator::new(&customers, &dm, &fleet[0]).build_route(&[1]).0);
+    /// solution.add_route(RouteEvaluator::new(&customers, &dm, &fleet[1]).build_route(&[2]).0);
+    ///
+    /// let evaluator = RouteEvaluator::new(&customers, &dm, &fleet[0]).with_fleet(&fleet);
+    /// let (cost, _) = evaluator.evaluate_solution(&solution);
+    /// assert!((cost - 120.0).abs() < 1e-10);
+    /// ```
+    pub fn with_fleet(mut self, fleet: &'a [Vehicle]) -> Self {
+        self.fleet = fleet;
+        self
+    }
+
+    /// Evaluator for the vehicle type serving `route`: the type of its
+    /// vehicle ID in the fleet, or this evaluator's vehicle.
+    fn for_route(&self, route: &Route) -> Self {
+        let vehicle =
+            fleet_index(self.fleet, route.vehicle_id()).map_or(self.vehicle, |k| &self.fleet[k]);
+        Self {
+            distances: self.network.for_vehicle(vehicle),
+            vehicle,
+            ..*self
         }
     }
 
@@ -49,64 +146,253 @@ impl<'a> RouteEvaluator<'a> {
     ///
     /// Returns the constructed route and any constraint violations found.
     pub fn build_route(&self, customer_ids: &[usize]) -> (Route, Vec<Violation>) {
+        let quantities = self.demands_of(customer_ids);
+        self.build_route_with_quantities(customer_ids, &quantities)
+    }
+
+    /// Builds a route delivering `quantities[i]` to `customer_ids[i]`
+    /// instead of the full demand, for customers served over several visits
+    /// (see [`Customer::with_split_delivery`](crate::models::Customer::with_split_delivery)).
+    ///
+    /// The quantity replaces the first demand dimension; the other
+    /// dimensions are still loaded in full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::evaluation::RouteEvaluator;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let vehicle = Vehicle::new(0, 100);
+    /// let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+    ///
+    /// let (route, violations) = evaluator.build_route_with_quantities(&[1], &[100]);
+    /// assert!(violations.is_empty());
+    /// assert_eq!(route.quantity_of(1), Some(100));
+    /// ```
+    pub fn build_route_with_quantities(
+        &self,
+        customer_ids: &[usize],
+        quantities: &[i32],
+    ) -> (Route, Vec<Violation>) {
+        let mut clock = RouteClock::new(self.vehicle);
+        self.build_trip(
+            customer_ids,
+            quantities,
+            &mut clock,
+            self.vehicle.route_ends(),
+        )
+    }
+
+    /// Full first-dimension demand of each customer.
+    fn demands_of(&self, customer_ids: &[usize]) -> Vec<i32> {
+        customer_ids
+            .iter()
+            .map(|&cid| self.customers[cid].demand())
+            .collect()
+    }
+
+    /// Builds the trips of a [multi-trip](Vehicle::with_reload_time) vehicle,
+    /// run in the given order within one shift.
+    ///
+    /// Each trip starts once the previous one is back at the depot and the
+    /// vehicle has been reloaded; all but the last trip return to the depot.
+    /// Route durations are measured from the start of each trip, and the
+    /// `route_index` of each violation is the trip's position in `trips`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::evaluation::RouteEvaluator;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 10.0, 0.0, 40, 0.0),
+    ///     Customer::new(2, 0.0, 10.0, 40, 0.0),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let van = Vehicle::new(0, 50).with_reload_time(5.0);
+    ///
+    /// let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+    /// let (trips, violations) = evaluator.build_trips(&[vec![1], vec![2]]);
+    /// assert!(violations.is_empty());
+    /// // Back at 20, reloaded at 25, at customer 2 at 35
+    /// assert_eq!(trips[1].visits()[0].arrival_time, 35.0);
+    /// ```
+    pub fn build_trips(&self, trips: &[Vec<usize>]) -> (Vec<Route>, Vec<Violation>) {
+        let quantities: Vec<Vec<i32>> = trips.iter().map(|t| self.demands_of(t)).collect();
+        self.build_trips_with_quantities(trips, &quantities)
+    }
+
+    /// Builds consecutive trips delivering `quantities[t][i]` to
+    /// `trips[t][i]`.
+    fn build_trips_with_quantities(
+        &self,
+        trips: &[Vec<usize>],
+        quantities: &[Vec<i32>],
+    ) -> (Vec<Route>, Vec<Violation>) {
+        let mut clock = RouteClock::new(self.vehicle);
+        let mut routes = Vec::with_capacity(trips.len());
+        let mut all_violations = Vec::new();
+        for (idx, trip) in trips.iter().enumerate() {
+            if idx > 0 {
+                clock.reload(self.vehicle.reload_time().unwrap_or(0.0));
+            }
+            let (route, mut violations) = self.build_trip(
+                trip,
+                &quantities[idx],
+                &mut clock,
+                self.trip_ends(idx + 1 == trips.len()),
+            );
+            set_route_index(&mut violations, idx);
+            routes.push(route);
+            all_violations.append(&mut violations);
+        }
+        (routes, all_violations)
+    }
+
+    /// Start and end of a trip: every trip but the last returns to the depot.
+    fn trip_ends(&self, last: bool) -> RouteEnds {
+        if last {
+            self.vehicle.route_ends()
+        } else {
+            RouteEnds::closed(self.vehicle.depot_id())
+        }
+    }
+
+    /// Builds one trip starting at the current time of `clock`.
+    fn build_trip(
+        &self,
+        customer_ids: &[usize],
+        quantities: &[i32],
+        clock: &mut RouteClock,
+        ends: RouteEnds,
+    ) -> (Route, Vec<Violation>) {
         let mut route = Route::new(self.vehicle.id());
         let mut violations = Vec::new();
-        let depot_id = self.vehicle.depot_id();
-        let mut current_time = 0.0;
-        let mut current_load: i32 = 0;
+        let trip_start = clock.time();
+        // On-board load leaving the depot and after each stop
+        let on_board = Load::profile_of_deliveries(customer_ids, quantities, self.customers);
         let mut total_distance = 0.0;
-        let mut prev = depot_id;
+        let mut prev = ends.start();
+        let mut unreachable = None;
 
-        for &cid in customer_ids {
-            let travel = self.distances.get(prev, cid);
-            total_distance += travel;
-            let arrival = current_time + travel;
+        for (pos, &cid) in customer_ids.iter().enumerate() {
+            total_distance += self.distances.get(prev, cid);
+            if let Some((start, end)) = clock.drive(prev, cid, self.distances) {
+                route.push_break(ScheduledBreak {
+                    before_visit: pos,
+                    start,
+                    end,
+                });
+            }
+            if clock.out_of_energy() {
+                unreachable.get_or_insert(cid);
+            }
+            let arrival = clock.time();
 
             let customer = &self.customers[cid];
 
-            // Check time window
-            let service_start = if let Some(tw) = customer.time_window() {
-                if tw.is_violated(arrival) {
-                    violations.push(Violation::new(ViolationType::TimeWindowViolated {
-                        customer_id: cid,
-                        arrival,
-                        due: tw.due(),
-                    }));
-                }
-                arrival + tw.waiting_time(arrival)
-            } else {
-                arrival
-            };
-
-            let departure = service_start + customer.service_duration();
-            current_load += customer.demand();
-
+            // Check time windows: serve in the earliest window still open
+            if clock.serve(customer).is_none() {
+                violations.push(Violation::new(ViolationType::TimeWindowViolated {
+                    customer_id: cid,
+                    arrival,
+                    due: customer.latest_due().unwrap_or(arrival),
+                }));
+            }
             route.push_visit(Visit {
                 customer_id: cid,
                 arrival_time: arrival,
-                departure_time: departure,
-                load_after: current_load,
+                departure_time: clock.time(),
+                load_after: on_board[pos + 1].get(0),
+                quantity: quantities[pos],
             });
 
-            current_time = departure;
             prev = cid;
         }
 
-        // Return to depot
-        let return_travel = self.distances.get(prev, depot_id);
-        total_distance += return_travel;
-        let total_duration = current_time + return_travel;
+        // Return to the end location (skipped for open routes)
+        if let Some(end_location) = ends.end() {
+            total_distance += self.distances.get(prev, end_location);
+            if let Some((start, end)) = clock.drive(prev, end_location, self.distances) {
+                route.push_break(ScheduledBreak {
+                    before_visit: customer_ids.len(),
+                    start,
+                    end,
+                });
+            }
+            if clock.out_of_energy() {
+                unreachable.get_or_insert(end_location);
+            }
+        }
+        let finish_time = clock.time();
+        let total_duration = finish_time - trip_start;
 
         route.set_total_distance(total_distance);
         route.set_total_duration(total_duration);
+        route.set_emissions(self.vehicle.trip_emissions(
+            customer_ids,
+            quantities,
+            ends,
+            self.customers,
+            self.distances,
+        ));
+        route.set_time_window_penalty(clock.penalty());
 
-        // Check capacity
-        if current_load > self.vehicle.capacity() {
-            violations.push(Violation::new(ViolationType::CapacityExceeded {
-                route_index: 0,
-                load: current_load,
-                capacity: self.vehicle.capacity(),
-            }));
+        // Check peak on-board load in every dimension
+        let peak_load = Load::peak_of_deliveries(customer_ids, quantities, self.customers);
+        for (dimension, &capacity) in self.vehicle.capacities().iter().enumerate() {
+            let load = peak_load.get(dimension);
+            if load > capacity {
+                violations.push(Violation::new(ViolationType::CapacityExceeded {
+                    route_index: 0,
+                    dimension,
+                    load,
+                    capacity,
+                }));
+            }
+        }
+
+        // Check pickup-before-delivery on the same route
+        for (pos, &cid) in customer_ids.iter().enumerate() {
+            let (pickup_id, delivery_id, ok) = match self.customers[cid].request_role() {
+                Some(RequestRole::Pickup { delivery_id }) => (
+                    cid,
+                    delivery_id,
+                    customer_ids[pos + 1..].contains(&delivery_id),
+                ),
+                Some(RequestRole::Delivery { pickup_id }) => {
+                    // Misordered pairs are reported from the pickup side
+                    (pickup_id, cid, customer_ids.contains(&pickup_id))
+                }
+                None => continue,
+            };
+            if !ok {
+                violations.push(Violation::new(ViolationType::PrecedenceViolated {
+                    route_index: 0,
+                    pickup_id,
+                    delivery_id,
+                }));
+            }
+        }
+
+        // Check vehicle-customer compatibility
+        for &cid in customer_ids {
+            if !self.vehicle.can_serve(&self.customers[cid]) {
+                violations.push(Violation::new(ViolationType::SkillMismatch {
+                    route_index: 0,
+                    customer_id: cid,
+                }));
+            }
         }
 
         // Check max distance
@@ -131,44 +417,378 @@ impl<'a> RouteEvaluator<'a> {
             }
         }
 
+        // Check driver breaks
+        if clock.missed_break() {
+            violations.push(Violation::new(ViolationType::BreakMissed {
+                route_index: 0,
+            }));
+        }
+
+        // Check battery
+        if let Some(location_id) = unreachable {
+            violations.push(Violation::new(ViolationType::BatteryDepleted {
+                route_index: 0,
+                location_id,
+            }));
+        }
+
+        // Check shift end
+        if let Some(shift_end) = self.vehicle.shift_end() {
+            if finish_time > shift_end {
+                violations.push(Violation::new(ViolationType::ShiftEndExceeded {
+                    route_index: 0,
+                    finish_time,
+                    shift_end,
+                }));
+            }
+        }
+
         (route, violations)
     }
 
+    /// Time at which a route visiting `customer_ids` reaches its end location
+    /// (or its last customer, for an open route), waiting for time windows and
+    /// taking driver breaks as [`build_route`](Self::build_route) does.
+    ///
+    /// Returns infinity if the route misses a driver break or runs out of
+    /// battery.
+    pub fn finish_time(&self, customer_ids: &[usize]) -> f64 {
+        let (clock, last) = self.clock_after(customer_ids);
+        clock
+            .finish_time(last, self.vehicle.route_ends(), self.distances)
+            .unwrap_or(f64::INFINITY)
+    }
+
+    /// Duration of a route visiting `customer_ids`: the time from the
+    /// vehicle's shift start to its [finish](Self::finish_time).
+    pub fn duration(&self, customer_ids: &[usize]) -> f64 {
+        self.finish_time(customer_ids) - self.vehicle.shift_start()
+    }
+
+    /// Distance, duration, emissions and soft time-window penalty of a route
+    /// visiting `customer_ids`.
+    ///
+    /// The duration runs until the route is back at its end location, even
+    /// if it misses a break or runs out of battery on the way.
+    pub fn route_metrics(&self, customer_ids: &[usize]) -> RouteMetrics {
+        self.metrics_between(customer_ids, self.vehicle.route_ends())
+    }
+
+    /// Metrics of a route driven between `ends`, scheduled from the vehicle's
+    /// depot.
+    pub(crate) fn metrics_between(&self, customer_ids: &[usize], ends: RouteEnds) -> RouteMetrics {
+        let (mut clock, last) = self.clock_after(customer_ids);
+        if let Some(end) = ends.end() {
+            clock.drive(last, end, self.distances);
+        }
+        RouteMetrics {
+            distance: ends.distance(customer_ids, self.distances),
+            duration: clock.time() - self.vehicle.shift_start(),
+            emissions: self.vehicle.emissions_between(
+                customer_ids,
+                ends,
+                self.customers,
+                self.distances,
+            ),
+            penalty: clock.penalty(),
+        }
+    }
+
+    /// Cost of a route visiting `customer_ids` under the evaluator's cost
+    /// function; an empty route costs nothing.
+    pub fn route_cost(&self, customer_ids: &[usize]) -> f64 {
+        if customer_ids.is_empty() {
+            return 0.0;
+        }
+        self.cost
+            .route_cost(self.vehicle, &self.route_metrics(customer_ids))
+    }
+
+    /// Soft time-window penalty of a route visiting `customer_ids`, as
+    /// reported by [`Route::time_window_penalty`].
+    pub fn time_window_penalty(&self, customer_ids: &[usize]) -> f64 {
+        self.clock_after(customer_ids).0.penalty()
+    }
+
+    /// Schedule after serving `customer_ids`, and the last stop.
+    fn clock_after(&self, customer_ids: &[usize]) -> (RouteClock, usize) {
+        let mut clock = RouteClock::new(self.vehicle);
+        let mut prev = self.vehicle.depot_id();
+        for &cid in customer_ids {
+            clock.drive(prev, cid, self.distances);
+            clock.serve(&self.customers[cid]);
+            prev = cid;
+        }
+        (clock, prev)
+    }
+
+    /// Returns `true` if a route visiting `customer_ids` finishes within the
+    /// vehicle's shift (if it has a shift end), without missing a driver
+    /// break or running out of battery.
+    pub fn within_shift(&self, customer_ids: &[usize]) -> bool {
+        let finish = self.finish_time(customer_ids);
+        finish.is_finite() && self.vehicle.shift_end().is_none_or(|end| finish <= end)
+    }
+
+    /// Packs trips onto as few vehicles as possible.
+    ///
+    /// Returns the trips of each vehicle as indices into `trips`, in the order
+    /// the vehicle runs them. A vehicle that is not
+    /// [multi-trip](Vehicle::with_reload_time) runs a single trip. Otherwise
+    /// trips are taken by increasing finish time and each is appended to the
+    /// first vehicle that can still run it within its time windows, driver
+    /// breaks and shift (first fit); a trip that is infeasible on its own gets
+    /// a vehicle of its own.
+    ///
+    /// Give the routes of each vehicle a common
+    /// [`Route::set_vehicle_index`] so that
+    /// [`evaluate_solution`](Self::evaluate_solution) schedules them together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::evaluation::RouteEvaluator;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 10.0, 0.0, 40, 0.0),
+    ///     Customer::new(2, 0.0, 10.0, 40, 0.0),
+    ///     Customer::new(3, -10.0, 0.0, 40, 0.0),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// // Trips take 20; two fit in a shift of 45 with a reload of 5
+    /// let van = Vehicle::new(0, 50).with_shift(0.0, 45.0).with_reload_time(5.0);
+    ///
+    /// let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+    /// let packed = evaluator.pack_trips(&[vec![1], vec![2], vec![3]]);
+    /// assert_eq!(packed, vec![vec![0, 1], vec![2]]);
+    /// ```
+    pub fn pack_trips(&self, trips: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        if !self.vehicle.is_multi_trip() {
+            return (0..trips.len()).map(|t| vec![t]).collect();
+        }
+        let finish: Vec<f64> = trips
+            .iter()
+            .map(|t| self.run_trips(&[t]).0.time())
+            .collect();
+        let mut order: Vec<usize> = (0..trips.len()).collect();
+        order.sort_by(|&a, &b| finish[a].total_cmp(&finish[b]));
+
+        let mut packed: Vec<Vec<usize>> = Vec::new();
+        for t in order {
+            let fits = |group: &Vec<usize>| {
+                let run: Vec<&[usize]> = group
+                    .iter()
+                    .chain(std::iter::once(&t))
+                    .map(|&i| trips[i].as_slice())
+                    .collect();
+                self.run_trips(&run).1
+            };
+            match packed.iter_mut().find(|group| fits(group)) {
+                Some(group) => group.push(t),
+                None => packed.push(vec![t]),
+            }
+        }
+        packed
+    }
+
+    /// Metrics of one vehicle running `trips` in sequence: the distance and
+    /// emissions of every trip, and the duration and soft time-window penalty
+    /// of the whole schedule.
+    pub fn trips_metrics(&self, trips: &[&[usize]]) -> RouteMetrics {
+        let mut distance = 0.0;
+        let mut emissions = 0.0;
+        for (idx, trip) in trips.iter().enumerate() {
+            let ends = self.trip_ends(idx + 1 == trips.len());
+            distance += ends.distance(trip, self.distances);
+            emissions += self
+                .vehicle
+                .emissions_between(trip, ends, self.customers, self.distances);
+        }
+        let clock = self.run_trips(trips).0;
+        RouteMetrics {
+            distance,
+            duration: clock.time() - self.vehicle.shift_start(),
+            emissions,
+            penalty: clock.penalty(),
+        }
+    }
+
+    /// Cost of one vehicle running `trips` in sequence under the evaluator's
+    /// cost function; with [`StandardCost`] the fixed cost is charged once.
+    pub fn trips_cost(&self, trips: &[&[usize]]) -> f64 {
+        self.cost
+            .route_cost(self.vehicle, &self.trips_metrics(trips))
+    }
+
+    /// Total cost of `trips` once [packed](Self::pack_trips) onto vehicles.
+    pub(crate) fn packed_trips_cost(&self, trips: &[Vec<usize>]) -> f64 {
+        self.pack_trips(trips)
+            .iter()
+            .map(|group| {
+                let run: Vec<&[usize]> = group.iter().map(|&i| trips[i].as_slice()).collect();
+                self.trips_cost(&run)
+            })
+            .sum()
+    }
+
+    /// Duration of each vehicle once `trips` are [packed](Self::pack_trips)
+    /// onto vehicles, from its shift start to the end of its last trip.
+    pub(crate) fn packed_trips_durations(&self, trips: &[Vec<usize>]) -> Vec<f64> {
+        self.pack_trips(trips)
+            .iter()
+            .map(|group| {
+                let run: Vec<&[usize]> = group.iter().map(|&i| trips[i].as_slice()).collect();
+                self.run_trips(&run).0.time() - self.vehicle.shift_start()
+            })
+            .collect()
+    }
+
+    /// Schedule after running `trips` in sequence, and whether every customer
+    /// was served within a time window, no break was missed and the shift end
+    /// was met.
+    fn run_trips(&self, trips: &[&[usize]]) -> (RouteClock, bool) {
+        let mut clock = RouteClock::new(self.vehicle);
+        let mut feasible = true;
+        for (idx, trip) in trips.iter().enumerate() {
+            if idx > 0 {
+                clock.reload(self.vehicle.reload_time().unwrap_or(0.0));
+            }
+            let ends = self.trip_ends(idx + 1 == trips.len());
+            let mut prev = ends.start();
+            for &cid in *trip {
+                clock.drive(prev, cid, self.distances);
+                feasible &= clock.serve(&self.customers[cid]).is_some();
+                prev = cid;
+            }
+            if let Some(end) = ends.end() {
+                clock.drive(prev, end, self.distances);
+            }
+        }
+        feasible &= !clock.missed_break()
+            && !clock.battery_depleted()
+            && self
+                .vehicle
+                .shift_end()
+                .is_none_or(|end| clock.time() <= end);
+        (clock, feasible)
+    }
+
     /// Evaluates an entire solution, computing route metrics and violations.
+    ///
+    /// Each route is evaluated with the vehicle type of its
+    /// [`Route::vehicle_id`] in the [fleet](Self::with_fleet), starting and
+    /// ending at that vehicle's depot and end location, or with this
+    /// evaluator's vehicle if the ID is not in the fleet. It is priced by the
+    /// evaluator's cost function (by default its fixed, distance, emission
+    /// and time cost plus its soft time-window penalty). For a
+    /// [multi-trip](Vehicle::with_reload_time) vehicle type, routes sharing a
+    /// [`Route::vehicle_index`] are scheduled as consecutive trips (see
+    /// [`build_trips`](Self::build_trips)) and priced together, so the fixed
+    /// cost is charged once per vehicle.
     pub fn evaluate_solution(&self, solution: &Solution) -> (f64, Vec<Violation>) {
-        let mut total_cost = 0.0;
-        let mut all_violations = Vec::new();
-
+        // Routes of each vehicle in solution order: the trips of a multi-trip
+        // vehicle together, every other route alone
+        let mut vehicles: Vec<(Self, Option<usize>, Vec<usize>)> = Vec::new();
         for (idx, route) in solution.routes().iter().enumerate() {
-            let customer_ids = route.customer_ids();
-            let (_, mut violations) = self.build_route(&customer_ids);
-
-            // Adjust route_index in violations
-            for v in &mut violations {
-                match &mut v.kind {
-                    ViolationType::CapacityExceeded { route_index, .. }
-                    | ViolationType::MaxDistanceExceeded { route_index, .. }
-                    | ViolationType::MaxDurationExceeded { route_index, .. } => {
-                        *route_index = idx;
-                    }
-                    ViolationType::TimeWindowViolated { .. } => {}
-                }
+            let evaluator = self.for_route(route);
+            let index = route
+                .vehicle_index()
+                .filter(|_| evaluator.vehicle.is_multi_trip());
+            match vehicles.iter_mut().find(|(e, v, _)| {
+                index.is_some() && *v == index && std::ptr::eq(e.vehicle, evaluator.vehicle)
+            }) {
+                Some((_, _, routes)) => routes.push(idx),
+                None => vehicles.push((evaluator, index, vec![idx])),
             }
+        }
 
-            total_cost += route.total_distance() * self.vehicle.cost_per_distance()
-                + self.vehicle.fixed_cost();
+        let mut total_cost = 0.0;
+        let mut all_violations = Vec::new();
+        for (evaluator, _, routes) in vehicles {
+            let (cost, mut violations) = evaluator.evaluate_vehicle(solution, &routes);
+            total_cost += cost;
             all_violations.append(&mut violations);
         }
 
         (total_cost, all_violations)
     }
+
+    /// Evaluates the `routes` of a solution run by one vehicle of this
+    /// evaluator's type: a single route, or the trips of a multi-trip vehicle.
+    fn evaluate_vehicle(&self, solution: &Solution, routes: &[usize]) -> (f64, Vec<Violation>) {
+        if !self.vehicle.is_multi_trip() {
+            let idx = routes[0];
+            let route = &solution.routes()[idx];
+            let (built, mut violations) =
+                self.build_route_with_quantities(&route.customer_ids(), &route.quantities());
+            set_route_index(&mut violations, idx);
+
+            let metrics = RouteMetrics {
+                distance: built.total_distance(),
+                duration: built.total_duration(),
+                emissions: built.emissions(),
+                penalty: built.time_window_penalty(),
+            };
+            return (self.cost.route_cost(self.vehicle, &metrics), violations);
+        }
+
+        let trips: Vec<Vec<usize>> = routes
+            .iter()
+            .map(|&idx| solution.routes()[idx].customer_ids())
+            .collect();
+        let quantities: Vec<Vec<i32>> = routes
+            .iter()
+            .map(|&idx| solution.routes()[idx].quantities())
+            .collect();
+        let (built, mut violations) = self.build_trips_with_quantities(&trips, &quantities);
+        for v in &mut violations {
+            if let Some(trip) = route_index_mut(v) {
+                *trip = routes[*trip];
+            }
+        }
+        let run: Vec<&[usize]> = trips.iter().map(Vec::as_slice).collect();
+        let metrics = RouteMetrics {
+            distance: built.iter().map(Route::total_distance).sum(),
+            duration: self.run_trips(&run).0.time() - self.vehicle.shift_start(),
+            emissions: built.iter().map(Route::emissions).sum(),
+            penalty: built.iter().map(Route::time_window_penalty).sum(),
+        };
+        (self.cost.route_cost(self.vehicle, &metrics), violations)
+    }
+}
+
+/// Route index of a violation, if it refers to a route.
+fn route_index_mut(violation: &mut Violation) -> Option<&mut usize> {
+    match &mut violation.kind {
+        ViolationType::CapacityExceeded { route_index, .. }
+        | ViolationType::MaxDistanceExceeded { route_index, .. }
+        | ViolationType::MaxDurationExceeded { route_index, .. }
+        | ViolationType::ShiftEndExceeded { route_index, .. }
+        | ViolationType::BreakMissed { route_index }
+        | ViolationType::SkillMismatch { route_index, .. }
+        | ViolationType::BatteryDepleted { route_index, .. }
+        | ViolationType::PrecedenceViolated { route_index, .. } => Some(route_index),
+        ViolationType::TimeWindowViolated { .. } => None,
+    }
+}
+
+/// Sets the route index of violations found on a single route.
+fn set_route_index(violations: &mut [Violation], idx: usize) {
+    for v in violations {
+        if let Some(route_index) = route_index_mut(v) {
+            *route_index = idx;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::distance::DistanceMatrix;
-    use crate::models::TimeWindow;
+    use crate::models::{DriverBreak, EmissionModel, TimeWindow};
 
     fn setup() -> (Vec<Customer>, DistanceMatrix, Vehicle) {
         let customers = vec![
@@ -204,6 +824,17 @@ mod tests {
         assert_eq!(route.total_load(), 10);
     }
 
+    #[test]
+    fn test_build_route_on_board_load() {
+        let (customers, dm, vehicle) = setup();
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Leaves the depot with 45 and drops 10, 20 and 15
+        let (route, _) = eval.build_route(&[1, 2, 3]);
+        let loads: Vec<i32> = route.visits().iter().map(|v| v.load_after).collect();
+        assert_eq!(loads, vec![35, 15, 0]);
+        assert_eq!(route.total_load(), 45);
+    }
+
     #[test]
     fn test_build_route_capacity_violated() {
         let (customers, dm, _) = setup();
@@ -223,6 +854,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_build_route_second_dimension_violated() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 5.0).with_demands(vec![10, 6]),
+            Customer::new(2, 6.0, 8.0, 20, 5.0).with_demands(vec![20, 6]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Weight fits (30 <= 100), volume does not (12 > 10)
+        let vehicle = Vehicle::new(0, 0).with_capacities(vec![100, 10]);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1, 2]);
+        assert_eq!(route.total_load(), 30);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].kind,
+            ViolationType::CapacityExceeded {
+                route_index: 0,
+                dimension: 1,
+                load: 12,
+                capacity: 10,
+            }
+        );
+    }
+
     #[test]
     fn test_build_route_within_capacity() {
         let (customers, dm, vehicle) = setup();
@@ -265,6 +921,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_build_route_soft_time_window() {
+        // Due at 3 but accepts up to 5 late at 2 per time unit
+        let tw = TimeWindow::new(0.0, 3.0)
+            .expect("valid")
+            .with_soft_late(5.0, 2.0);
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 5.0).with_time_window(tw),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Arrives at 5: 2 late
+        let (route, violations) = eval.build_route(&[1]);
+        assert!(violations.is_empty());
+        assert!((route.time_window_penalty() - 4.0).abs() < 1e-10);
+        assert!((eval.time_window_penalty(&[1]) - 4.0).abs() < 1e-10);
+
+        let mut sol = Solution::new();
+        sol.add_route(route);
+        let (cost, _) = eval.evaluate_solution(&sol);
+        assert!((cost - 14.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_build_route_waiting() {
         let tw = TimeWindow::new(20.0, 100.0).expect("valid");
@@ -283,6 +964,147 @@ mod tests {
         assert!((visit.departure_time - 25.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_build_route_waits_for_second_window() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 5.0).with_time_windows(vec![
+                TimeWindow::new(0.0, 2.0).expect("valid"),
+                TimeWindow::new(30.0, 40.0).expect("valid"),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1]);
+        assert!(violations.is_empty());
+        // Arrives at 5.0 after the first window closed, waits until 30.0
+        let visit = &route.visits()[0];
+        assert!((visit.arrival_time - 5.0).abs() < 1e-10);
+        assert!((visit.departure_time - 35.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_route_all_windows_closed() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 3.0, 4.0, 10, 5.0).with_time_windows(vec![
+                TimeWindow::new(0.0, 2.0).expect("valid"),
+                TimeWindow::new(3.0, 4.0).expect("valid"),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (_, violations) = eval.build_route(&[1]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::TimeWindowViolated { due, .. } if (due - 4.0).abs() < 1e-10
+        ));
+    }
+
+    fn pdp_setup() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+            Customer::new(2, 2.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+            Customer::new(3, 3.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 4.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_build_route_pickup_delivery_load_profile() {
+        let (customers, dm) = pdp_setup();
+        // Capacity 10 suffices when each pair is delivered before the next pickup
+        let vehicle = Vehicle::new(0, 10);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1, 2, 3, 4]);
+        assert!(violations.is_empty());
+        let loads: Vec<i32> = route.visits().iter().map(|v| v.load_after).collect();
+        assert_eq!(loads, vec![10, 0, 10, 0]);
+
+        // Carrying both at once peaks at 20
+        let (_, violations) = eval.build_route(&[1, 3, 2, 4]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::CapacityExceeded { load: 20, .. }
+        ));
+    }
+
+    #[test]
+    fn test_build_route_simultaneous_pickup_delivery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 0, 0.0).with_pickup(10),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Total delivery (10) and total pickup (10) both fit the vehicle
+        let vehicle = Vehicle::new(0, 10);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Drop first: 10 → 0 → 10
+        let (_, violations) = eval.build_route(&[1, 2]);
+        assert!(violations.is_empty());
+        // Collect first: 10 → 20 → 10
+        let (_, violations) = eval.build_route(&[2, 1]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::CapacityExceeded {
+                load: 20,
+                capacity: 10,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_build_route_precedence_violated() {
+        let (customers, dm) = pdp_setup();
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Delivery 2 before pickup 1
+        let (_, violations) = eval.build_route(&[2, 1, 3, 4]);
+        assert_eq!(
+            violations,
+            vec![Violation::new(ViolationType::PrecedenceViolated {
+                route_index: 0,
+                pickup_id: 1,
+                delivery_id: 2,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_solution_split_pair() {
+        let (customers, dm) = pdp_setup();
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(eval.build_route(&[1, 3, 4]).0);
+        sol.add_route(eval.build_route(&[2]).0);
+        let (_, violations) = eval.evaluate_solution(&sol);
+        // Reported once for the pickup's route and once for the delivery's route
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[1].kind,
+            ViolationType::PrecedenceViolated {
+                route_index: 1,
+                pickup_id: 1,
+                delivery_id: 2
+            }
+        ));
+    }
+
     #[test]
     fn test_build_route_max_distance_violated() {
         let (customers, dm, _) = setup();
@@ -297,6 +1119,97 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_build_route_open_route() {
+        let (customers, dm, _) = setup();
+        // depot->1 = 5.0 without the return leg, within the limit of 8.0
+        let vehicle = Vehicle::new(0, 100)
+            .with_open_route()
+            .with_max_distance(8.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1]);
+        assert!(violations.is_empty());
+        assert!((route.total_distance() - 5.0).abs() < 1e-10);
+        // Ends after serving customer 1: travel 5 + service 5
+        assert!((route.total_duration() - 10.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_route_shift_and_end_location() {
+        let (customers, dm, _) = setup();
+        // Leaves the depot at 100 and finishes at customer 2's location
+        let vehicle = Vehicle::new(0, 100)
+            .with_end_location(2)
+            .with_shift(100.0, 110.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1]);
+        assert!((route.visits()[0].arrival_time - 105.0).abs() < 1e-10);
+        // depot->1 = 5.0, 1->2 = 5.0
+        assert!((route.total_distance() - 10.0).abs() < 1e-10);
+        assert!((route.total_duration() - 15.0).abs() < 1e-10);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::ShiftEndExceeded { finish_time, shift_end, .. }
+                if (finish_time - 115.0).abs() < 1e-10 && shift_end == 110.0
+        ));
+        assert!((eval.finish_time(&[1]) - 115.0).abs() < 1e-10);
+        assert!(!eval.within_shift(&[1]));
+        assert!(eval.within_shift(&[]));
+    }
+
+    #[test]
+    fn test_build_route_driver_break() {
+        let (customers, dm, _) = setup();
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::after_driving(10.0, 2.0));
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Drives 5 + 5, then rests at customer 2 before the 10-unit return
+        let (route, violations) = eval.build_route(&[1, 2]);
+        assert!(violations.is_empty());
+        assert!((route.visits()[1].arrival_time - 15.0).abs() < 1e-10);
+        assert_eq!(route.breaks().len(), 1);
+        assert_eq!(route.break_before(1), None);
+        let rest = route.break_before(2).expect("rest before return");
+        assert!((rest.start - 20.0).abs() < 1e-10);
+        assert!((rest.end - 22.0).abs() < 1e-10);
+        assert!((route.total_duration() - 32.0).abs() < 1e-10);
+        assert!((eval.finish_time(&[1, 2]) - 32.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_route_break_missed() {
+        let (customers, dm, _) = setup();
+        // The 10-unit return leg alone exceeds the driving limit
+        let vehicle = Vehicle::new(0, 100).with_break(DriverBreak::after_driving(8.0, 2.0));
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (_, violations) = eval.build_route(&[1, 2]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::BreakMissed { route_index: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_build_route_skill_mismatch() {
+        let (mut customers, dm, vehicle) = setup();
+        customers[2] = customers[2].clone().with_skills(vec![1]);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (_, violations) = eval.build_route(&[1, 2]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::SkillMismatch {
+                route_index: 0,
+                customer_id: 2
+            }
+        ));
+
+        let equipped = vehicle.with_skills(vec![1]);
+        let eval = RouteEvaluator::new(&customers, &dm, &equipped);
+        assert!(eval.build_route(&[1, 2]).1.is_empty());
+    }
+
     #[test]
     fn test_build_route_max_duration_violated() {
         let (customers, dm, _) = setup();
@@ -311,6 +1224,120 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_build_route_time_dependent() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 2.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 2.5).expect("valid")),
+        ];
+        // Half speed from time 1: one unit by 1, the other takes 2
+        let rush = crate::distance::SpeedProfile::new(vec![(0.0, 1.0), (1.0, 0.5)]).expect("valid");
+        let dm = DistanceMatrix::from_customers(&customers).with_speed_profile(rush);
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1]);
+        assert!((route.visits()[0].arrival_time - 3.0).abs() < 1e-10);
+        assert!((route.total_distance() - 4.0).abs() < 1e-10);
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_build_route_separate_travel_times() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 0.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 10.0).expect("valid")),
+        ];
+        // 2 km each way, 12 minutes out and 8 back
+        let dm = DistanceMatrix::from_data(2, vec![0.0, 2000.0, 2000.0, 0.0])
+            .expect("valid")
+            .with_travel_times(vec![0.0, 12.0, 8.0, 0.0])
+            .expect("valid");
+        let vehicle = Vehicle::new(0, 100);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let (route, violations) = eval.build_route(&[1]);
+        assert!((route.total_distance() - 4000.0).abs() < 1e-10);
+        assert!((route.total_duration() - 20.0).abs() < 1e-10);
+        assert!((route.visits()[0].arrival_time - 12.0).abs() < 1e-10);
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_build_route_routing_profile() {
+        const BIKE: usize = 1;
+        let (customers, dm, _) = setup();
+        let n = dm.size();
+        // Bikes take shortcuts: every leg is half as long
+        let shortcuts = (0..n * n).map(|k| dm.get(k / n, k % n) / 2.0).collect();
+        let dm = dm
+            .with_routing_profile(
+                BIKE,
+                DistanceMatrix::from_data(n, shortcuts).expect("valid"),
+            )
+            .expect("valid");
+        let truck = Vehicle::new(0, 100);
+        let bike = Vehicle::new(1, 100).with_routing_profile(BIKE);
+        let (by_truck, _) = RouteEvaluator::new(&customers, &dm, &truck).build_route(&[1, 2]);
+        let (by_bike, _) = RouteEvaluator::new(&customers, &dm, &bike).build_route(&[1, 2]);
+        assert!((by_bike.total_distance() * 2.0 - by_truck.total_distance()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_route_emissions() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 60, 0.0).with_split_delivery(),
+            Customer::new(2, 2.0, 0.0, 40, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100)
+            .with_emissions(EmissionModel::new(1.0, 2.0).expect("valid"))
+            .with_cost_per_emission(2.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+
+        // Legs carry 100, 40 and nothing: 2 + 1.4 + 2
+        let (route, _) = eval.build_route(&[1, 2]);
+        assert!((route.emissions() - 5.4).abs() < 1e-10);
+        // Delivering only 10 of customer 1's demand lightens the first leg
+        let (split, _) = eval.build_route_with_quantities(&[1, 2], &[10, 40]);
+        assert!((split.emissions() - 4.9).abs() < 1e-10);
+
+        let mut sol = Solution::new();
+        sol.add_route(route);
+        let (cost, _) = eval.evaluate_solution(&sol);
+        assert!((cost - (4.0 + 2.0 * 5.4)).abs() < 1e-10);
+        assert!((sol.total_emissions() - 5.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_route_battery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 10, 0.0),
+            Customer::charging_station(2, 10.0, 0.0, 2.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100).with_battery(15.0, 1.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+
+        let (_, violations) = eval.build_route(&[1]);
+        assert_eq!(
+            violations[0].kind,
+            ViolationType::BatteryDepleted {
+                route_index: 0,
+                location_id: 0
+            }
+        );
+        assert!(eval.finish_time(&[1]).is_infinite());
+
+        // Recharging the 10 used at rate 2 takes 5
+        let (route, violations) = eval.build_route(&[1, 2]);
+        assert!(violations.is_empty());
+        assert!((route.visits()[1].departure_time - 15.0).abs() < 1e-10);
+        assert!((route.total_duration() - 25.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_timing_chain() {
         let (customers, dm, vehicle) = setup();
@@ -322,4 +1349,144 @@ mod tests {
         let expected_arrival_2 = v1.departure_time + dm.get(1, 2);
         assert!((v2.arrival_time - expected_arrival_2).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_route_metrics_and_cost() {
+        let (customers, dm, _) = setup();
+        let vehicle = Vehicle::new(0, 50)
+            .with_cost_per_time(2.0)
+            .with_overtime(25.0, 4.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // 5 + 5 + 10 driven, 10 of service
+        let metrics = eval.route_metrics(&[1, 2]);
+        assert!((metrics.distance - 20.0).abs() < 1e-10);
+        assert!((metrics.duration - 30.0).abs() < 1e-10);
+        // 20 + 2 × 30 + 4 × 5 of overtime
+        assert!((eval.route_cost(&[1, 2]) - 100.0).abs() < 1e-10);
+        assert_eq!(eval.route_cost(&[]), 0.0);
+
+        struct Flat;
+        impl CostFunction for Flat {
+            fn route_cost(&self, _vehicle: &Vehicle, _metrics: &RouteMetrics) -> f64 {
+                7.0
+            }
+        }
+        let eval = eval.with_cost_function(&Flat);
+        let mut sol = Solution::new();
+        sol.add_route(eval.build_route(&[1, 2]).0);
+        sol.add_route(eval.build_route(&[3]).0);
+        assert!((eval.evaluate_solution(&sol).0 - 14.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_build_trips() {
+        let (customers, dm, _) = setup();
+        let vehicle = Vehicle::new(0, 50)
+            .with_shift(0.0, 60.0)
+            .with_reload_time(10.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        // Back from customer 1 at 15, reloaded at 25, at customer 3 at 35
+        let (trips, violations) = eval.build_trips(&[vec![1], vec![3]]);
+        assert!(violations.is_empty());
+        assert_eq!(trips.len(), 2);
+        assert!((trips[1].visits()[0].arrival_time - 35.0).abs() < 1e-10);
+        assert!((trips[1].total_duration() - 25.0).abs() < 1e-10);
+
+        // A third trip would end at 85, past the shift end
+        let (_, violations) = eval.build_trips(&[vec![1], vec![3], vec![2]]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::ShiftEndExceeded { route_index: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_pack_trips() {
+        let (customers, dm, _) = setup();
+        let vehicle = Vehicle::new(0, 50)
+            .with_shift(0.0, 60.0)
+            .with_fixed_cost(100.0)
+            .with_reload_time(10.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let trips = vec![vec![1], vec![2], vec![3]];
+        assert_eq!(eval.pack_trips(&trips), vec![vec![0, 1], vec![2]]);
+        // Fixed cost once per vehicle: 100 + 10 + 20, then 100 + 20
+        assert!((eval.packed_trips_cost(&trips) - 250.0).abs() < 1e-10);
+
+        // Without reloading, every route needs its own vehicle
+        let single = Vehicle::new(0, 50).with_shift(0.0, 60.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &single);
+        assert_eq!(eval.pack_trips(&trips), vec![vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_evaluate_solution_multi_trip() {
+        let (customers, dm, _) = setup();
+        let vehicle = Vehicle::new(0, 50)
+            .with_shift(0.0, 60.0)
+            .with_fixed_cost(100.0)
+            .with_reload_time(10.0);
+        let eval = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        for (ids, index) in [(vec![1], 0), (vec![3], 0), (vec![2], 1)] {
+            let mut route = eval.build_route(&ids).0;
+            route.set_vehicle_index(index);
+            sol.add_route(route);
+        }
+        let (cost, violations) = eval.evaluate_solution(&sol);
+        assert!(violations.is_empty());
+        assert!((cost - 250.0).abs() < 1e-10);
+
+        // All three trips on one vehicle overrun the shift on the last one
+        sol.routes_mut()[2].set_vehicle_index(0);
+        let (cost, violations) = eval.evaluate_solution(&sol);
+        assert!((cost - 150.0).abs() < 1e-10);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::ShiftEndExceeded { route_index: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_solution_fleet() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::depot_with_id(1, 10.0, 0.0),
+            Customer::new(2, 11.0, 0.0, 30, 0.0),
+            Customer::new(3, -1.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = [
+            Vehicle::new(0, 50),
+            Vehicle::new(1, 20).with_depot(1).with_fixed_cost(5.0),
+        ];
+        let mut sol = Solution::new();
+        sol.add_route(
+            RouteEvaluator::new(&customers, &dm, &fleet[0])
+                .build_route(&[3])
+                .0,
+        );
+        sol.add_route(
+            RouteEvaluator::new(&customers, &dm, &fleet[1])
+                .build_route(&[2])
+                .0,
+        );
+
+        // Without the fleet, both routes are driven by the first vehicle
+        let eval = RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let (cost, violations) = eval.evaluate_solution(&sol);
+        assert!((cost - 24.0).abs() < 1e-10);
+        assert!(violations.is_empty());
+
+        // Each route from its own depot, within its own capacity
+        let (cost, violations) = eval.with_fleet(&fleet).evaluate_solution(&sol);
+        assert!((cost - 9.0).abs() < 1e-10);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0].kind,
+            ViolationType::CapacityExceeded { route_index: 1, .. }
+        ));
+    }
 }
@@ -1,5 +1,16 @@
 //! Route feasibility checking and cost evaluation.
 
+mod charging;
+mod clock;
+mod cost;
 mod evaluator;
+mod objective;
 
+pub(crate) use clock::RouteClock;
+pub(crate) use cost::{priced_by_distance, priced_route};
+pub(crate) use objective::{primary_weight, Change};
+
+pub use charging::{insert_charging_stops, remove_charging_stops};
+pub use cost::{CostFunction, RouteMetrics, StandardCost};
 pub use evaluator::RouteEvaluator;
+pub use objective::Objective;
@@ -0,0 +1,311 @@
+//! Route-balancing objectives.
+//!
+//! # Algorithm
+//!
+//! Besides the total cost, an objective can rank solutions by a primary
+//! measure taken over the duration of each route (from the vehicle's shift
+//! start to its finish): the longest duration (makespan, min-max VRP), the
+//! spread between the longest and shortest duration, or the number of
+//! routes. The primary measure is compared first and the total cost breaks
+//! ties.
+//!
+//! Local search compares the changes in both as a pair. The GA and ALNS
+//! rank solutions by a single value, the primary measure weighted by an
+//! upper bound on the route cost of any solution of the instance (see
+//! [`Objective::value`]), plus the cost: a solution ahead by one unit of
+//! the primary measure then ranks first whatever the costs, while smaller
+//! differences are traded against the cost.
+//!
+//! # Complexity
+//!
+//! O(R) per evaluation, where R = number of routes; O(K × L²) once per
+//! problem for the weight, where K = vehicle types and L = locations.
+//!
+//! # Reference
+//!
+//! Golden, B. L., Laporte, G. & Taillard, É. D. (1997). "An adaptive memory
+//! heuristic for a class of vehicle routing problems with minmax objective",
+//! *Computers & Operations Research* 24(5), 445-452.
+
+use crate::distance::DistanceMatrix;
+use crate::models::{Customer, DriverBreak, TimeWindow, Vehicle};
+
+use super::{CostFunction, RouteMetrics};
+
+/// What a solver minimizes.
+///
+/// Durations are given per vehicle used: one entry per non-empty route, or
+/// per vehicle running a sequence of trips.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::evaluation::Objective;
+///
+/// // Two routes of 3 and 10 hours, total cost 50
+/// let durations = [3.0, 10.0];
+/// assert!((Objective::TotalCost.value(50.0, &durations, 1e3) - 50.0).abs() < 1e-10);
+/// assert_eq!(Objective::Makespan.primary(&durations), Some(10.0));
+/// assert_eq!(Objective::DurationSpread.primary(&durations), Some(7.0));
+/// assert_eq!(Objective::FewestRoutes.primary(&durations), Some(2.0));
+///
+/// // No solution costs more than 1,000: a shorter makespan wins whatever the cost
+/// let balanced = Objective::Makespan.value(80.0, &[6.0, 7.0], 1e3);
+/// assert!(balanced < Objective::Makespan.value(50.0, &durations, 1e3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// Total cost of the routes under the cost function, plus penalties.
+    #[default]
+    TotalCost,
+    /// Longest route duration first, then total cost.
+    Makespan,
+    /// Difference between the longest and shortest route duration first,
+    /// then total cost.
+    DurationSpread,
+    /// Number of routes first, then total cost.
+    FewestRoutes,
+}
+
+impl Objective {
+    /// Primary measure over the route `durations`, or `None` for
+    /// [`TotalCost`](Self::TotalCost).
+    pub fn primary(&self, durations: &[f64]) -> Option<f64> {
+        let longest = durations.iter().copied().fold(0.0, f64::max);
+        match self {
+            Self::TotalCost => None,
+            Self::Makespan => Some(longest),
+            Self::DurationSpread => {
+                let shortest = durations.iter().copied().fold(longest, f64::min);
+                Some(longest - shortest)
+            }
+            Self::FewestRoutes => Some(durations.len() as f64),
+        }
+    }
+
+    /// Value to minimize for a solution of total `cost` whose routes take
+    /// `durations`, with one unit of the primary measure worth `weight`.
+    ///
+    /// Choose `weight` at least as large as the cost of any solution (the
+    /// GA and ALNS derive it from the instance), so that the primary measure
+    /// ranks first.
+    pub fn value(&self, cost: f64, durations: &[f64], weight: f64) -> f64 {
+        self.primary(durations).map_or(cost, |p| p * weight + cost)
+    }
+
+    /// Change in the primary measure and the cost when the cost changes by
+    /// `cost_delta` and the route durations go from `before` to `after`.
+    ///
+    /// Differences in the primary measure below 1e-9 count as none, so that
+    /// rounding in recomputed durations does not outweigh the cost.
+    pub(crate) fn change(&self, cost_delta: f64, before: &[f64], after: &[f64]) -> Change {
+        let primary = match (self.primary(before), self.primary(after)) {
+            (Some(b), Some(a)) if (a - b).abs() > 1e-9 => a - b,
+            _ => 0.0,
+        };
+        Change {
+            primary,
+            cost: cost_delta,
+        }
+    }
+
+    /// Change in value when the cost changes by `cost_delta` and the routes
+    /// listed in `changed` take new durations, given the current duration of
+    /// every route (`None` for an empty route).
+    pub(crate) fn change_of_routes(
+        &self,
+        cost_delta: f64,
+        durations: &[Option<f64>],
+        changed: &[(usize, Option<f64>)],
+    ) -> Change {
+        let before: Vec<f64> = durations.iter().flatten().copied().collect();
+        let mut after = durations.to_vec();
+        for &(r, duration) in changed {
+            after[r] = duration;
+        }
+        let after: Vec<f64> = after.into_iter().flatten().collect();
+        self.change(cost_delta, &before, &after)
+    }
+
+    /// Weight of penalties that rank above the primary measure, such as
+    /// leaving a customer unassigned, when one unit of the primary measure
+    /// is worth `weight` (see [`value`](Self::value)).
+    pub(crate) fn penalty_weight(&self, weight: f64) -> f64 {
+        match self {
+            Self::TotalCost => 1.0,
+            _ => weight,
+        }
+    }
+}
+
+/// Change in the objective made by a move: in the primary measure (zero
+/// under [`Objective::TotalCost`]), then in the cost.
+///
+/// The derived ordering compares the primary measure first.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub(crate) struct Change {
+    pub(crate) primary: f64,
+    pub(crate) cost: f64,
+}
+
+impl Change {
+    /// A change in cost alone.
+    pub(crate) fn of_cost(cost: f64) -> Self {
+        Self { primary: 0.0, cost }
+    }
+
+    /// Returns `true` if the move lowers the primary measure, or keeps it
+    /// and lowers the cost by more than 1e-10.
+    pub(crate) fn improves(&self) -> bool {
+        self.primary < 0.0 || (self.primary == 0.0 && self.cost < -1e-10)
+    }
+}
+
+/// Weight of one unit of the primary measure in [`Objective::value`]: an
+/// upper bound on the route cost of any solution of the instance.
+///
+/// A solution has at most one route per location, and a route at most two
+/// legs per location (a charging stop between any two stops). Each route is
+/// bounded by its price under `cost` on the vehicle type for which that is
+/// highest, with every metric at its largest: the longest arc of the type's
+/// matrix on every leg, at the highest emission rate; the whole shift, or
+/// without a shift end, waiting until the last time window (or break
+/// window) closes and then the slowest travel time on every leg, every
+/// service, a break after every stop, a full charge at every leg and a
+/// reload before every stop; and
+/// the largest finite soft-window penalty of every customer. The bound
+/// holds for any cost function that does not decrease when a metric grows,
+/// as [`StandardCost`](super::StandardCost) does.
+pub(crate) fn primary_weight(
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    cost: &dyn CostFunction,
+) -> f64 {
+    let locations = customers.len() as f64;
+    let legs = 2.0 * locations;
+    let windows = customers.iter().flat_map(Customer::time_windows);
+    let penalty: f64 = customers
+        .iter()
+        .map(|c| {
+            c.time_windows()
+                .iter()
+                .map(|tw| tw.penalty(tw.earliest()).max(tw.penalty(tw.latest())))
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    let last_close = windows.map(TimeWindow::latest).fold(0.0, f64::max);
+    let service: f64 = customers.iter().map(Customer::service_duration).sum();
+    let slowest_charge = customers
+        .iter()
+        .filter_map(Customer::charge_rate)
+        .fold(f64::INFINITY, f64::min);
+
+    let route_bound = |vehicle: &Vehicle| {
+        let (longest, slowest) = distances.for_vehicle(vehicle).longest_arc();
+        let emission_rate = vehicle
+            .emission_model()
+            .map_or(0.0, |m| m.empty_rate().max(m.full_rate()));
+        let duration = match vehicle.shift_end() {
+            Some(end) => end - vehicle.shift_start(),
+            None => {
+                let (rest, rest_close) = match vehicle.driver_break() {
+                    Some(DriverBreak::Window { window, duration }) => (*duration, window.latest()),
+                    Some(b) => (b.duration(), 0.0),
+                    None => (0.0, 0.0),
+                };
+                let charge = vehicle
+                    .battery_capacity()
+                    .filter(|_| slowest_charge.is_finite())
+                    .map_or(0.0, |battery| battery / slowest_charge);
+                (last_close.max(rest_close) - vehicle.shift_start()).max(0.0)
+                    + legs * (slowest + rest + charge)
+                    + service
+                    + locations * vehicle.reload_time().unwrap_or(0.0)
+            }
+        };
+        let metrics = RouteMetrics {
+            distance: legs * longest,
+            duration,
+            emissions: legs * longest * emission_rate,
+            penalty,
+        };
+        cost.route_cost(vehicle, &metrics)
+    };
+    let route = fleet.iter().map(route_bound).fold(0.0, f64::max);
+    (locations * route).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objective_primary() {
+        let durations = [4.0, 9.0, 6.0];
+        assert_eq!(Objective::TotalCost.primary(&durations), None);
+        assert_eq!(Objective::Makespan.primary(&durations), Some(9.0));
+        assert_eq!(Objective::DurationSpread.primary(&durations), Some(5.0));
+        assert_eq!(Objective::FewestRoutes.primary(&durations), Some(3.0));
+        assert_eq!(Objective::DurationSpread.primary(&[]), Some(0.0));
+        assert_eq!(Objective::default(), Objective::TotalCost);
+    }
+
+    #[test]
+    fn test_objective_change() {
+        let objective = Objective::Makespan;
+        // Same makespan: only the cost counts
+        let same = objective.change(-2.0, &[5.0, 3.0], &[5.0, 4.0]);
+        assert!(same.primary.abs() < 1e-10 && (same.cost + 2.0).abs() < 1e-10);
+        assert!(same.improves());
+        // A longer makespan outweighs any saving, however large
+        let longer = objective.change(-1e12, &[5.0, 3.0], &[6.0, 1.0]);
+        assert!((longer.primary - 1.0).abs() < 1e-10);
+        assert!(!longer.improves());
+        assert!(same < longer);
+        // A shorter makespan wins whatever it costs
+        assert!(objective.change(1e12, &[5.0, 3.0], &[4.0, 4.0]).improves());
+        let total = Objective::TotalCost.change(1.5, &[1.0], &[9.0]);
+        assert_eq!(total, Change::of_cost(1.5));
+        // Emptying a route
+        let durations = [Some(1.0), Some(2.0), None];
+        let change = Objective::FewestRoutes.change_of_routes(5.0, &durations, &[(0, None)]);
+        assert!((change.primary + 1.0).abs() < 1e-10 && (change.cost - 5.0).abs() < 1e-10);
+        assert!(change.improves());
+        // Rounding in recomputed durations is no change
+        assert!(Objective::Makespan
+            .change(-1.0, &[5.0], &[5.0 + 1e-12])
+            .improves());
+    }
+
+    #[test]
+    fn test_primary_weight_bounds_solution_cost() {
+        use crate::constructive::nearest_neighbor;
+        use crate::evaluation::{RouteEvaluator, StandardCost};
+        use crate::models::Vehicle;
+
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 300.0, 0.0, 10, 5.0).with_time_window(
+                TimeWindow::new(0.0, 50.0)
+                    .expect("valid")
+                    .with_soft_late(1e3, 100.0),
+            ),
+            Customer::new(2, 0.0, 400.0, 10, 5.0),
+            Customer::new(3, -200.0, -100.0, 10, 5.0),
+        ];
+        let distances = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 10)
+            .with_fixed_cost(1e5)
+            .with_cost_per_distance(3.0);
+        let fleet = std::slice::from_ref(&vehicle);
+        let weight = primary_weight(&customers, &distances, fleet, &StandardCost);
+        // One customer per route: the costliest solution
+        let solution = nearest_neighbor(&customers, &distances, &vec![vehicle.clone(); 3]);
+        assert_eq!(solution.num_routes(), 3);
+        let (cost, _) =
+            RouteEvaluator::new(&customers, &distances, &vehicle).evaluate_solution(&solution);
+        assert!(cost > 3e5);
+        assert!(weight > cost);
+    }
+}
@@ -17,12 +17,17 @@ use std::panic;
 #[cfg(feature = "ffi")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ffi")]
+use u_metaheur::alns::AlnsProblem;
+
+#[cfg(feature = "ffi")]
+use crate::alns::{RoutingAlnsProblem, RoutingSolution};
 #[cfg(feature = "ffi")]
 use crate::constructive::{clarke_wright_savings, nearest_neighbor};
 #[cfg(feature = "ffi")]
-use crate::distance::DistanceMatrix;
+use crate::distance::{DistanceMatrix, Metric};
 #[cfg(feature = "ffi")]
-use crate::models::{Customer, TimeWindow, Vehicle};
+use crate::models::{fleet_index, Customer, TimeWindow, Vehicle};
 
 // ── Types ───────────────────────────────────────────────────
 
@@ -45,6 +50,15 @@ struct InputCustomer {
 struct InputVehicle {
     #[serde(default = "default_capacity")]
     capacity: f64,
+    #[serde(default)]
+    fixed_cost: f64,
+    #[serde(default = "default_cost_per_distance")]
+    cost_per_distance: f64,
+    #[serde(default)]
+    cost_per_time: f64,
+    /// Optional overtime as `[regular_time, extra_cost_per_time]`.
+    #[serde(default)]
+    overtime: Option<[f64; 2]>,
 }
 
 #[cfg(feature = "ffi")]
@@ -52,6 +66,11 @@ fn default_capacity() -> f64 {
     1e9
 }
 
+#[cfg(feature = "ffi")]
+fn default_cost_per_distance() -> f64 {
+    1.0
+}
+
 #[cfg(feature = "ffi")]
 #[derive(Deserialize)]
 struct VrpInput {
@@ -62,6 +81,13 @@ struct VrpInput {
     depot_y: f64,
     #[serde(default = "default_method")]
     method: String,
+    /// Distance metric: "euclidean" (default), "manhattan", "chebyshev",
+    /// "euc_2d", "ceil_2d" or "geo"; any other name is an error.
+    #[serde(default = "default_metric")]
+    metric: String,
+    /// Cost of each customer left unrouted in `total_cost` (default: 10,000).
+    #[serde(default)]
+    unassigned_penalty: Option<f64>,
 }
 
 #[cfg(feature = "ffi")]
@@ -69,11 +95,17 @@ fn default_method() -> String {
     "nn".to_string()
 }
 
+#[cfg(feature = "ffi")]
+fn default_metric() -> String {
+    "euclidean".to_string()
+}
+
 #[cfg(feature = "ffi")]
 #[derive(Serialize)]
 struct VrpOutput {
     routes: Vec<Vec<usize>>,
     total_distance: f64,
+    total_cost: f64,
     num_vehicles: usize,
     method_used: String,
 }
@@ -153,7 +185,8 @@ fn solve_internal(input: &VrpInput) -> Result<VrpOutput, String> {
         customers.push(c);
     }
 
-    let dm = DistanceMatrix::from_customers(&customers);
+    let metric: Metric = input.metric.parse()?;
+    let dm = DistanceMatrix::from_customers_with(&customers, metric);
 
     let cap = input
         .vehicles
@@ -168,17 +201,51 @@ fn solve_internal(input: &VrpInput) -> Result<VrpOutput, String> {
             .vehicles
             .iter()
             .enumerate()
-            .map(|(i, v)| Vehicle::new(i, v.capacity.round() as i32))
+            .map(|(i, v)| {
+                let mut vehicle = Vehicle::new(i, v.capacity.round() as i32)
+                    .with_fixed_cost(v.fixed_cost)
+                    .with_cost_per_distance(v.cost_per_distance)
+                    .with_cost_per_time(v.cost_per_time);
+                if let Some([regular_time, cost_per_time]) = v.overtime {
+                    vehicle = vehicle.with_overtime(regular_time, cost_per_time);
+                }
+                // Each input vehicle drives one route
+                vehicle.with_available(1)
+            })
             .collect()
     };
 
     let method = input.method.to_lowercase();
 
     let solution = match method.as_str() {
-        "savings" => clarke_wright_savings(&customers, &dm, &vehicles[0]),
+        "savings" => clarke_wright_savings(&customers, &dm, &vehicles),
         _ => nearest_neighbor(&customers, &dm, &vehicles),
     };
 
+    // Standard cost of each route on its vehicle type, plus the penalty
+    // of every customer left unrouted
+    let route_vehicles = solution
+        .routes()
+        .iter()
+        .map(|r| {
+            fleet_index(&vehicles, r.vehicle_id())
+                .ok_or_else(|| format!("route uses unknown vehicle {}", r.vehicle_id()))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    let mut problem =
+        RoutingAlnsProblem::new(customers.clone(), dm.clone(), 0).with_fleet(&vehicles);
+    if let Some(penalty) = input.unassigned_penalty {
+        problem = problem.with_unassigned_penalty(penalty);
+    }
+    let priced = RoutingSolution::new(
+        solution.routes().iter().map(|r| r.customer_ids()).collect(),
+        solution.unassigned().to_vec(),
+        &customers,
+        &dm,
+    )
+    .with_vehicles(route_vehicles);
+    let total_cost = problem.cost(&priced);
+
     // Extract routes with original customer IDs
     let routes: Vec<Vec<usize>> = solution
         .routes()
@@ -199,6 +266,7 @@ fn solve_internal(input: &VrpInput) -> Result<VrpOutput, String> {
 
     Ok(VrpOutput {
         total_distance: solution.total_cost(),
+        total_cost,
         num_vehicles: routes.len(),
         routes,
         method_used: method,
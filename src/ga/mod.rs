@@ -2,15 +2,23 @@
 //!
 //! - [`GiantTour`] — Permutation chromosome encoding all customers
 //! - [`split()`] — Prins (2004) split DP to partition giant tour into routes
+//!   ([`split_from_depot()`] for a depot other than location 0)
 //! - [`split_tw()`] — Time-window-aware split for VRPTW
+//!   ([`split_tw_for_vehicle()`] for shifts and driver breaks)
+//! - [`split_fleet()`] — Heterogeneous-fleet split (Prins 2009)
 //! - [`RoutingGaProblem`] — [`GaProblem`](u_metaheur::ga::GaProblem) implementation
+//! - [`nsga2()`] — Multi-objective GA (Deb et al. 2002) returning a Pareto front
 
 mod chromosome;
+pub mod nsga2;
 mod problem;
 pub mod split;
+pub mod split_fleet;
 pub mod split_tw;
 
 pub use chromosome::GiantTour;
+pub use nsga2::{nsga2, Criterion, ParetoSolution};
 pub use problem::RoutingGaProblem;
-pub use split::split;
-pub use split_tw::split_tw;
+pub use split::{split, split_from_depot};
+pub use split_fleet::split_fleet;
+pub use split_tw::{split_tw, split_tw_for_vehicle, split_tw_from_depot};
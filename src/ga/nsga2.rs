@@ -0,0 +1,422 @@
+//! Multi-objective GA (NSGA-II) returning a Pareto front of solutions.
+//!
+//! # Algorithm
+//!
+//! Individuals are giant tours decoded as in [`RoutingGaProblem`] (split,
+//! optional 2-opt) and measured on several [`Criterion`]s instead of one
+//! fitness. Each generation, parents are picked by binary tournament on
+//! (Pareto rank, crowding distance), offspring are produced with the
+//! problem's crossover and mutation, and the next population keeps the best
+//! fronts of parents and offspring together, the last front admitted by
+//! decreasing crowding distance. The first front of the final population,
+//! without duplicate objective vectors, is returned.
+//!
+//! The split still builds the routes of least cost for each tour: the
+//! trade-offs on the front are between tours. Tours the fleet cannot serve
+//! are dominated by every servable tour and never returned.
+//!
+//! # Complexity
+//!
+//! O(G × (N × D + M × N²)) where G = generations, N = population size,
+//! D = cost of decoding a tour and M = number of criteria.
+//!
+//! # Reference
+//!
+//! Deb, K., Pratap, A., Agarwal, S. & Meyarivan, T. (2002). "A fast and
+//! elitist multiobjective genetic algorithm: NSGA-II", *IEEE Transactions on
+//! Evolutionary Computation* 6(2), 182-197.
+
+use std::collections::HashSet;
+
+use rand::Rng;
+use u_metaheur::ga::multi_objective::{crowding_distance, non_dominated_sort};
+use u_metaheur::ga::{GaConfig, GaProblem};
+use u_numflow::random::create_rng;
+
+use crate::models::{Customer, Solution};
+
+use super::chromosome::GiantTour;
+use super::problem::RoutingGaProblem;
+
+/// A quantity to minimize in multi-objective optimization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// Number of vehicles used: one per route, or per vehicle running a
+    /// sequence of trips.
+    Vehicles,
+    /// Total distance.
+    Distance,
+    /// Total time by which service starts after the due time of the
+    /// customers' windows (see [`Customer::lateness`]).
+    Lateness,
+    /// Total cost under the problem's cost function.
+    Cost,
+    /// Total emissions.
+    Emissions,
+}
+
+impl Criterion {
+    /// Value of this criterion for `solution`, whose routes visit
+    /// `customers`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::evaluation::RouteEvaluator;
+    /// use u_routing::ga::Criterion;
+    /// use u_routing::models::{Solution, TimeWindow};
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 3.0, 4.0, 10, 0.0)
+    ///         .with_time_window(TimeWindow::new(0.0, 2.0).unwrap()),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let van = Vehicle::new(0, 100);
+    /// let mut solution = Solution::new();
+    /// solution.add_route(RouteEvaluator::new(&customers, &dm, &van).build_route(&[1]).0);
+    ///
+    /// assert_eq!(Criterion::Vehicles.measure(&solution, &customers), 1.0);
+    /// assert!((Criterion::Distance.measure(&solution, &customers) - 10.0).abs() < 1e-10);
+    /// // Reached at 5, due at 2
+    /// assert!((Criterion::Lateness.measure(&solution, &customers) - 3.0).abs() < 1e-10);
+    /// ```
+    pub fn measure(&self, solution: &Solution, customers: &[Customer]) -> f64 {
+        match self {
+            Self::Vehicles => vehicles_used(solution) as f64,
+            Self::Distance => solution.total_distance(),
+            Self::Lateness => solution
+                .routes()
+                .iter()
+                .flat_map(|route| route.visits())
+                .map(|v| customers[v.customer_id].lateness(v.arrival_time))
+                .sum(),
+            Self::Cost => solution.total_cost(),
+            Self::Emissions => solution.total_emissions(),
+        }
+    }
+}
+
+/// A non-dominated solution and its value on each criterion.
+#[derive(Debug, Clone)]
+pub struct ParetoSolution {
+    /// The decoded solution.
+    pub solution: Solution,
+    /// Value of each criterion, in the order they were given.
+    pub objectives: Vec<f64>,
+}
+
+/// A member of the population with its decoded solution, if servable.
+struct Member {
+    tour: GiantTour,
+    solution: Option<Solution>,
+    objectives: Vec<f64>,
+}
+
+/// Runs NSGA-II over giant tours of `problem`, minimizing every criterion.
+///
+/// Uses the population size, number of generations, crossover and mutation
+/// rates and seed of `config`; selection, elitism and stagnation settings
+/// do not apply. The problem's objective plays no part, but its cost
+/// function defines [`Criterion::Cost`].
+///
+/// Returns the non-dominated solutions found, sorted by their objective
+/// vectors.
+///
+/// # Errors
+///
+/// Returns an error if `criteria` is empty or `config` is invalid.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Customer;
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::ga::{nsga2, Criterion, RoutingGaProblem};
+/// use u_metaheur::ga::GaConfig;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+///     Customer::new(2, 2.0, 0.0, 10, 0.0),
+///     Customer::new(3, 0.0, 2.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let problem = RoutingGaProblem::new(customers, dm, 20);
+/// let config = GaConfig::default()
+///     .with_population_size(20)
+///     .with_max_generations(20)
+///     .with_seed(42);
+///
+/// let front = nsga2(&problem, &[Criterion::Vehicles, Criterion::Distance], &config).unwrap();
+/// assert!(!front.is_empty());
+/// assert!(front.iter().all(|p| p.solution.num_served() == 3));
+/// ```
+pub fn nsga2(
+    problem: &RoutingGaProblem,
+    criteria: &[Criterion],
+    config: &GaConfig,
+) -> Result<Vec<ParetoSolution>, String> {
+    if criteria.is_empty() {
+        return Err("criteria must not be empty".to_string());
+    }
+    config.validate()?;
+
+    let mut rng = match config.seed {
+        Some(seed) => create_rng(seed),
+        None => create_rng(rand::random()),
+    };
+    let size = config.population_size;
+    let decode = |tour: GiantTour| {
+        let solution = problem.decode(&tour);
+        let objectives = match &solution {
+            Some(s) => criteria
+                .iter()
+                .map(|c| c.measure(s, problem.customers()))
+                .collect(),
+            None => vec![f64::INFINITY; criteria.len()],
+        };
+        Member {
+            tour,
+            solution,
+            objectives,
+        }
+    };
+
+    let mut population: Vec<Member> = (0..size)
+        .map(|_| decode(problem.create_individual(&mut rng)))
+        .collect();
+
+    for _ in 0..config.max_generations {
+        let (ranks, crowding) = rank(&population);
+        let mut offspring = Vec::with_capacity(size);
+        while offspring.len() < size {
+            let p1 = &population[tournament(&ranks, &crowding, &mut rng)].tour;
+            let p2 = &population[tournament(&ranks, &crowding, &mut rng)].tour;
+            let children = if rng.random_range(0.0..1.0) < config.crossover_rate {
+                problem.crossover(p1, p2, &mut rng)
+            } else {
+                vec![p1.clone()]
+            };
+            for mut child in children {
+                if offspring.len() == size {
+                    break;
+                }
+                if rng.random_range(0.0..1.0) < config.mutation_rate {
+                    problem.mutate(&mut child, &mut rng);
+                }
+                offspring.push(decode(child));
+            }
+        }
+
+        population.extend(offspring);
+        population = survivors(population, size);
+    }
+
+    Ok(pareto_front(population))
+}
+
+/// Binary tournament: the lower rank wins, then the larger crowding
+/// distance.
+fn tournament<R: Rng>(ranks: &[usize], crowding: &[f64], rng: &mut R) -> usize {
+    let a = rng.random_range(0..ranks.len() as u64) as usize;
+    let b = rng.random_range(0..ranks.len() as u64) as usize;
+    if ranks[a] < ranks[b] || (ranks[a] == ranks[b] && crowding[a] > crowding[b]) {
+        a
+    } else {
+        b
+    }
+}
+
+/// Pareto rank and crowding distance (within its front) of every member.
+fn rank(population: &[Member]) -> (Vec<usize>, Vec<f64>) {
+    let objectives: Vec<Vec<f64>> = population.iter().map(|m| m.objectives.clone()).collect();
+    let sorted = non_dominated_sort(&objectives);
+    let mut crowding = vec![0.0; population.len()];
+    for front in &sorted.fronts {
+        let values: Vec<Vec<f64>> = front.iter().map(|&i| objectives[i].clone()).collect();
+        for (&i, d) in front.iter().zip(crowding_distance(&values)) {
+            crowding[i] = d;
+        }
+    }
+    (sorted.ranks, crowding)
+}
+
+/// The `size` members of the best fronts, the last front cut by crowding
+/// distance.
+fn survivors(population: Vec<Member>, size: usize) -> Vec<Member> {
+    let objectives: Vec<Vec<f64>> = population.iter().map(|m| m.objectives.clone()).collect();
+    let sorted = non_dominated_sort(&objectives);
+    let mut members: Vec<Option<Member>> = population.into_iter().map(Some).collect();
+    let mut next = Vec::with_capacity(size);
+    for front in sorted.fronts {
+        let room = size - next.len();
+        if room == 0 {
+            break;
+        }
+        let mut front = front;
+        if front.len() > room {
+            let values: Vec<Vec<f64>> = front.iter().map(|&i| objectives[i].clone()).collect();
+            let crowding = crowding_distance(&values);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.sort_by(|&a, &b| crowding[b].total_cmp(&crowding[a]));
+            front = order.into_iter().take(room).map(|p| front[p]).collect();
+        }
+        next.extend(front.into_iter().filter_map(|i| members[i].take()));
+    }
+    next
+}
+
+/// Servable members of the first front, one per objective vector, sorted.
+fn pareto_front(population: Vec<Member>) -> Vec<ParetoSolution> {
+    let objectives: Vec<Vec<f64>> = population.iter().map(|m| m.objectives.clone()).collect();
+    let sorted = non_dominated_sort(&objectives);
+    let first: HashSet<usize> = sorted.fronts[0].iter().copied().collect();
+
+    let mut front: Vec<ParetoSolution> = Vec::new();
+    for (i, member) in population.into_iter().enumerate() {
+        let Some(solution) = member.solution else {
+            continue;
+        };
+        if first.contains(&i) && !front.iter().any(|p| p.objectives == member.objectives) {
+            front.push(ParetoSolution {
+                solution,
+                objectives: member.objectives,
+            });
+        }
+    }
+    front.sort_by(|a, b| {
+        a.objectives
+            .iter()
+            .zip(&b.objectives)
+            .map(|(x, y)| x.total_cmp(y))
+            .find(|o| o.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    front
+}
+
+/// Number of vehicles running the routes of `solution`: routes sharing a
+/// vehicle index on the same vehicle type count once.
+fn vehicles_used(solution: &Solution) -> usize {
+    let mut shared = HashSet::new();
+    solution
+        .routes()
+        .iter()
+        .filter(|r| !r.is_empty())
+        .filter(|r| match r.vehicle_index() {
+            Some(index) => shared.insert((r.vehicle_id(), index)),
+            None => true,
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::DistanceMatrix;
+    use crate::models::{TimeWindow, Vehicle};
+
+    fn config() -> GaConfig {
+        GaConfig::default()
+            .with_population_size(20)
+            .with_max_generations(30)
+            .with_parallel(false)
+            .with_seed(7)
+    }
+
+    #[test]
+    fn test_nsga2_distance_lateness_front() {
+        // An open route reaches customer 2 on time only by driving past 1
+        let due = TimeWindow::new(0.0, 10.0)
+            .expect("valid")
+            .with_soft_late(100.0, 0.01);
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, -1.0, 0.0, 10, 0.0),
+            Customer::new(2, 10.0, 0.0, 10, 0.0).with_time_window(due),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100)
+            .with_fixed_cost(100.0)
+            .with_open_route();
+        let problem = RoutingGaProblem::new(customers, dm, 0)
+            .with_fleet(&[van])
+            .without_local_search();
+
+        let front = nsga2(
+            &problem,
+            &[Criterion::Distance, Criterion::Lateness],
+            &config(),
+        )
+        .expect("valid");
+        let objectives: Vec<&[f64]> = front.iter().map(|p| p.objectives.as_slice()).collect();
+        assert_eq!(objectives, vec![&[12.0, 2.0][..], &[21.0, 0.0][..]]);
+        assert!(front.iter().all(|p| p.solution.num_routes() == 1));
+    }
+
+    #[test]
+    fn test_nsga2_front_is_non_dominated() {
+        let mut customers = vec![Customer::depot(0.0, 0.0)];
+        for i in 1..=8 {
+            let angle = i as f64;
+            customers.push(Customer::new(
+                i,
+                10.0 * angle.cos(),
+                10.0 * angle.sin(),
+                10,
+                1.0,
+            ));
+        }
+        let dm = DistanceMatrix::from_customers(&customers);
+        let criteria = [Criterion::Vehicles, Criterion::Distance, Criterion::Cost];
+        let problem = RoutingGaProblem::new(customers.clone(), dm, 30);
+
+        let front = nsga2(&problem, &criteria, &config()).expect("valid");
+        assert!(!front.is_empty());
+        for p in &front {
+            assert_eq!(p.solution.num_served(), 8);
+            for (c, &value) in criteria.iter().zip(&p.objectives) {
+                assert!((c.measure(&p.solution, &customers) - value).abs() < 1e-10);
+            }
+            let dominated = front.iter().any(|q| {
+                q.objectives.iter().zip(&p.objectives).all(|(a, b)| a <= b)
+                    && q.objectives != p.objectives
+            });
+            assert!(!dominated);
+        }
+    }
+
+    #[test]
+    fn test_nsga2_rejects_empty_criteria() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let problem = RoutingGaProblem::new(customers, dm, 30);
+        assert!(nsga2(&problem, &[], &config()).is_err());
+        let bad = config().with_population_size(0);
+        assert!(nsga2(&problem, &[Criterion::Distance], &bad).is_err());
+    }
+
+    #[test]
+    fn test_vehicles_used_multi_trip() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Capacity 10: two trips, run by one reloading van
+        let van = Vehicle::new(0, 10).with_reload_time(0.0);
+        let problem = RoutingGaProblem::new(customers.clone(), dm, 0).with_fleet(&[van]);
+        let solution = problem
+            .decode(&GiantTour::new(vec![1, 2]))
+            .expect("servable");
+        assert_eq!(solution.num_routes(), 2);
+        assert_eq!(Criterion::Vehicles.measure(&solution, &customers), 1.0);
+    }
+}
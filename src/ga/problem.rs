@@ -7,23 +7,64 @@
 //!
 //! - **Crossover**: Order crossover (OX) — preserves relative customer ordering
 //! - **Mutation**: Swap + invert (2-opt) with equal probability
-//! - **Evaluation**: Split DP → local search (optional 2-opt) → total distance
+//! - **Evaluation**: Split DP → local search (optional 2-opt) → total cost
+//!
+//! With a heterogeneous fleet ([`RoutingGaProblem::with_fleet`]) evaluation
+//! uses the heterogeneous split ([`split_fleet`]), and the cost
+//! of each route is its vehicle's fixed cost plus distance cost, plus any
+//! soft time-window penalty. For the default single vehicle type without
+//! soft windows this equals the total distance. A custom [`CostFunction`]
+//! ([`RoutingGaProblem::with_cost_function`]) prices the routes the split
+//! builds instead. Vehicle types
+//! may be based at different depots (multi-depot VRP): the split then also
+//! chooses the depot serving each route, and depot locations are never part
+//! of the giant tour.
+//!
+//! The routes of a [multi-trip](crate::models::Vehicle::with_reload_time)
+//! vehicle type are trips: after the split they are packed onto vehicles
+//! ([`RouteEvaluator::pack_trips`]), and the fixed cost is charged once per
+//! vehicle, so fewer vehicles running more trips are preferred. The
+//! availability of such a type limits the vehicles its trips are packed
+//! onto, not the number of trips.
+//!
+//! With a route-balancing [`Objective`] ([`RoutingGaProblem::with_objective`])
+//! fitness ranks tours by makespan, spread of route durations or number of
+//! routes before their cost.
+//!
+//! [Optional](crate::models::Customer::with_optional) customers
+//! (prize-collecting VRP) are dropped from the routes of the split, most
+//! profitable first, while leaving one out saves more route cost than the
+//! prize it forfeits; the fitness charges the forfeited prizes.
+//!
+//! Pickup-and-delivery requests ([`RequestRole`]) are served by one route,
+//! pickup first: before the split, the two halves of a request whose
+//! delivery comes first in the giant tour swap places, and the split only
+//! builds routes holding both halves of every request they touch.
 //!
 //! # Reference
 //!
 //! Prins, C. (2004). "A simple and effective evolutionary algorithm for the
 //! vehicle routing problem", *Computers & Operations Research* 31(12), 1985-2002.
 
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+
 use rand::Rng;
 use u_metaheur::ga::operators::{invert_mutation, order_crossover, swap_mutation};
 use u_metaheur::ga::GaProblem;
 
 use crate::distance::DistanceMatrix;
+use crate::evaluation::{
+    insert_charging_stops, priced_route, primary_weight, CostFunction, Objective, RouteEvaluator,
+    StandardCost,
+};
 use crate::local_search::two_opt_improve;
-use crate::models::Customer;
+use crate::models::{
+    has_load_profile, precedence_holds, stop_ids, Customer, Load, RequestRole, Solution, Vehicle,
+};
 
 use super::chromosome::GiantTour;
-use super::split::split;
+use super::split_fleet::{split_fleet, FleetSplitResult};
 
 /// GA problem for capacitated vehicle routing.
 ///
@@ -58,8 +99,13 @@ use super::split::split;
 pub struct RoutingGaProblem {
     customers: Vec<Customer>,
     distances: DistanceMatrix,
-    capacity: i32,
+    fleet: Vec<Vehicle>,
+    stops: Vec<usize>,
     apply_local_search: bool,
+    objective: Objective,
+    cost_function: Option<Arc<dyn CostFunction>>,
+    /// Weight of the objective's primary measure, computed on first use.
+    primary_weight: OnceLock<f64>,
 }
 
 impl RoutingGaProblem {
@@ -72,22 +118,291 @@ impl RoutingGaProblem {
     /// * `capacity` — Vehicle capacity
     pub fn new(customers: Vec<Customer>, distances: DistanceMatrix, capacity: i32) -> Self {
         Self {
+            stops: stop_ids(&customers).collect(),
             customers,
             distances,
-            capacity,
+            fleet: vec![Vehicle::new(0, capacity)],
             apply_local_search: true,
+            objective: Objective::TotalCost,
+            cost_function: None,
+            primary_weight: OnceLock::new(),
         }
     }
 
+    /// Sets a multi-dimensional vehicle capacity, replacing the one given to
+    /// [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.fleet = vec![Vehicle::new(0, 0).with_capacities(capacities)];
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
+    /// Uses a heterogeneous fleet, replacing the capacity given to
+    /// [`new`](Self::new).
+    ///
+    /// Each entry is a vehicle type with its own capacity, costs and
+    /// optional availability limit.
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.fleet = fleet.to_vec();
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
     /// Disables intra-route 2-opt local search during evaluation.
     pub fn without_local_search(mut self) -> Self {
         self.apply_local_search = false;
         self
     }
 
-    /// Returns the number of customers (excluding depot).
-    fn num_customers(&self) -> usize {
-        self.customers.len() - 1
+    /// Sets what the fitness minimizes ([`Objective::TotalCost`] by default).
+    ///
+    /// The split still builds routes of least cost; the objective ranks the
+    /// resulting solutions.
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Prices routes with `cost` instead of [`StandardCost`].
+    ///
+    /// The split still builds routes of least standard cost; the cost
+    /// function decides the fitness.
+    pub fn with_cost_function(mut self, cost: impl CostFunction + 'static) -> Self {
+        self.cost_function = Some(Arc::new(cost));
+        self.primary_weight = OnceLock::new();
+        self
+    }
+
+    /// Weight of one unit of the objective's primary measure: an upper
+    /// bound on the route cost of any solution (see [`Objective::value`]).
+    fn primary_weight(&self) -> f64 {
+        *self.primary_weight.get_or_init(|| {
+            let cost = self.cost_function.as_deref().unwrap_or(&StandardCost);
+            primary_weight(&self.customers, &self.distances, &self.fleet, cost)
+        })
+    }
+
+    /// All locations (index 0 = depot).
+    pub(crate) fn customers(&self) -> &[Customer] {
+        &self.customers
+    }
+
+    /// Decodes a giant tour into the solution its fitness is computed on, or
+    /// `None` if the fleet cannot serve the tour.
+    ///
+    /// Routes are split and improved as in evaluation; the trips of a
+    /// multi-trip vehicle type are packed onto vehicles
+    /// ([`Route::vehicle_index`](crate::models::Route::vehicle_index)), and
+    /// the tour cannot be served if they need more vehicles than the type
+    /// has available.
+    /// Optional customers not worth their routing cost are unassigned. The
+    /// solution's total cost is the cost of its routes plus the forfeited
+    /// prizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::ga::{GiantTour, RoutingGaProblem};
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+    ///     Customer::new(2, 2.0, 0.0, 10, 0.0),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let problem = RoutingGaProblem::new(customers, dm, 10);
+    ///
+    /// let solution = problem.decode(&GiantTour::new(vec![1, 2])).unwrap();
+    /// assert_eq!(solution.num_routes(), 2);
+    /// assert!((solution.total_cost() - 6.0).abs() < 1e-10);
+    /// ```
+    pub fn decode(&self, individual: &GiantTour) -> Option<Solution> {
+        let result = split_fleet(
+            &self.pickups_first(individual.customers()),
+            &self.customers,
+            &self.distances,
+            &self.fleet,
+        );
+        if !result.total_cost.is_finite() {
+            return None;
+        }
+
+        let cost = self.cost_function.as_deref().unwrap_or(&StandardCost);
+        let mut solution = Solution::new();
+        let (routes, dropped) = self.improve_routes(&result);
+        let mut total = self.dropped_cost(&dropped);
+        for cid in dropped {
+            solution.add_unassigned(cid);
+        }
+        let mut trips: Vec<Vec<Vec<usize>>> = vec![Vec::new(); self.fleet.len()];
+        for (k, route) in routes {
+            let vehicle = &self.fleet[k];
+            if vehicle.is_multi_trip() {
+                trips[k].push(route);
+            } else {
+                let evaluator = RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                    .with_cost_function(cost);
+                total += evaluator.route_cost(&route);
+                solution.add_route(evaluator.build_route(&route).0);
+            }
+        }
+        for (vehicle, trips) in self.fleet.iter().zip(&trips) {
+            let evaluator = RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                .with_cost_function(cost);
+            let packed = evaluator.pack_trips(trips);
+            if vehicle.available().is_some_and(|n| packed.len() > n) {
+                return None;
+            }
+            for (index, group) in packed.into_iter().enumerate() {
+                let run: Vec<Vec<usize>> = group.iter().map(|&t| trips[t].clone()).collect();
+                let refs: Vec<&[usize]> = run.iter().map(Vec::as_slice).collect();
+                total += evaluator.trips_cost(&refs);
+                for mut route in evaluator.build_trips(&run).0 {
+                    route.set_vehicle_index(index);
+                    solution.add_route(route);
+                }
+            }
+        }
+        solution.set_total_cost(total);
+        Some(solution)
+    }
+
+    /// `tour` with the pickup and delivery of every request whose delivery
+    /// comes first swapped, so that the split can serve it.
+    fn pickups_first<'t>(&self, tour: &'t [usize]) -> Cow<'t, [usize]> {
+        if !tour
+            .iter()
+            .any(|&cid| self.customers[cid].request_role().is_some())
+        {
+            return Cow::Borrowed(tour);
+        }
+        let mut tour = tour.to_vec();
+        let mut positions = vec![0; self.customers.len()];
+        for (pos, &cid) in tour.iter().enumerate() {
+            positions[cid] = pos;
+        }
+        for pos in 0..tour.len() {
+            if let Some(RequestRole::Delivery { pickup_id }) =
+                self.customers[tour[pos]].request_role()
+            {
+                if positions[pickup_id] > pos {
+                    tour.swap(pos, positions[pickup_id]);
+                }
+            }
+        }
+        Cow::Owned(tour)
+    }
+
+    /// Routes of a split, each with the index of its vehicle type, after
+    /// intra-route 2-opt (if enabled) and dropping unprofitable optional
+    /// customers, and the dropped customers.
+    fn improve_routes(&self, result: &FleetSplitResult) -> (Vec<(usize, Vec<usize>)>, Vec<usize>) {
+        let mut routes = Vec::with_capacity(result.routes.len());
+        let mut dropped = Vec::new();
+        let profiled = has_load_profile(&self.customers);
+        for (route, &k) in result.routes.iter().zip(&result.vehicles) {
+            let vehicle = &self.fleet[k];
+            let distances = self.distances.for_vehicle(vehicle);
+            let mut improved = if self.apply_local_search {
+                two_opt_improve(route, vehicle.route_ends(), distances).0
+            } else {
+                route.clone()
+            };
+            // 2-opt reverses segments by distance alone: keep the split's
+            // route if the reversal breaks a constraint the split checked
+            if self.apply_local_search && !self.still_feasible(&improved, vehicle, profiled) {
+                improved = route.clone();
+            }
+            // 2-opt moves charging stops around: plan them again, keeping
+            // the split's route if that is shorter
+            if vehicle.is_electric() && self.apply_local_search {
+                let ends = vehicle.route_ends();
+                improved = insert_charging_stops(&improved, &self.customers, distances, vehicle)
+                    .filter(|r| ends.distance(r, distances) < ends.distance(route, distances))
+                    .unwrap_or_else(|| route.clone());
+            }
+            self.drop_unprofitable(&mut improved, vehicle, &mut dropped);
+            if !improved.is_empty() {
+                routes.push((k, improved));
+            }
+        }
+        (routes, dropped)
+    }
+
+    /// Returns `true` if `route` keeps the on-board load within capacity at
+    /// every point and each delivery after its pickup (checked only if
+    /// `profiled`, see [`has_load_profile`]), and reaches every customer
+    /// within a time window and finishes within the shift. Electric routes
+    /// are checked once their charging stops are planned.
+    fn still_feasible(&self, route: &[usize], vehicle: &Vehicle, profiled: bool) -> bool {
+        if profiled
+            && !(vehicle.can_carry(&Load::peak_of_route(route, &self.customers))
+                && precedence_holds(route, &self.customers))
+        {
+            return false;
+        }
+        let evaluator = RouteEvaluator::new(&self.customers, &self.distances, vehicle);
+        vehicle.is_electric()
+            || (evaluator.within_shift(route) && evaluator.time_window_penalty(route).is_finite())
+    }
+
+    /// Removes optional customers from `route`, most profitable first, while
+    /// leaving one out saves more route cost than it forfeits. Halves of a
+    /// pickup-and-delivery request are kept.
+    fn drop_unprofitable(
+        &self,
+        route: &mut Vec<usize>,
+        vehicle: &Vehicle,
+        dropped: &mut Vec<usize>,
+    ) {
+        let droppable = |cid: usize| {
+            let customer = &self.customers[cid];
+            customer.is_optional() && customer.request_role().is_none()
+        };
+        if !route.iter().any(|&cid| droppable(cid)) {
+            return;
+        }
+        let cost = self.cost_function.as_deref().unwrap_or(&StandardCost);
+        let price =
+            |r: &[usize]| priced_route(r, &self.customers, &self.distances, vehicle, cost).0;
+        loop {
+            let current = price(route);
+            let best = route
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cid)| droppable(cid))
+                .map(|(pos, &cid)| {
+                    let mut shorter = route.clone();
+                    shorter.remove(pos);
+                    let forfeited = cost.unassigned_cost(&self.customers[cid], 0.0);
+                    (pos, current - price(&shorter) - forfeited)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((pos, gain)) if gain > 1e-10 => dropped.push(route.remove(pos)),
+                _ => break,
+            }
+        }
+    }
+
+    /// Cost of leaving the `dropped` optional customers unserved.
+    fn dropped_cost(&self, dropped: &[usize]) -> f64 {
+        let cost = self.cost_function.as_deref().unwrap_or(&StandardCost);
+        dropped
+            .iter()
+            .map(|&cid| cost.unassigned_cost(&self.customers[cid], 0.0))
+            .sum()
+    }
+
+    /// Position of each stop in `stops`, indexed by customer ID.
+    fn stop_positions(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.customers.len()];
+        for (p, &cid) in self.stops.iter().enumerate() {
+            positions[cid] = p;
+        }
+        positions
     }
 }
 
@@ -95,8 +410,7 @@ impl GaProblem for RoutingGaProblem {
     type Individual = GiantTour;
 
     fn create_individual<R: Rng>(&self, rng: &mut R) -> GiantTour {
-        let n = self.num_customers();
-        let mut perm: Vec<usize> = (1..=n).collect();
+        let mut perm = self.stops.clone();
 
         // Fisher-Yates shuffle
         for i in (1..perm.len()).rev() {
@@ -108,24 +422,65 @@ impl GaProblem for RoutingGaProblem {
     }
 
     fn evaluate(&self, individual: &GiantTour) -> f64 {
-        let result = split(
-            individual.customers(),
+        let result = split_fleet(
+            &self.pickups_first(individual.customers()),
             &self.customers,
             &self.distances,
-            self.capacity,
+            &self.fleet,
         );
 
-        if !self.apply_local_search {
-            return result.total_distance;
+        let multi_trip = self.fleet.iter().any(Vehicle::is_multi_trip);
+        let plain = !self.apply_local_search
+            && !multi_trip
+            && self.objective == Objective::TotalCost
+            && self.cost_function.is_none()
+            && !self.customers.iter().any(Customer::is_optional);
+        if !result.total_cost.is_finite() || plain {
+            return result.total_cost;
         }
 
-        // Apply 2-opt to each route
+        // Trips of multi-trip types are packed onto vehicles afterwards
+        let (routes, dropped) = self.improve_routes(&result);
         let mut total = 0.0;
-        for route in &result.routes {
-            let (_, dist) = two_opt_improve(route, 0, &self.distances);
-            total += dist;
+        let mut durations = Vec::new();
+        let mut trips: Vec<Vec<Vec<usize>>> = vec![Vec::new(); self.fleet.len()];
+        let cost = self.cost_function.as_deref().unwrap_or(&StandardCost);
+        for (k, route) in routes {
+            let vehicle = &self.fleet[k];
+            if vehicle.is_multi_trip() {
+                trips[k].push(route);
+            } else {
+                let metrics = RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                    .route_metrics(&route);
+                total += cost.route_cost(vehicle, &metrics);
+                durations.push(metrics.duration);
+            }
+        }
+        for (vehicle, trips) in self.fleet.iter().zip(&trips) {
+            if !trips.is_empty() {
+                let evaluator = RouteEvaluator::new(&self.customers, &self.distances, vehicle)
+                    .with_cost_function(cost);
+                // More vehicles than the type has
+                if vehicle
+                    .available()
+                    .is_some_and(|n| evaluator.pack_trips(trips).len() > n)
+                {
+                    return f64::INFINITY;
+                }
+                total += evaluator.packed_trips_cost(trips);
+                if self.objective != Objective::TotalCost {
+                    durations.extend(evaluator.packed_trips_durations(trips));
+                }
+            }
+        }
+        match self.objective {
+            Objective::TotalCost => total + self.dropped_cost(&dropped),
+            objective => {
+                let weight = self.primary_weight();
+                objective.value(total, &durations, weight)
+                    + objective.penalty_weight(weight) * self.dropped_cost(&dropped)
+            }
         }
-        total
     }
 
     fn crossover<R: Rng>(
@@ -134,13 +489,14 @@ impl GaProblem for RoutingGaProblem {
         parent2: &GiantTour,
         rng: &mut R,
     ) -> Vec<GiantTour> {
-        // OX expects 0-indexed permutation (0..n), convert customer IDs (1..=n)
-        let p1: Vec<usize> = parent1.customers().iter().map(|&c| c - 1).collect();
-        let p2: Vec<usize> = parent2.customers().iter().map(|&c| c - 1).collect();
+        // OX expects a 0-indexed permutation (0..n): map stops to positions
+        let positions = self.stop_positions();
+        let p1: Vec<usize> = parent1.customers().iter().map(|&c| positions[c]).collect();
+        let p2: Vec<usize> = parent2.customers().iter().map(|&c| positions[c]).collect();
         let (c1, c2) = order_crossover(&p1, &p2, rng);
         vec![
-            GiantTour::new(c1.into_iter().map(|c| c + 1).collect()),
-            GiantTour::new(c2.into_iter().map(|c| c + 1).collect()),
+            GiantTour::new(c1.into_iter().map(|p| self.stops[p]).collect()),
+            GiantTour::new(c2.into_iter().map(|p| self.stops[p]).collect()),
         ]
     }
 
@@ -164,6 +520,7 @@ unsafe impl Sync for RoutingGaProblem {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::evaluation::RouteMetrics;
     use u_metaheur::ga::{GaConfig, GaRunner};
 
     fn setup() -> (Vec<Customer>, DistanceMatrix) {
@@ -209,6 +566,213 @@ mod tests {
         assert!(fitness <= 6.0 + 1e-10);
     }
 
+    #[test]
+    fn test_evaluate_multi_dimensional_capacity() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 5]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_demands(vec![10, 5]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_demands(vec![10, 5]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Volume limit of 5 forces one customer per route: 2 + 4 + 6 = 12
+        let problem = RoutingGaProblem::new(customers, dm, 30)
+            .with_capacities(vec![30, 5])
+            .without_local_search();
+        let fitness = problem.evaluate(&GiantTour::new(vec![1, 2, 3]));
+        assert!((fitness - 12.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_evaluate_objectives() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 0.0, 10, 0.0),
+            Customer::new(2, -5.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let tour = GiantTour::new(vec![1, 2]);
+        // One route of 20
+        let problem = RoutingGaProblem::new(customers.clone(), dm.clone(), 20)
+            .with_objective(Objective::Makespan);
+        let fitness = problem.evaluate(&tour);
+        assert!((fitness - (20.0 * problem.primary_weight() + 20.0)).abs() < 1e-6);
+        // Two routes of 10
+        let problem = RoutingGaProblem::new(customers, dm, 10)
+            .without_local_search()
+            .with_objective(Objective::FewestRoutes);
+        let fitness = problem.evaluate(&tour);
+        assert!((fitness - (2.0 * problem.primary_weight() + 20.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_evaluate_cost_function() {
+        struct Wages;
+        impl CostFunction for Wages {
+            fn route_cost(&self, _vehicle: &Vehicle, metrics: &RouteMetrics) -> f64 {
+                30.0 * metrics.duration
+            }
+        }
+
+        let (cust, dm) = setup();
+        let tour = GiantTour::new(vec![1, 2, 3]);
+        // Capacity 10 forces routes of 2, 4 and 6
+        let problem = RoutingGaProblem::new(cust.clone(), dm.clone(), 10).with_cost_function(Wages);
+        assert!((problem.evaluate(&tour) - 360.0).abs() < 1e-10);
+        let problem = problem.without_local_search();
+        assert!((problem.evaluate(&tour) - 360.0).abs() < 1e-10);
+        // The standard cost charges the vehicle's wages on top of distance
+        let van = Vehicle::new(0, 10).with_cost_per_time(1.0);
+        let problem = RoutingGaProblem::new(cust, dm, 0)
+            .with_fleet(&[van])
+            .without_local_search();
+        assert!((problem.evaluate(&tour) - 24.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_optional_customers() {
+        let customers = |prize: f64| {
+            vec![
+                Customer::depot(0.0, 0.0),
+                Customer::new(1, 1.0, 0.0, 10, 0.0),
+                Customer::new(2, 50.0, 0.0, 10, 0.0)
+                    .with_prize(prize)
+                    .with_optional(),
+            ]
+        };
+        let tour = GiantTour::new(vec![1, 2]);
+
+        // A detour of 98 is not worth a prize of 10
+        let cust = customers(10.0);
+        let dm = DistanceMatrix::from_customers(&cust);
+        let problem = RoutingGaProblem::new(cust, dm, 100).without_local_search();
+        assert!((problem.evaluate(&tour) - 12.0).abs() < 1e-10);
+        let solution = problem.decode(&tour).expect("feasible");
+        assert_eq!(solution.unassigned(), &[2]);
+        assert!((solution.total_cost() - 12.0).abs() < 1e-10);
+
+        // ... but is worth a prize of 200
+        let cust = customers(200.0);
+        let dm = DistanceMatrix::from_customers(&cust);
+        let problem = RoutingGaProblem::new(cust, dm, 100);
+        assert!((problem.evaluate(&tour) - 100.0).abs() < 1e-10);
+        let solution = problem.decode(&tour).expect("feasible");
+        assert!(solution.unassigned().is_empty());
+    }
+
+    #[test]
+    fn test_multi_depot() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 1.0, 1.0, 10, 0.0),
+            Customer::depot_with_id(3, 10.0, 0.0),
+            Customer::new(4, 9.0, 0.0, 10, 0.0),
+            Customer::new(5, 9.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = [Vehicle::new(0, 100), Vehicle::new(1, 100).with_depot(3)];
+        let problem = RoutingGaProblem::new(customers, dm, 0).with_fleet(&fleet);
+
+        let mut rng = u_numflow::random::create_rng(42);
+        let mut ids = problem.create_individual(&mut rng).customers().to_vec();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+
+        // Each depot serves its two neighbours: 2 × (1 + 1 + √2)
+        let expected = 2.0 * (2.0 + 2.0_f64.sqrt());
+        let fitness = problem.evaluate(&GiantTour::new(vec![1, 2, 5, 4]));
+        assert!((fitness - expected).abs() < 1e-10);
+
+        let config = GaConfig::default()
+            .with_population_size(20)
+            .with_max_generations(30)
+            .with_seed(42);
+        let result = GaRunner::run(&problem, &config).unwrap();
+        assert!((result.best_fitness - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_evaluate_open_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = [Vehicle::new(0, 100).with_open_route()];
+        let problem = RoutingGaProblem::new(customers, dm, 0).with_fleet(&fleet);
+        // 2-opt turns 0→3→2→1 (5) into 0→1→2→3 (3), with no return leg
+        let fitness = problem.evaluate(&GiantTour::new(vec![3, 2, 1]));
+        assert!((fitness - 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_evaluate_multi_trip() {
+        let (cust, dm) = setup();
+        // One customer per trip; the fixed cost is paid once for all trips
+        let fleet = [Vehicle::new(0, 10)
+            .with_fixed_cost(50.0)
+            .with_reload_time(0.0)];
+        let problem = RoutingGaProblem::new(cust, dm, 0).with_fleet(&fleet);
+        let fitness = problem.evaluate(&GiantTour::new(vec![1, 2, 3]));
+        assert!((fitness - 62.0).abs() < 1e-10);
+        let fitness = problem
+            .without_local_search()
+            .evaluate(&GiantTour::new(vec![1, 2, 3]));
+        assert!((fitness - 62.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_multi_trip_availability_counts_vehicles() {
+        let (cust, dm) = setup();
+        // One van runs all three trips
+        let van = Vehicle::new(0, 10)
+            .with_fixed_cost(50.0)
+            .with_reload_time(0.0)
+            .with_available(1);
+        let problem = RoutingGaProblem::new(cust.clone(), dm.clone(), 0)
+            .with_fleet(std::slice::from_ref(&van));
+        let tour = GiantTour::new(vec![1, 2, 3]);
+        assert!((problem.evaluate(&tour) - 62.0).abs() < 1e-10);
+        let solution = problem.decode(&tour).expect("valid");
+        assert_eq!(solution.num_routes(), 3);
+        assert!(solution
+            .routes()
+            .iter()
+            .all(|r| r.vehicle_index() == Some(0)));
+
+        // Trips take 2, 4 and 6: only two fit in a shift of 8
+        let van = van.with_shift(0.0, 8.0);
+        let problem = RoutingGaProblem::new(cust, dm, 0).with_fleet(&[van]);
+        assert!(problem.evaluate(&tour).is_infinite());
+        assert!(problem.decode(&tour).is_none());
+    }
+
+    #[test]
+    fn test_evaluate_heterogeneous_fleet() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Only one van (cap 20) is available; the truck costs 50 to dispatch
+        let fleet = vec![
+            Vehicle::new(0, 20).with_available(1),
+            Vehicle::new(1, 30).with_fixed_cost(50.0),
+        ];
+        let problem = RoutingGaProblem::new(customers, dm, 0)
+            .with_fleet(&fleet)
+            .without_local_search();
+        // Two vans would cost 2 + 6 = 8, but with one van the truck is needed
+        // and serving everything with it (50 + 6) is cheapest
+        let fitness = problem.evaluate(&GiantTour::new(vec![1, 2, 3]));
+        assert!((fitness - 56.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_crossover_preserves_genes() {
         let (cust, dm) = setup();
@@ -270,4 +834,114 @@ mod tests {
         assert!(result.best_fitness < f64::INFINITY);
         // Must split into at least 2 routes
     }
+
+    #[test]
+    fn test_ga_simultaneous_pickup_delivery() {
+        use crate::models::ViolationType;
+
+        // Each customer takes 5 and returns 10: at most two fit in 20
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain(
+                [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| Customer::new(i + 1, x, y, 5, 0.0).with_pickup(10)),
+            )
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let problem = RoutingGaProblem::new(customers.clone(), dm.clone(), 20);
+        let config = GaConfig::default()
+            .with_population_size(20)
+            .with_max_generations(30)
+            .with_seed(42);
+        let result = GaRunner::run(&problem, &config).expect("valid");
+        let sol = problem.decode(&result.best).expect("valid");
+        let vehicle = Vehicle::new(0, 20);
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::CapacityExceeded { .. })));
+        assert_eq!(sol.num_served(), 4);
+    }
+
+    #[test]
+    fn test_ga_pickup_delivery_time_windows() {
+        use crate::models::{TimeWindow, ViolationType};
+
+        let window = |ready, due| TimeWindow::new(ready, due).expect("valid");
+        let mut customers = vec![Customer::depot(0.0, 0.0)];
+        for (r, &(x, y)) in [(4.0, 0.0), (0.0, 4.0), (-4.0, 0.0), (0.0, -4.0)]
+            .iter()
+            .enumerate()
+        {
+            let (pickup, delivery) = (2 * r + 1, 2 * r + 2);
+            customers.push(
+                Customer::new(pickup, x / 4.0, y / 4.0, 10, 1.0)
+                    .with_request_role(RequestRole::Pickup {
+                        delivery_id: delivery,
+                    })
+                    .with_time_window(window(0.0, 50.0)),
+            );
+            customers.push(
+                Customer::new(delivery, x, y, -10, 1.0)
+                    .with_request_role(RequestRole::Delivery { pickup_id: pickup })
+                    .with_time_window(window(0.0, 100.0)),
+            );
+        }
+        let dm = DistanceMatrix::from_customers(&customers);
+        let problem = RoutingGaProblem::new(customers.clone(), dm.clone(), 20);
+
+        // Every delivery ahead of its pickup: served once the halves swap
+        let reversed = GiantTour::new((1..=8).rev().collect());
+        assert!(problem.evaluate(&reversed).is_finite());
+        // One request on board at a time: 1-2 and 3-4 cannot be interleaved
+        let narrow = RoutingGaProblem::new(customers.clone(), dm.clone(), 10);
+        let interleaved = GiantTour::new(vec![1, 3, 2, 4, 5, 6, 7, 8]);
+        assert!(narrow.evaluate(&interleaved).is_infinite());
+        assert!(narrow.decode(&interleaved).is_none());
+        let nested = GiantTour::new(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let sol = narrow.decode(&nested).expect("valid");
+        assert_eq!(sol.num_served(), 8);
+
+        let config = GaConfig::default()
+            .with_population_size(20)
+            .with_max_generations(30)
+            .with_seed(42);
+        let result = GaRunner::run(&problem, &config).expect("valid");
+        let sol = problem.decode(&result.best).expect("valid");
+        assert_eq!(sol.num_served(), 8);
+        let vehicle = Vehicle::new(0, 20);
+        let (_, violations) =
+            RouteEvaluator::new(&customers, &dm, &vehicle).evaluate_solution(&sol);
+        assert!(violations.is_empty(), "{violations:?}");
+        assert!(violations
+            .iter()
+            .all(|v| !matches!(v.kind, ViolationType::PrecedenceViolated { .. })));
+    }
+
+    #[test]
+    fn test_two_opt_keeps_load_profile() {
+        // 2-opt would visit the pickup at 1 before the delivery at 2 empties
+        // the vehicle: 20 on board against a capacity of 10
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 0, 0.0).with_pickup(10),
+            Customer::new(2, 3.0, 0.0, 10, 0.0),
+            Customer::new(3, 2.0, 0.0, 0, 0.0),
+            Customer::new(4, 4.0, 0.0, 0, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let problem = RoutingGaProblem::new(customers.clone(), dm, 10);
+        let split = FleetSplitResult {
+            routes: vec![vec![2, 3, 1, 4]],
+            vehicles: vec![0],
+            total_cost: 12.0,
+        };
+        let (routes, dropped) = problem.improve_routes(&split);
+        assert!(dropped.is_empty());
+        assert_eq!(routes.len(), 1);
+        let peak = Load::peak_of_route(&routes[0].1, &customers);
+        assert!(peak.get(0) <= 10);
+    }
 }
@@ -21,7 +21,7 @@
 //! vehicle routing problem", *Computers & Operations Research* 31(12), 1985-2002.
 
 use crate::distance::DistanceMatrix;
-use crate::models::Customer;
+use crate::models::{Customer, LoadProfile, RouteEnds};
 
 /// Result of the split algorithm.
 #[derive(Debug, Clone)]
@@ -42,7 +42,7 @@ pub struct SplitResult {
 /// * `tour` — Customer IDs in giant-tour order (excluding depot)
 /// * `customers` — All locations (index 0 = depot)
 /// * `distances` — Distance matrix
-/// * `capacity` — Vehicle capacity
+/// * `capacity` — Vehicle capacity (one entry per load dimension)
 ///
 /// # Examples
 ///
@@ -60,7 +60,7 @@ pub struct SplitResult {
 /// let dm = DistanceMatrix::from_customers(&customers);
 ///
 /// // Giant tour: [1, 2, 3] — customers in order
-/// let result = split(&[1, 2, 3], &customers, &dm, 30);
+/// let result = split(&[1, 2, 3], &customers, &dm, &[30]);
 /// assert_eq!(result.routes.len(), 1); // all fit in one route
 /// assert!((result.total_distance - 6.0).abs() < 1e-10);
 /// ```
@@ -68,8 +68,42 @@ pub fn split(
     tour: &[usize],
     customers: &[Customer],
     distances: &DistanceMatrix,
-    capacity: i32,
+    capacity: &[i32],
 ) -> SplitResult {
+    split_from_depot(tour, 0, customers, distances, capacity)
+}
+
+/// Like [`split`], but every sub-route starts and ends at `depot` instead
+/// of location 0 (e.g. one depot of a multi-depot problem).
+///
+/// Pass [`RouteEnds::open`] for open routes, whose return leg is not costed.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Customer;
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::ga::split_from_depot;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 9.0, 0.0, 10, 0.0),
+///     Customer::depot_with_id(2, 10.0, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+///
+/// let result = split_from_depot(&[1], 2, &customers, &dm, &[30]);
+/// assert!((result.total_distance - 2.0).abs() < 1e-10);
+/// ```
+pub fn split_from_depot(
+    tour: &[usize],
+    depot: impl Into<RouteEnds>,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    capacity: &[i32],
+) -> SplitResult {
+    let ends = depot.into();
+    let depot = ends.start();
     let n = tour.len();
 
     if n == 0 {
@@ -79,8 +113,6 @@ pub fn split(
         };
     }
 
-    let depot = 0;
-
     // cost[i] = minimum total distance to serve tour[0..i]
     // pred[i] = predecessor index (start of the last route ending at i)
     let mut cost = vec![f64::INFINITY; n + 1];
@@ -92,14 +124,14 @@ pub fn split(
             continue;
         }
 
-        let mut load = 0i32;
+        let mut load = LoadProfile::new();
         let mut route_dist = 0.0;
 
         for j in i..n {
             let cid = tour[j];
-            load += customers[cid].demand();
+            load.push(&customers[cid]);
 
-            if load > capacity {
+            if !load.peak().fits(capacity) {
                 break;
             }
 
@@ -112,8 +144,8 @@ pub fn split(
                 route_dist += distances.get(tour[j - 1], cid);
             }
 
-            // Complete route: ... → cid → depot
-            let total_route = route_dist + distances.get(cid, depot);
+            // Complete route: ... → cid → depot (free for open routes)
+            let total_route = route_dist + ends.leg(cid, ends.end_node(), distances);
             let new_cost = cost[i] + total_route;
 
             if new_cost < cost[j + 1] {
@@ -157,7 +189,7 @@ mod tests {
     #[test]
     fn test_split_single_route() {
         let (cust, dm) = line_customers();
-        let result = split(&[1, 2, 3], &cust, &dm, 30);
+        let result = split(&[1, 2, 3], &cust, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
         assert_eq!(result.routes[0], vec![1, 2, 3]);
         // 0→1→2→3→0 = 1+1+1+3 = 6
@@ -168,7 +200,7 @@ mod tests {
     fn test_split_forced_two_routes() {
         let (cust, dm) = line_customers();
         // Capacity 20 can hold 2 customers (demand 10 each)
-        let result = split(&[1, 2, 3], &cust, &dm, 20);
+        let result = split(&[1, 2, 3], &cust, &dm, &[20]);
         assert_eq!(result.routes.len(), 2);
         // Optimal split: [1]+[2,3] = (0→1→0)+(0→2→3→0) = 2+6 = 8
         assert_eq!(result.routes[0], vec![1]);
@@ -180,7 +212,7 @@ mod tests {
     fn test_split_each_alone() {
         let (cust, dm) = line_customers();
         // Capacity 10 — each customer alone
-        let result = split(&[1, 2, 3], &cust, &dm, 10);
+        let result = split(&[1, 2, 3], &cust, &dm, &[10]);
         assert_eq!(result.routes.len(), 3);
         // (0→1→0)+(0→2→0)+(0→3→0) = 2+4+6 = 12
         assert!((result.total_distance - 12.0).abs() < 1e-10);
@@ -189,7 +221,7 @@ mod tests {
     #[test]
     fn test_split_empty() {
         let (cust, dm) = line_customers();
-        let result = split(&[], &cust, &dm, 30);
+        let result = split(&[], &cust, &dm, &[30]);
         assert!(result.routes.is_empty());
         assert_eq!(result.total_distance, 0.0);
     }
@@ -197,7 +229,7 @@ mod tests {
     #[test]
     fn test_split_single_customer() {
         let (cust, dm) = line_customers();
-        let result = split(&[2], &cust, &dm, 30);
+        let result = split(&[2], &cust, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
         assert_eq!(result.routes[0], vec![2]);
         // 0→2→0 = 4
@@ -208,12 +240,27 @@ mod tests {
     fn test_split_reversed_order() {
         let (cust, dm) = line_customers();
         // Tour [3,2,1] — reversed
-        let result = split(&[3, 2, 1], &cust, &dm, 30);
+        let result = split(&[3, 2, 1], &cust, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
         // 0→3→2→1→0 = 3+1+1+1 = 6
         assert!((result.total_distance - 6.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_split_multi_dimensional_capacity() {
+        // Weight would allow all three together; volume allows only two
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 4]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_demands(vec![10, 4]),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_demands(vec![10, 4]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let result = split(&[1, 2, 3], &customers, &dm, &[30, 8]);
+        assert_eq!(result.routes.len(), 2);
+        assert!((result.total_distance - 8.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_split_optimal_partition() {
         // Cluster layout: two clusters far apart
@@ -226,7 +273,7 @@ mod tests {
         ];
         let dm = DistanceMatrix::from_customers(&customers);
         // Tour: [1,2,3,4], capacity 20 → split into [1,2] + [3,4]
-        let result = split(&[1, 2, 3, 4], &customers, &dm, 20);
+        let result = split(&[1, 2, 3, 4], &customers, &dm, &[20]);
         assert_eq!(result.routes.len(), 2);
         assert_eq!(result.routes[0], vec![1, 2]);
         assert_eq!(result.routes[1], vec![3, 4]);
@@ -235,4 +282,18 @@ mod tests {
         // Total: 26
         assert!((result.total_distance - 26.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_split_open_routes() {
+        let (cust, dm) = line_customers();
+        let open = RouteEnds::open(0);
+        // No return leg: 0→1→2→3 = 3
+        let result = split_from_depot(&[1, 2, 3], open, &cust, &dm, &[30]);
+        assert_eq!(result.routes.len(), 1);
+        assert!((result.total_distance - 3.0).abs() < 1e-10);
+        // One customer per route: 1 + 2 + 3
+        let result = split_from_depot(&[1, 2, 3], open, &cust, &dm, &[10]);
+        assert_eq!(result.routes.len(), 3);
+        assert!((result.total_distance - 6.0).abs() < 1e-10);
+    }
 }
@@ -0,0 +1,560 @@
+//! Heterogeneous-fleet split for partitioning a giant tour into routes.
+//!
+//! # Algorithm
+//!
+//! Extends the Prins (2004) split to a fleet of vehicle types that differ in
+//! capacity, fixed cost, cost per distance, availability and routing
+//! profile (the matrices they travel with). Each arc (i, j)
+//! of the auxiliary graph can be served by any type that provides the skills
+//! and carries the peak on-board load of customers i+1..=j (see
+//! [`Load::peak_of_route`](crate::models::Load::peak_of_route)), and
+//! serves each of them within a time window and finishes them, breaks
+//! included, within its shift, at cost
+//! `fixed + cost_per_distance × distance + cost_per_emission × emissions`
+//! plus the cost of the route's duration (wages and overtime) and its soft
+//! time-window penalty, i.e. the
+//! [`StandardCost`](crate::evaluation::StandardCost) of the route.
+//!
+//! Both halves of a pickup-and-delivery request go on the same route, the
+//! pickup first: an arc is not served if it holds a delivery without its
+//! pickup ahead of it. A pickup whose delivery is left to a later route
+//! leaves that route without one, so no split serves it.
+//!
+//! Routes of an [electric](Vehicle::with_battery) type are costed with the
+//! charging stops they need (see [`insert_charging_stops`]).
+//!
+//! With limited availability the shortest path becomes resource-constrained:
+//! every node keeps a set of labels `(cost, vehicles used per type)` and a
+//! label is discarded when another label at the same node is no more
+//! expensive and uses no more vehicles of any limited type. Types without a
+//! limit do not take part in dominance, so an unlimited fleet keeps a single
+//! label per node and reduces to the classic split.
+//!
+//! Each route of a [multi-trip](Vehicle::with_reload_time) type is one trip,
+//! and the split does not know how trips will pack onto vehicles, so such
+//! types count as unlimited here: the caller packs their trips (see
+//! [`RouteEvaluator::pack_trips`]) and checks the number of vehicles.
+//!
+//! # Complexity
+//!
+//! O(n² × K × L) where n = customers, K = vehicle types and L = labels per
+//! node (at most [`MAX_LABELS`], 1 for an unlimited fleet), times a further
+//! O(n) per arc for types that price load-dependent emissions, and the cost
+//! of planning charging stops per arc for electric types.
+//!
+//! # Reference
+//!
+//! Prins, C. (2009). "Two memetic algorithms for heterogeneous fleet vehicle
+//! routing problems", *Engineering Applications of Artificial Intelligence*
+//! 22(6), 916-928.
+
+use crate::distance::DistanceMatrix;
+use crate::evaluation::{insert_charging_stops, RouteClock, RouteEvaluator};
+use crate::models::{Customer, LoadProfile, RequestRole, Vehicle};
+
+/// Maximum number of labels kept per node; the most expensive are dropped.
+pub const MAX_LABELS: usize = 32;
+
+/// Result of the heterogeneous-fleet split.
+#[derive(Debug, Clone)]
+pub struct FleetSplitResult {
+    /// Routes as sequences of customer IDs, including the charging stops of
+    /// electric vehicles.
+    pub routes: Vec<Vec<usize>>,
+    /// Index into the fleet of the vehicle type serving each route.
+    pub vehicles: Vec<usize>,
+    /// Total [standard cost](crate::evaluation::StandardCost) of all routes;
+    /// infinite if the fleet cannot serve the tour.
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Label {
+    cost: f64,
+    used: Vec<usize>,
+    pred: Option<(usize, usize)>,
+    vehicle: usize,
+}
+
+/// Splits a giant tour into routes served by a heterogeneous fleet.
+///
+/// Each route starts and ends at the depot of its vehicle type and respects
+/// that type's skills, capacity and shift end; the number of routes per type
+/// respects its availability limit. Each pickup-and-delivery request is
+/// served by one route, pickup first. Open vehicle types do not pay the
+/// return leg.
+///
+/// # Arguments
+///
+/// * `tour` — Customer IDs in giant-tour order (excluding depot)
+/// * `customers` — All locations (index 0 = depot)
+/// * `distances` — Distance matrix
+/// * `fleet` — Vehicle types
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::ga::split_fleet;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+///     Customer::new(2, 2.0, 0.0, 10, 0.0),
+///     Customer::new(3, 3.0, 0.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let fleet = vec![
+///     Vehicle::new(0, 10).with_fixed_cost(5.0),
+///     Vehicle::new(1, 30).with_fixed_cost(20.0).with_available(1),
+/// ];
+///
+/// let result = split_fleet(&[1, 2, 3], &customers, &dm, &fleet);
+/// assert_eq!(result.routes, vec![vec![1, 2, 3]]);
+/// assert_eq!(result.vehicles, vec![1]);
+/// assert!((result.total_cost - 26.0).abs() < 1e-10);
+/// ```
+pub fn split_fleet(
+    tour: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+) -> FleetSplitResult {
+    let n = tour.len();
+
+    if n == 0 || fleet.is_empty() {
+        return FleetSplitResult {
+            routes: vec![],
+            vehicles: vec![],
+            total_cost: if n == 0 { 0.0 } else { f64::INFINITY },
+        };
+    }
+
+    let limited: Vec<usize> = (0..fleet.len())
+        .filter(|&k| fleet[k].available().is_some() && !fleet[k].is_multi_trip())
+        .collect();
+
+    let mut labels: Vec<Vec<Label>> = vec![Vec::new(); n + 1];
+    labels[0].push(Label {
+        cost: 0.0,
+        used: vec![0; fleet.len()],
+        pred: None,
+        vehicle: 0,
+    });
+
+    for i in 0..n {
+        let (head, tail) = labels.split_at_mut(i + 1);
+        let from = &head[i];
+        if from.is_empty() {
+            continue;
+        }
+
+        let mut load = LoadProfile::new();
+        // Distance between the customers of the route, per vehicle type
+        let mut inner_dist = vec![0.0; fleet.len()];
+        // Schedule up to the last customer, and whether every customer so
+        // far is compatible, per vehicle type
+        let mut clocks: Vec<RouteClock> = fleet.iter().map(RouteClock::new).collect();
+        let mut serves = vec![true; fleet.len()];
+        // Pickups on the route so far
+        let mut picked_up = vec![false; customers.len()];
+
+        for j in i..n {
+            let cid = tour[j];
+            match customers[cid].request_role() {
+                Some(RequestRole::Pickup { .. }) => picked_up[cid] = true,
+                // Picked up on an earlier route, or later in the tour
+                Some(RequestRole::Delivery { pickup_id }) if !picked_up[pickup_id] => break,
+                _ => {}
+            }
+            load.push(&customers[cid]);
+            for (k, vehicle) in fleet.iter().enumerate() {
+                serves[k] &= vehicle.can_serve(&customers[cid]);
+            }
+
+            if !fleet
+                .iter()
+                .zip(&serves)
+                .any(|(v, &ok)| ok && v.can_carry(load.peak()))
+            {
+                break;
+            }
+            for (k, vehicle) in fleet.iter().enumerate() {
+                let distances = distances.for_vehicle(vehicle);
+                let ends = vehicle.route_ends();
+                let prev = if j > i { tour[j - 1] } else { ends.start() };
+                if j > i {
+                    inner_dist[k] += distances.get(prev, cid);
+                }
+                clocks[k].drive(prev, cid, distances);
+                // Once a hard window is missed the route only gets later
+                serves[k] &= clocks[k].serve(&customers[cid]).is_some();
+                if !serves[k] || !vehicle.can_carry(load.peak()) {
+                    continue;
+                }
+                let route_cost = if vehicle.is_electric() {
+                    match charged_route_cost(&tour[i..=j], customers, distances, vehicle) {
+                        Some(cost) => cost,
+                        None => continue,
+                    }
+                } else if let Some(finish) = clocks[k]
+                    .finish_time(cid, ends, distances)
+                    .filter(|&finish| vehicle.shift_end().is_none_or(|end| finish <= end))
+                {
+                    vehicle.route_cost(
+                        distances.get(ends.start(), tour[i])
+                            + inner_dist[k]
+                            + ends.leg(cid, ends.end_node(), distances),
+                    ) + vehicle.emission_cost_between(&tour[i..=j], ends, customers, distances)
+                        + vehicle.time_cost(finish - vehicle.shift_start())
+                        + clocks[k].penalty()
+                } else {
+                    continue;
+                };
+
+                for (li, label) in from.iter().enumerate() {
+                    if !vehicle.is_multi_trip() && !vehicle.is_available(label.used[k]) {
+                        continue;
+                    }
+                    let mut used = label.used.clone();
+                    used[k] += 1;
+                    insert_label(
+                        &mut tail[j - i],
+                        Label {
+                            cost: label.cost + route_cost,
+                            used,
+                            pred: Some((i, li)),
+                            vehicle: k,
+                        },
+                        &limited,
+                    );
+                }
+            }
+        }
+    }
+
+    // Backtrack from the cheapest label at the last node
+    let Some(best) = (0..labels[n].len()).min_by(|&a, &b| {
+        labels[n][a]
+            .cost
+            .partial_cmp(&labels[n][b].cost)
+            .expect("split costs should not be NaN")
+    }) else {
+        return FleetSplitResult {
+            routes: vec![],
+            vehicles: vec![],
+            total_cost: f64::INFINITY,
+        };
+    };
+
+    let total_cost = labels[n][best].cost;
+    let mut routes = Vec::new();
+    let mut vehicles = Vec::new();
+    let (mut j, mut li) = (n, best);
+    while let Some((i, pi)) = labels[j][li].pred {
+        let vehicle = &fleet[labels[j][li].vehicle];
+        routes.push(
+            insert_charging_stops(&tour[i..j], customers, distances, vehicle)
+                .unwrap_or_else(|| tour[i..j].to_vec()),
+        );
+        vehicles.push(labels[j][li].vehicle);
+        (j, li) = (i, pi);
+    }
+    routes.reverse();
+    vehicles.reverse();
+
+    FleetSplitResult {
+        routes,
+        vehicles,
+        total_cost,
+    }
+}
+
+/// Cost of `route` on an electric vehicle once its charging stops are
+/// inserted, or `None` if it cannot be covered within the vehicle's shift.
+fn charged_route_cost(
+    route: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> Option<f64> {
+    let charged = insert_charging_stops(route, customers, distances, vehicle)?;
+    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+    if !evaluator.within_shift(&charged) {
+        return None;
+    }
+    let ends = vehicle.route_ends();
+    Some(
+        vehicle.route_cost(ends.distance(&charged, distances))
+            + vehicle.emission_cost_between(&charged, ends, customers, distances)
+            + vehicle.time_cost(evaluator.duration(&charged))
+            + evaluator.time_window_penalty(&charged),
+    )
+}
+
+/// Adds a label to a node unless it is dominated, pruning labels it dominates.
+fn insert_label(node: &mut Vec<Label>, label: Label, limited: &[usize]) {
+    let dominates =
+        |a: &Label, b: &Label| a.cost <= b.cost && limited.iter().all(|&k| a.used[k] <= b.used[k]);
+
+    if node.iter().any(|l| dominates(l, &label)) {
+        return;
+    }
+    node.retain(|l| !dominates(&label, l));
+    node.push(label);
+
+    if node.len() > MAX_LABELS {
+        let worst = (0..node.len())
+            .max_by(|&a, &b| {
+                node[a]
+                    .cost
+                    .partial_cmp(&node[b].cost)
+                    .expect("split costs should not be NaN")
+            })
+            .expect("node is not empty");
+        node.swap_remove(worst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga::split;
+    use crate::models::{EmissionModel, TimeWindow};
+
+    fn line_customers() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+            Customer::new(4, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_split_fleet_matches_split_for_single_type() {
+        let (cust, dm) = line_customers();
+        let tour = [1, 2, 3, 4];
+        let fleet = vec![Vehicle::new(0, 20)];
+        let result = split_fleet(&tour, &cust, &dm, &fleet);
+        let classic = split(&tour, &cust, &dm, &[20]);
+        assert_eq!(result.routes, classic.routes);
+        assert!((result.total_cost - classic.total_distance).abs() < 1e-10);
+        assert!(result.vehicles.iter().all(|&k| k == 0));
+    }
+
+    #[test]
+    fn test_split_fleet_picks_cheaper_type() {
+        let (cust, dm) = line_customers();
+        // Small van is cheap per distance, truck has a high fixed cost
+        let fleet = vec![
+            Vehicle::new(0, 10).with_cost_per_distance(1.0),
+            Vehicle::new(1, 40).with_fixed_cost(100.0),
+        ];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes.len(), 4);
+        assert!(result.vehicles.iter().all(|&k| k == 0));
+        // 2 + 4 + 6 + 20
+        assert!((result.total_cost - 32.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_respects_availability() {
+        let (cust, dm) = line_customers();
+        let fleet = vec![
+            Vehicle::new(0, 10).with_available(2),
+            Vehicle::new(1, 40).with_fixed_cost(100.0),
+        ];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        // Four vans (cost 32) are not available; one truck serving
+        // everything (100 + 20) beats mixing it with vans
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert_eq!(result.vehicles, vec![1]);
+        assert!((result.total_cost - 120.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_open_type() {
+        let (cust, dm) = line_customers();
+        // The open type skips the 10-unit return leg, outweighing its fixed cost
+        let fleet = vec![
+            Vehicle::new(0, 40),
+            Vehicle::new(1, 40).with_fixed_cost(5.0).with_open_route(),
+        ];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert_eq!(result.vehicles, vec![1]);
+        // 5 + (1 + 1 + 1 + 7)
+        assert!((result.total_cost - 15.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_prices_emissions() {
+        let (cust, dm) = line_customers();
+        let diesel =
+            Vehicle::new(0, 40).with_emissions(EmissionModel::new(1.0, 2.0).expect("valid"));
+        let electric = Vehicle::new(1, 40).with_fixed_cost(15.0);
+        let fleet = vec![diesel.clone(), electric.clone()];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.vehicles, vec![0]);
+
+        // Emitting 2 + 1.75 + 1.5 + 8.75 + 10 now costs more than the
+        // electric vehicle's fixed cost
+        let fleet = vec![diesel.with_cost_per_emission(1.0), electric];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert_eq!(result.vehicles, vec![1]);
+        assert!((result.total_cost - 35.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_electric() {
+        let (mut cust, _) = line_customers();
+        cust.push(Customer::charging_station(5, 10.0, 1.0, 1.0));
+        let dm = DistanceMatrix::from_customers(&cust);
+        // 15 units of range: customer 4 is only reachable via the station
+        let fleet = vec![Vehicle::new(0, 10).with_battery(15.0, 1.0)];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1], vec![2], vec![3], vec![5, 4]]);
+        let detour = 1.0 + 101f64.sqrt();
+        assert!((result.total_cost - (2.0 + 4.0 + 6.0 + 10.0 + detour)).abs() < 1e-10);
+
+        let short = vec![Vehicle::new(0, 10).with_battery(9.0, 1.0)];
+        assert!(split_fleet(&[1, 2, 3, 4], &cust, &dm, &short)
+            .total_cost
+            .is_infinite());
+    }
+
+    #[test]
+    fn test_split_fleet_shift_end() {
+        let (cust, dm) = line_customers();
+        // The van cannot reach customer 4 and return within its shift
+        let fleet = vec![
+            Vehicle::new(0, 40).with_shift(0.0, 8.0),
+            Vehicle::new(1, 40).with_fixed_cost(10.0),
+        ];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert_eq!(result.vehicles, vec![1]);
+        assert!((result.total_cost - 30.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_skills() {
+        let (mut cust, dm) = line_customers();
+        cust[4] = cust[4].clone().with_skills(vec![1]);
+        let fleet = vec![
+            Vehicle::new(0, 40),
+            Vehicle::new(1, 40)
+                .with_skills(vec![1])
+                .with_fixed_cost(10.0),
+        ];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert_eq!(result.vehicles, vec![1]);
+        assert!((result.total_cost - 30.0).abs() < 1e-10);
+
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet[..1]);
+        assert!(result.total_cost.is_infinite());
+    }
+
+    #[test]
+    fn test_split_fleet_soft_time_window() {
+        let (mut cust, dm) = line_customers();
+        let tw = TimeWindow::new(0.0, 8.0)
+            .expect("valid")
+            .with_soft_late(100.0, 5.0);
+        cust[4] = cust[4].clone().with_time_window(tw);
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &[Vehicle::new(0, 40)]);
+        // Customer 4 is reached at 10 either way: distance 20 plus 2 × 5 late
+        assert_eq!(result.routes, vec![vec![1, 2, 3, 4]]);
+        assert!((result.total_cost - 30.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_hard_time_window() {
+        let cust = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&cust);
+        let fleet = [Vehicle::new(0, 40)];
+        let tw = TimeWindow::new(0.0, 5.0).expect("valid");
+
+        // Reached at 10: 5 late
+        let mut soft = cust.clone();
+        soft[1] = soft[1]
+            .clone()
+            .with_time_window(tw.with_soft_late(10.0, 100.0));
+        let result = split_fleet(&[1], &soft, &dm, &fleet);
+        assert!((result.total_cost - 520.0).abs() < 1e-10);
+
+        // Missing a hard window is never cheaper than being late
+        let mut hard = cust;
+        hard[1] = hard[1].clone().with_time_window(tw);
+        let result = split_fleet(&[1], &hard, &dm, &fleet);
+        assert!(result.total_cost.is_infinite());
+        assert!(result.routes.is_empty());
+    }
+
+    #[test]
+    fn test_split_fleet_infeasible() {
+        let (cust, dm) = line_customers();
+        let fleet = vec![Vehicle::new(0, 10).with_available(3)];
+        let result = split_fleet(&[1, 2, 3, 4], &cust, &dm, &fleet);
+        assert!(result.total_cost.is_infinite());
+        assert!(result.routes.is_empty());
+    }
+
+    #[test]
+    fn test_split_fleet_empty() {
+        let (cust, dm) = line_customers();
+        let result = split_fleet(&[], &cust, &dm, &[Vehicle::new(0, 10)]);
+        assert!(result.routes.is_empty());
+        assert_eq!(result.total_cost, 0.0);
+    }
+
+    #[test]
+    fn test_split_fleet_simultaneous_pickup_delivery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 0, 0.0).with_pickup(10),
+            Customer::new(2, 2.0, 0.0, 0, 0.0).with_pickup(10),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 10)];
+        // Both pickups together would bring back 20
+        let result = split_fleet(&[1, 2], &customers, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![1], vec![2]]);
+        assert!((result.total_cost - 6.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_split_fleet_pickup_delivery() {
+        use crate::models::RequestRole;
+
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 3 }),
+            Customer::new(2, 2.0, 0.0, 0, 0.0).with_pickup(15),
+            Customer::new(3, 3.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 20)];
+        let result = split_fleet(&[2, 1, 3], &customers, &dm, &fleet);
+        assert_eq!(result.routes, vec![vec![2], vec![1, 3]]);
+        assert!((result.total_cost - 10.0).abs() < 1e-10);
+        // All three exceed the capacity, and [1], [2, 3] would part the request
+        let result = split_fleet(&[1, 2, 3], &customers, &dm, &fleet);
+        assert!(result.total_cost.is_infinite());
+        // A delivery ahead of its pickup cannot be served
+        let result = split_fleet(&[3, 2, 1], &customers, &dm, &fleet);
+        assert!(result.total_cost.is_infinite());
+    }
+}
@@ -7,8 +7,12 @@
 //! sub-route tour[i..=j] can be executed without violating any time window.
 //!
 //! For each sub-route candidate, simulates the timing forward from the depot:
-//! arrival → wait (if early) → service → next customer. If any customer's
-//! arrival exceeds its due date, the sub-route is infeasible and pruned.
+//! arrival → wait (until the earliest open window) → service → next customer.
+//! If every window of a customer has closed on arrival, the sub-route is
+//! infeasible and pruned. With [`split_tw_for_vehicle`] the simulation also
+//! takes the vehicle's driver breaks and starts at its shift start, and
+//! sub-routes that end after the shift are skipped. Travel times follow the
+//! matrix's speed profiles at each departure time.
 //!
 //! # Complexity
 //!
@@ -23,7 +27,8 @@
 //! Problems with Time Window Constraints", *Operations Research* 35(2), 254-265.
 
 use crate::distance::DistanceMatrix;
-use crate::models::Customer;
+use crate::evaluation::RouteClock;
+use crate::models::{Customer, LoadProfile, RouteEnds, Vehicle};
 
 use super::split::SplitResult;
 
@@ -41,7 +46,7 @@ use super::split::SplitResult;
 /// * `tour` — Customer IDs in giant-tour order (excluding depot)
 /// * `customers` — All locations (index 0 = depot, with optional time windows)
 /// * `distances` — Distance matrix
-/// * `capacity` — Vehicle capacity
+/// * `capacity` — Vehicle capacity (one entry per load dimension)
 ///
 /// # Examples
 ///
@@ -59,15 +64,90 @@ use super::split::SplitResult;
 /// ];
 /// let dm = DistanceMatrix::from_customers(&customers);
 ///
-/// let result = split_tw(&[1, 2], &customers, &dm, 30);
+/// let result = split_tw(&[1, 2], &customers, &dm, &[30]);
 /// assert_eq!(result.routes.len(), 1);
 /// ```
 pub fn split_tw(
     tour: &[usize],
     customers: &[Customer],
     distances: &DistanceMatrix,
-    capacity: i32,
+    capacity: &[i32],
 ) -> SplitResult {
+    split_tw_from_depot(tour, 0, customers, distances, capacity)
+}
+
+/// Like [`split_tw`], but every sub-route starts and ends at `depot` instead
+/// of location 0 (e.g. one depot of a multi-depot problem).
+///
+/// Pass [`RouteEnds::open`] for open routes, whose return leg is not costed.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Customer;
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::ga::split_tw_from_depot;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 9.0, 0.0, 10, 0.0),
+///     Customer::depot_with_id(2, 10.0, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+///
+/// let result = split_tw_from_depot(&[1], 2, &customers, &dm, &[30]);
+/// assert!((result.total_distance - 2.0).abs() < 1e-10);
+/// ```
+pub fn split_tw_from_depot(
+    tour: &[usize],
+    depot: impl Into<RouteEnds>,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    capacity: &[i32],
+) -> SplitResult {
+    let ends: RouteEnds = depot.into();
+    let vehicle = Vehicle::new(0, 0)
+        .with_capacities(capacity.to_vec())
+        .with_depot(ends.start());
+    let vehicle = match ends.end() {
+        Some(end) => vehicle.with_end_location(end),
+        None => vehicle.with_open_route(),
+    };
+    split_tw_for_vehicle(tour, customers, distances, &vehicle)
+}
+
+/// Like [`split_tw`], but every sub-route is driven by `vehicle`: it uses the
+/// vehicle's capacity, skills, start and end locations, shift and driver
+/// breaks.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, DriverBreak, TimeWindow, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::ga::split_tw_for_vehicle;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 4.0, 0.0, 10, 0.0),
+///     Customer::new(2, 4.0, 3.0, 10, 0.0)
+///         .with_time_window(TimeWindow::new(0.0, 8.0).expect("valid")),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let vehicle = Vehicle::new(0, 30).with_break(DriverBreak::after_driving(6.0, 2.0));
+///
+/// // Via customer 1, the driver must rest before reaching 2 and arrives at 9
+/// let result = split_tw_for_vehicle(&[1, 2], &customers, &dm, &vehicle);
+/// assert_eq!(result.routes, vec![vec![1], vec![2]]);
+/// ```
+pub fn split_tw_for_vehicle(
+    tour: &[usize],
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> SplitResult {
+    let ends = vehicle.route_ends();
+    let depot = ends.start();
     let n = tour.len();
 
     if n == 0 {
@@ -77,8 +157,6 @@ pub fn split_tw(
         };
     }
 
-    let depot = 0;
-
     let mut cost = vec![f64::INFINITY; n + 1];
     let mut pred = vec![0usize; n + 1];
     cost[0] = 0.0;
@@ -88,42 +166,35 @@ pub fn split_tw(
             continue;
         }
 
-        let mut load = 0i32;
+        let mut load = LoadProfile::new();
         let mut route_dist = 0.0;
-        let mut time = 0.0;
+        let mut clock = RouteClock::new(vehicle);
 
         for j in i..n {
             let cid = tour[j];
-            load += customers[cid].demand();
+            load.push(&customers[cid]);
 
-            if load > capacity {
+            if !vehicle.can_carry(load.peak()) || !vehicle.can_serve(&customers[cid]) {
                 break;
             }
 
-            // Compute distance
-            if j == i {
-                route_dist = distances.get(depot, cid);
-                time = route_dist;
-            } else {
-                let travel = distances.get(tour[j - 1], cid);
-                route_dist += travel;
-                time += travel;
-            }
+            // Compute distance, taking any break due on the way
+            let prev = if j == i { depot } else { tour[j - 1] };
+            route_dist += distances.get(prev, cid);
+            clock.drive(prev, cid, distances);
 
-            // Check time window
-            if let Some(tw) = customers[cid].time_window() {
-                if time > tw.due() {
-                    break;
-                }
-                // Wait if early
-                time = time.max(tw.ready());
+            // Check time windows, waiting for the earliest one still open
+            if clock.serve(&customers[cid]).is_none() || clock.missed_break() {
+                break;
             }
 
-            // Add service time
-            time += customers[cid].service_duration();
+            // The vehicle must be back by its shift end
+            if !clock.can_finish(cid, ends, distances) {
+                continue;
+            }
 
-            // Complete route cost: ... → cid → depot
-            let total_route = route_dist + distances.get(cid, depot);
+            // Complete route cost: ... → cid → depot (free for open routes)
+            let total_route = route_dist + ends.leg(cid, ends.end_node(), distances);
             let new_cost = cost[i] + total_route;
 
             if new_cost < cost[j + 1] {
@@ -193,7 +264,7 @@ mod tests {
                 .with_time_window(TimeWindow::new(0.0, 100.0).expect("valid")),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let result = split_tw(&[1, 2, 3], &customers, &dm, 30);
+        let result = split_tw(&[1, 2, 3], &customers, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
     }
 
@@ -209,8 +280,45 @@ mod tests {
         ];
         let dm = DistanceMatrix::from_customers(&customers);
         // Tour [1, 2]: after visiting 1 (arrive=5, service=5, depart=10), travel to 2 takes 10, arrive=20 > due=6
-        let result = split_tw(&[1, 2], &customers, &dm, 100);
+        let result = split_tw(&[1, 2], &customers, &dm, &[100]);
+        assert_eq!(result.routes.len(), 2);
+    }
+
+    #[test]
+    fn test_split_tw_time_dependent() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 2.0, 0.0, 10, 1.0)
+                .with_time_window(TimeWindow::new(0.0, 10.0).expect("valid")),
+            Customer::new(2, 3.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 5.0).expect("valid")),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        assert_eq!(split_tw(&[1, 2], &customers, &dm, &[100]).routes.len(), 1);
+
+        // Quarter speed from 2.5: 2 is only reached in time straight from the depot
+        let rush =
+            crate::distance::SpeedProfile::new(vec![(0.0, 1.0), (2.5, 0.25)]).expect("valid");
+        let dm = dm.with_speed_profile(rush);
+        assert_eq!(split_tw(&[1, 2], &customers, &dm, &[100]).routes.len(), 2);
+    }
+
+    #[test]
+    fn test_split_tw_separate_travel_times() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 5.0).expect("valid")),
+            Customer::new(2, 2.0, 0.0, 10, 0.0)
+                .with_time_window(TimeWindow::new(0.0, 5.0).expect("valid")),
+        ];
+        // Short legs that take long: 1 → 2 takes 10 minutes
+        let mut dm = DistanceMatrix::from_customers(&customers);
+        dm.set_travel_time(1, 2, 10.0);
+        let result = split_tw(&[1, 2], &customers, &dm, &[100]);
         assert_eq!(result.routes.len(), 2);
+        // Cost is still measured in distance: 2 + 4
+        assert!((result.total_distance - 6.0).abs() < 1e-10);
     }
 
     #[test]
@@ -223,7 +331,7 @@ mod tests {
             Customer::new(3, 3.0, 0.0, 10, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let result = split_tw(&[1, 2, 3], &customers, &dm, 30);
+        let result = split_tw(&[1, 2, 3], &customers, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
         assert!((result.total_distance - 6.0).abs() < 1e-10);
     }
@@ -241,7 +349,25 @@ mod tests {
         let dm = DistanceMatrix::from_customers(&customers);
         // Cust 1: arrive=1, wait to 10, service=2, depart=12
         // Cust 2: arrive=12+1=13, wait to 14, service=2, depart=16
-        let result = split_tw(&[1, 2], &customers, &dm, 30);
+        let result = split_tw(&[1, 2], &customers, &dm, &[30]);
+        assert_eq!(result.routes.len(), 1);
+    }
+
+    #[test]
+    fn test_split_tw_multiple_windows() {
+        // Customer 2 misses its morning window but can wait for the afternoon one
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 5.0)
+                .with_time_window(TimeWindow::new(0.0, 10.0).expect("valid")),
+            Customer::new(2, 2.0, 0.0, 10, 5.0).with_time_windows(vec![
+                TimeWindow::new(0.0, 3.0).expect("valid"),
+                TimeWindow::new(20.0, 30.0).expect("valid"),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Cust 1: arrive=1, depart=6; cust 2: arrive=7, wait to 20
+        let result = split_tw(&[1, 2], &customers, &dm, &[30]);
         assert_eq!(result.routes.len(), 1);
     }
 
@@ -249,7 +375,7 @@ mod tests {
     fn test_split_tw_empty() {
         let customers = vec![Customer::depot(0.0, 0.0)];
         let dm = DistanceMatrix::from_customers(&customers);
-        let result = split_tw(&[], &customers, &dm, 30);
+        let result = split_tw(&[], &customers, &dm, &[30]);
         assert!(result.routes.is_empty());
         assert_eq!(result.total_distance, 0.0);
     }
@@ -268,7 +394,7 @@ mod tests {
         ];
         let dm = DistanceMatrix::from_customers(&customers);
         // Capacity 25: can hold at most 1 customer each (15+15=30>25)
-        let result = split_tw(&[1, 2, 3], &customers, &dm, 25);
+        let result = split_tw(&[1, 2, 3], &customers, &dm, &[25]);
         assert!(result.routes.len() >= 2);
     }
 }
@@ -12,6 +12,7 @@
 //! - [`local_search`] — Local search operators (2-opt, Relocate)
 //! - [`ga`] — Genetic algorithm with Prins split (giant tour encoding)
 //! - [`alns`] — ALNS with destroy/repair operators
+//! - [`periodic`] — Periodic VRP over a multi-day horizon (visit patterns)
 
 pub mod alns;
 pub mod constructive;
@@ -20,6 +21,7 @@ pub mod evaluation;
 pub mod ga;
 pub mod local_search;
 pub mod models;
+pub mod periodic;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
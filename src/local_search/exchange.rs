@@ -9,11 +9,28 @@
 //! R1' = [a₁, ..., aᵢ, bⱼ₊₁, ..., bₘ]
 //! R2' = [b₁, ..., bⱼ, aᵢ₊₁, ..., aₙ]
 //!
-//! Accepts moves that reduce total distance and maintain capacity feasibility.
+//! Accepts moves that reduce the total
+//! [standard cost](crate::evaluation::StandardCost) of both routes, each
+//! priced by its own vehicle type, keep the on-board load within
+//! capacity at every point of both routes, keep paired pickups and
+//! deliveries together in order, let both vehicles finish within their
+//! shifts, and give each vehicle only customers whose skills it provides.
+//! Each route is costed with the matrix of its vehicle's routing profile.
+//! Visits of a split delivery move with their quantity, and a route never
+//! ends up visiting the same customer twice.
+//!
+//! [`exchange_improve_with`] prices exchanges with any [`CostFunction`] and
+//! accepts them by an [`Objective`], e.g. trading cost for a shorter
+//! makespan.
 //!
 //! # Complexity
 //!
-//! O(n² × R²) per pass, where n = customers per route, R = number of routes.
+//! O(n² × R²) per pass, where n = customers per route, R = number of routes,
+//! when the standard cost depends on distance alone (no cost of time or
+//! emissions, no time windows): each exchange is then priced in O(1) from
+//! the lengths of the tails of both routes. Otherwise, and always for
+//! [`exchange_improve_with`], both new routes are priced in O(n), for
+//! O(n³ × R²).
 //!
 //! # Reference
 //!
@@ -22,20 +39,26 @@
 //! Society* 46(12), 1433-1446.
 
 use crate::distance::DistanceMatrix;
-use crate::evaluation::RouteEvaluator;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::evaluation::{
+    priced_by_distance, priced_route, Change, CostFunction, Objective, RouteEvaluator, StandardCost,
+};
+use crate::models::{route_vehicles, Customer, Load, RequestRole, Solution, Vehicle};
 
 /// Applies inter-route cross-exchange (2-opt*) improvement.
 ///
-/// Swaps tail segments between pairs of routes to reduce total distance
-/// while respecting capacity constraints.
+/// Swaps tail segments between pairs of routes to reduce the total
+/// [standard cost](StandardCost) while respecting the capacity of the
+/// vehicle serving each route. Equivalent to [`exchange_improve_with`] under
+/// [`StandardCost`] and [`Objective::TotalCost`].
 ///
 /// # Arguments
 ///
 /// * `solution` — Current VRP solution
 /// * `customers` — All locations (index 0 = depot)
 /// * `distances` — Distance matrix
-/// * `vehicle` — Vehicle type (homogeneous fleet)
+/// * `fleet` — Vehicle types; each route uses the type whose ID matches
+///   its [`vehicle_id`](crate::models::Route::vehicle_id). The solution is
+///   returned unchanged if a route's vehicle is not in the fleet.
 ///
 /// # Examples
 ///
@@ -56,21 +79,106 @@ use crate::models::{Customer, Solution, Vehicle};
 /// let vehicles = vec![Vehicle::new(0, 20), Vehicle::new(1, 20)];
 ///
 /// let initial = nearest_neighbor(&customers, &dm, &vehicles);
-/// let improved = exchange_improve(&initial, &customers, &dm, &vehicles[0]);
+/// let improved = exchange_improve(&initial, &customers, &dm, &vehicles);
 /// assert!(improved.total_distance() <= initial.total_distance() + 1e-10);
 /// ```
 pub fn exchange_improve(
     solution: &Solution,
     customers: &[Customer],
     distances: &DistanceMatrix,
-    vehicle: &Vehicle,
+    fleet: &[Vehicle],
+) -> Solution {
+    let pricing = (!priced_by_distance(fleet, customers))
+        .then_some((&StandardCost as &dyn CostFunction, Objective::TotalCost));
+    exchange(solution, customers, distances, fleet, pricing)
+}
+
+/// Applies inter-route cross-exchange (2-opt*) improvement priced by `cost`
+/// and accepted by `objective`.
+///
+/// Each exchange is priced by the change in the cost of both routes under
+/// `cost` and accepted if it lowers the objective. Both routes keep at least one customer, so the number of
+/// routes never changes.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Solution, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::{Objective, RouteEvaluator, StandardCost};
+/// use u_routing::local_search::exchange_improve_with;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 5.0),
+///     Customer::new(2, 1.0, 0.0, 10, 5.0),
+///     Customer::new(3, 0.0, 1.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let van = Vehicle::new(0, 100);
+/// let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+/// let mut unbalanced = Solution::new();
+/// unbalanced.add_route(evaluator.build_route(&[1, 2]).0);
+/// unbalanced.add_route(evaluator.build_route(&[3]).0);
+///
+/// // Routes of 12 and 2: handing over the tail [2] evens them out
+/// let balanced = exchange_improve_with(
+///     &unbalanced,
+///     &customers,
+///     &dm,
+///     &[van],
+///     &StandardCost,
+///     Objective::Makespan,
+/// );
+/// assert!(balanced.routes().iter().all(|r| r.total_duration() < 9.0));
+/// ```
+pub fn exchange_improve_with(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    cost: &dyn CostFunction,
+    objective: Objective,
+) -> Solution {
+    exchange(
+        solution,
+        customers,
+        distances,
+        fleet,
+        Some((cost, objective)),
+    )
+}
+
+/// Cross-exchange improvement by the change in standard cost computed from
+/// distances, or priced by a cost function and accepted by an objective.
+fn exchange(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    pricing: Option<(&dyn CostFunction, Objective)>,
 ) -> Solution {
     if solution.num_routes() < 2 {
         return solution.clone();
     }
 
-    let depot = vehicle.depot_id();
     let mut routes: Vec<Vec<usize>> = solution.routes().iter().map(|r| r.customer_ids()).collect();
+    let mut quantities: Vec<Vec<i32>> = solution.routes().iter().map(|r| r.quantities()).collect();
+    let Some(vehicles) = route_vehicles(solution, fleet) else {
+        return solution.clone();
+    };
+    // Cost and duration of each route under the cost function
+    let price = |route: &[usize], vehicle: &Vehicle, cost: &dyn CostFunction| {
+        priced_route(route, customers, distances, vehicle, cost)
+    };
+    let mut current: Vec<(f64, Option<f64>)> = match pricing {
+        Some((cost, _)) => routes
+            .iter()
+            .zip(&vehicles)
+            .map(|(route, vehicle)| price(route, vehicle, cost))
+            .collect(),
+        None => Vec::new(),
+    };
 
     let mut improved = true;
     while improved {
@@ -78,15 +186,22 @@ pub fn exchange_improve(
 
         for r1 in 0..routes.len() {
             for r2 in (r1 + 1)..routes.len() {
+                let priced = pricing.map(|(cost, objective)| Pricing {
+                    cost,
+                    objective,
+                    current: &current,
+                    durations: current.iter().map(|&(_, d)| d).collect(),
+                    pair: (r1, r2),
+                });
                 if let Some((cut1, cut2, delta)) = find_best_exchange(
-                    &routes[r1],
-                    &routes[r2],
-                    depot,
+                    (&routes[r1], &quantities[r1]),
+                    (&routes[r2], &quantities[r2]),
+                    (vehicles[r1], vehicles[r2]),
                     distances,
                     customers,
-                    vehicle,
+                    priced.as_ref(),
                 ) {
-                    if delta < -1e-10 {
+                    if delta.improves() {
                         // Execute the exchange
                         let tail1: Vec<usize> = routes[r1][cut1..].to_vec();
                         let tail2: Vec<usize> = routes[r2][cut2..].to_vec();
@@ -94,6 +209,14 @@ pub fn exchange_improve(
                         routes[r2].truncate(cut2);
                         routes[r1].extend(tail2);
                         routes[r2].extend(tail1);
+                        let tail1 = quantities[r1].split_off(cut1);
+                        let tail2 = quantities[r2].split_off(cut2);
+                        quantities[r1].extend(tail2);
+                        quantities[r2].extend(tail1);
+                        if let Some((cost, _)) = pricing {
+                            current[r1] = price(&routes[r1], vehicles[r1], cost);
+                            current[r2] = price(&routes[r2], vehicles[r2], cost);
+                        }
                         improved = true;
                     }
                 }
@@ -101,78 +224,107 @@ pub fn exchange_improve(
         }
     }
 
-    rebuild_solution(&routes, solution, distances, customers, vehicle)
+    rebuild_solution(
+        &routes,
+        &quantities,
+        solution,
+        distances,
+        customers,
+        &vehicles,
+    )
 }
 
-/// Finds the best cross-exchange between two routes.
+/// How exchanges between two routes are priced and accepted.
+struct Pricing<'a> {
+    cost: &'a dyn CostFunction,
+    objective: Objective,
+    /// Cost and duration of every route.
+    current: &'a [(f64, Option<f64>)],
+    /// Duration of every route.
+    durations: Vec<Option<f64>>,
+    /// Indices of the two routes.
+    pair: (usize, usize),
+}
+
+/// Finds the best cross-exchange between two routes served by `vehicles`,
+/// by the change in standard cost computed from distances or, with
+/// `pricing`, in the objective.
 /// Returns (cut_pos_r1, cut_pos_r2, delta) if improvement found.
 fn find_best_exchange(
-    route1: &[usize],
-    route2: &[usize],
-    depot: usize,
+    (route1, quantities1): (&[usize], &[i32]),
+    (route2, quantities2): (&[usize], &[i32]),
+    (vehicle1, vehicle2): (&Vehicle, &Vehicle),
     distances: &DistanceMatrix,
     customers: &[Customer],
-    vehicle: &Vehicle,
-) -> Option<(usize, usize, f64)> {
-    let n1 = route1.len();
-    let n2 = route2.len();
-
-    let mut best: Option<(usize, usize, f64)> = None;
-
-    // cut1 ranges from 1..n1 (split after position cut1-1)
-    // cut2 ranges from 1..n2
-    for cut1 in 1..=n1 {
-        for cut2 in 1..=n2 {
-            // New routes after exchange:
-            // R1' = route1[..cut1] + route2[cut2..]
-            // R2' = route2[..cut2] + route1[cut1..]
-
-            // Check capacity
-            let new_load1: i32 = route1[..cut1]
-                .iter()
-                .chain(route2[cut2..].iter())
-                .map(|&c| customers[c].demand())
-                .sum();
-            let new_load2: i32 = route2[..cut2]
-                .iter()
-                .chain(route1[cut1..].iter())
-                .map(|&c| customers[c].demand())
-                .sum();
-
-            if new_load1 > vehicle.capacity() || new_load2 > vehicle.capacity() {
-                continue;
-            }
-
-            // Compute delta: change in total distance
-            // Old edges broken: (route1[cut1-1] → route1[cut1] or depot)
-            //                   (route2[cut2-1] → route2[cut2] or depot)
-            let old_edge1 = if cut1 < n1 {
-                distances.get(route1[cut1 - 1], route1[cut1])
-            } else {
-                distances.get(route1[cut1 - 1], depot)
-            };
-            let old_edge2 = if cut2 < n2 {
-                distances.get(route2[cut2 - 1], route2[cut2])
-            } else {
-                distances.get(route2[cut2 - 1], depot)
-            };
+    pricing: Option<&Pricing>,
+) -> Option<(usize, usize, Change)> {
+    let (ends1, ends2) = (vehicle1.route_ends(), vehicle2.route_ends());
+    let (end1, end2) = (ends1.end_node(), ends2.end_node());
+    // Each route is costed with its own vehicle's matrix; legs to the end
+    // of an open route are free
+    let (matrix1, matrix2) = (
+        distances.for_vehicle(vehicle1),
+        distances.for_vehicle(vehicle2),
+    );
+    let dist1 = |a: usize, b: usize| ends1.leg(a, b, matrix1);
+    let dist2 = |a: usize, b: usize| ends2.leg(a, b, matrix2);
+    // Length of every tail of each route when driven by either vehicle
+    let (tails1, tails2) = (tail_lengths(route1, matrix1), tail_lengths(route2, matrix2));
+    let (tails1_in_2, tails2_in_1) = if std::ptr::eq(matrix1, matrix2) {
+        (tails1.clone(), tails2.clone())
+    } else {
+        (tail_lengths(route1, matrix2), tail_lengths(route2, matrix1))
+    };
+    let mut best: Option<(usize, usize, Change)> = None;
 
-            // New edges created
-            let new_edge1 = if cut2 < n2 {
-                distances.get(route1[cut1 - 1], route2[cut2])
-            } else {
-                distances.get(route1[cut1 - 1], depot)
+    // R1' = route1[..cut1] + route2[cut2..]
+    // R2' = route2[..cut2] + route1[cut1..]
+    for cut1 in 1..=route1.len() {
+        for cut2 in 1..=route2.len() {
+            let exchanged = || {
+                (
+                    [&route1[..cut1], &route2[cut2..]].concat(),
+                    [&route2[..cut2], &route1[cut1..]].concat(),
+                )
             };
-            let new_edge2 = if cut1 < n1 {
-                distances.get(route2[cut2 - 1], route1[cut1])
-            } else {
-                distances.get(route2[cut2 - 1], depot)
+            let delta = match pricing {
+                None => {
+                    // The prefixes stay; each is followed by the other tail
+                    let (a1, a2) = (route1[cut1 - 1], route2[cut2 - 1]);
+                    let change1 = tail_distance(a1, (route2, cut2), &tails2_in_1, dist1, end1)
+                        - tail_distance(a1, (route1, cut1), &tails1, dist1, end1);
+                    let change2 = tail_distance(a2, (route1, cut1), &tails1_in_2, dist2, end2)
+                        - tail_distance(a2, (route2, cut2), &tails2, dist2, end2);
+                    Change::of_cost(
+                        vehicle1.cost_per_distance() * change1
+                            + vehicle2.cost_per_distance() * change2,
+                    )
+                }
+                Some(pricing) => {
+                    let (r1, r2) = pricing.pair;
+                    let price = |route: &[usize], vehicle: &Vehicle| {
+                        priced_route(route, customers, distances, vehicle, pricing.cost)
+                    };
+                    let (new1, new2): (Vec<usize>, Vec<usize>) = exchanged();
+                    let ((cost1, duration1), (cost2, duration2)) =
+                        (price(&new1, vehicle1), price(&new2, vehicle2));
+                    pricing.objective.change_of_routes(
+                        cost1 + cost2 - pricing.current[r1].0 - pricing.current[r2].0,
+                        &pricing.durations,
+                        &[(r1, duration1), (r2, duration2)],
+                    )
+                }
             };
 
-            let delta = (new_edge1 + new_edge2) - (old_edge1 + old_edge2);
-
-            if delta < -1e-10 && best.as_ref().is_none_or(|b| delta < b.2) {
-                best = Some((cut1, cut2, delta));
+            if delta.improves() && best.as_ref().is_none_or(|b| delta < b.2) {
+                let (new1, new2): (Vec<usize>, Vec<usize>) = exchanged();
+                let q1: Vec<i32> = [&quantities1[..cut1], &quantities2[cut2..]].concat();
+                let q2: Vec<i32> = [&quantities2[..cut2], &quantities1[cut1..]].concat();
+                if is_feasible((&new1, &q1), customers, distances, vehicle1)
+                    && is_feasible((&new2, &q2), customers, distances, vehicle2)
+                {
+                    best = Some((cut1, cut2, delta));
+                }
             }
         }
     }
@@ -180,22 +332,81 @@ fn find_best_exchange(
     best
 }
 
+/// For every cut, the length of `route[cut..]` from its first to its last
+/// customer in `distances`.
+fn tail_lengths(route: &[usize], distances: &DistanceMatrix) -> Vec<f64> {
+    let mut lengths = vec![0.0; route.len() + 1];
+    for cut in (0..route.len().saturating_sub(1)).rev() {
+        lengths[cut] = lengths[cut + 1] + distances.get(route[cut], route[cut + 1]);
+    }
+    lengths
+}
+
+/// Distance driven from `from` through the tail `route[cut..]` to `end`,
+/// where `tails` are the [tail lengths](tail_lengths) of `route` in the
+/// matrix `dist` uses.
+fn tail_distance(
+    from: usize,
+    (route, cut): (&[usize], usize),
+    tails: &[f64],
+    dist: impl Fn(usize, usize) -> f64,
+    end: usize,
+) -> f64 {
+    match (route.get(cut), route.last()) {
+        (Some(&first), Some(&last)) => dist(from, first) + tails[cut] + dist(last, end),
+        _ => dist(from, end),
+    }
+}
+
+/// Checks the skills, load profile, pickup-before-delivery order, shift
+/// end and distinct customers of a route delivering `quantities`.
+fn is_feasible(
+    (route, quantities): (&[usize], &[i32]),
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> bool {
+    if !vehicle.can_serve_route(route, customers)
+        || !vehicle.can_carry(&Load::peak_of_deliveries(route, quantities, customers))
+        || !RouteEvaluator::new(customers, distances, vehicle).within_shift(route)
+    {
+        return false;
+    }
+    let mut distinct = route.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    distinct.len() == route.len()
+        && route
+            .iter()
+            .enumerate()
+            .all(|(pos, &cid)| match customers[cid].request_role() {
+                Some(RequestRole::Pickup { delivery_id }) => {
+                    route[pos + 1..].contains(&delivery_id)
+                }
+                Some(RequestRole::Delivery { pickup_id }) => route[..pos].contains(&pickup_id),
+                None => true,
+            })
+}
+
 /// Rebuilds a Solution from customer ID sequences.
 fn rebuild_solution(
     routes: &[Vec<usize>],
+    quantities: &[Vec<i32>],
     original: &Solution,
     distances: &DistanceMatrix,
     customers: &[Customer],
-    vehicle: &Vehicle,
+    vehicles: &[&Vehicle],
 ) -> Solution {
-    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
     let mut solution = Solution::new();
 
-    for route_customers in routes {
+    for ((route_customers, route_quantities), vehicle) in
+        routes.iter().zip(quantities).zip(vehicles)
+    {
         if route_customers.is_empty() {
             continue;
         }
-        let (route, _) = evaluator.build_route(route_customers);
+        let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+        let (route, _) = evaluator.build_route_with_quantities(route_customers, route_quantities);
         solution.add_route(route);
     }
 
@@ -223,7 +434,7 @@ mod tests {
         let vehicle = Vehicle::new(0, 100);
         let vehicles = vec![vehicle.clone()];
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = exchange_improve(&sol, &customers, &dm, &vehicle);
+        let improved = exchange_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         assert_eq!(improved.num_served(), 1);
     }
 
@@ -237,10 +448,9 @@ mod tests {
             Customer::new(4, -1.0, 1.0, 10, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 20);
         let vehicles = vec![Vehicle::new(0, 20), Vehicle::new(1, 20)];
         let initial = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = exchange_improve(&initial, &customers, &dm, &vehicle);
+        let improved = exchange_improve(&initial, &customers, &dm, &vehicles);
         assert!(improved.total_distance() <= initial.total_distance() + 1e-10);
     }
 
@@ -257,12 +467,190 @@ mod tests {
         let vehicle = Vehicle::new(0, 20);
         let vehicles = vec![Vehicle::new(0, 20), Vehicle::new(1, 20)];
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = exchange_improve(&sol, &customers, &dm, &vehicle);
+        let improved = exchange_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         for route in improved.routes() {
             assert!(route.total_load() <= 20);
         }
     }
 
+    #[test]
+    fn test_exchange_respects_every_capacity_dimension() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 1.0, 10, 0.0).with_demands(vec![10, 8]),
+            Customer::new(2, -5.0, -1.0, 10, 0.0).with_demands(vec![10, 1]),
+            Customer::new(3, 5.0, -1.0, 10, 0.0).with_demands(vec![10, 8]),
+            Customer::new(4, -5.0, 1.0, 10, 0.0).with_demands(vec![10, 1]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Grouping the two east customers would overflow the second dimension
+        let vehicle = Vehicle::new(0, 0).with_capacities(vec![20, 10]);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 4]).0);
+        sol.add_route(evaluator.build_route(&[3, 2]).0);
+
+        let improved = exchange_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_served(), 4);
+        for route in improved.routes() {
+            let load = Load::of_route(&route.customer_ids(), &customers);
+            assert!(vehicle.can_carry(&load));
+        }
+    }
+
+    #[test]
+    fn test_exchange_respects_load_profile() {
+        // Each customer either drops 10 or collects 10. Both the delivery and
+        // the pickup totals always fit, but a route that collects before it
+        // drops peaks at 20.
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, -9.0, -7.0, 10, 0.0),
+            Customer::new(2, -2.0, 10.0, 0, 0.0).with_pickup(10),
+            Customer::new(3, 5.0, -4.0, 10, 0.0),
+            Customer::new(4, -9.0, -8.0, 0, 0.0).with_pickup(10),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 10);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 2]).0);
+        sol.add_route(evaluator.build_route(&[3, 4]).0);
+
+        let improved = exchange_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_served(), 4);
+        for route in improved.routes() {
+            let (_, violations) = evaluator.build_route(&route.customer_ids());
+            assert!(violations.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_exchange_heterogeneous_fleet() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 1.0, 10, 0.0),
+            Customer::new(2, -5.0, -1.0, 10, 0.0),
+            Customer::new(3, 5.0, -1.0, 10, 0.0),
+            Customer::new(4, -5.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Van takes one customer, truck takes three
+        let fleet = vec![Vehicle::new(0, 10), Vehicle::new(1, 30)];
+        let van = RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let truck = RouteEvaluator::new(&customers, &dm, &fleet[1]);
+        let mut sol = Solution::new();
+        sol.add_route(van.build_route(&[1]).0);
+        sol.add_route(truck.build_route(&[3, 2, 4]).0);
+
+        let improved = exchange_improve(&sol, &customers, &dm, &fleet);
+        assert!(improved.total_distance() <= sol.total_distance() + 1e-10);
+        for route in improved.routes() {
+            let cap = fleet[route.vehicle_id()].capacity();
+            assert!(route.total_load() <= cap);
+        }
+    }
+
+    #[test]
+    fn test_exchange_distance_pricing_matches_standard_cost() {
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain((1..=12).map(|i| {
+                let angle = i as f64 * 2.4;
+                let radius = 1.0 + (i % 4) as f64;
+                Customer::new(i, radius * angle.cos(), radius * angle.sin(), 10, 0.0)
+            }))
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(0, 100).with_fixed_cost(5.0),
+            Vehicle::new(1, 100)
+                .with_cost_per_distance(1.5)
+                .with_open_route(),
+        ];
+        let mut sol = Solution::new();
+        for (vehicle, first) in fleet.iter().zip([1, 2]) {
+            let evaluator = RouteEvaluator::new(&customers, &dm, vehicle);
+            let ids: Vec<usize> = (first..=12).step_by(2).collect();
+            sol.add_route(evaluator.build_route(&ids).0);
+        }
+        let total = |s: &Solution| -> f64 {
+            s.routes()
+                .iter()
+                .map(|r| {
+                    let vehicle = &fleet[r.vehicle_id()];
+                    priced_route(&r.customer_ids(), &customers, &dm, vehicle, &StandardCost).0
+                })
+                .sum()
+        };
+
+        // Distance deltas price every move exactly as the standard cost does
+        let fast = exchange_improve(&sol, &customers, &dm, &fleet);
+        let priced = exchange_improve_with(
+            &sol,
+            &customers,
+            &dm,
+            &fleet,
+            &StandardCost,
+            Objective::TotalCost,
+        );
+        assert!(total(&fast) < total(&sol));
+        assert!((total(&fast) - total(&priced)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exchange_makespan_outweighs_large_costs() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 5.0),
+            Customer::new(2, 1.0, 0.0, 10, 5.0),
+            Customer::new(3, 0.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_cost_per_distance(1e7);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 2]).0);
+        sol.add_route(evaluator.build_route(&[3]).0);
+
+        // Handing over [2] cuts the makespan from 12 to under 9 at a cost of 1.4e7
+        let improved = exchange_improve_with(
+            &sol,
+            &customers,
+            &dm,
+            std::slice::from_ref(&van),
+            &StandardCost,
+            Objective::Makespan,
+        );
+        assert!(improved.routes().iter().all(|r| r.total_duration() < 9.0));
+        assert!(improved.total_distance() > sol.total_distance() + 1.0);
+    }
+
+    #[test]
+    fn test_exchange_prices_vehicle_types() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 0.0, 1.0, 10, 0.0),
+            Customer::new(2, 0.0, -1.0, 10, 0.0),
+            Customer::new(3, 0.0, -2.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_cost_per_distance(5.0),
+        ];
+        let van = RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let truck = RouteEvaluator::new(&customers, &dm, &fleet[1]);
+        let mut sol = Solution::new();
+        sol.add_route(van.build_route(&[1]).0);
+        sol.add_route(truck.build_route(&[2, 3]).0);
+
+        // Handing customer 3 to the van drives 2 further but costs 6 less
+        let improved = exchange_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.routes()[0].customer_ids(), vec![1, 3]);
+        assert_eq!(improved.routes()[1].customer_ids(), vec![2]);
+        assert!((improved.total_distance() - 8.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_exchange_fixes_interleaved_routes() {
         // Route 1: [1(east), 4(west)] and Route 2: [3(east), 2(west)]
@@ -287,7 +675,7 @@ mod tests {
         sol.set_total_cost(sol.total_distance());
 
         let initial_dist = sol.total_distance();
-        let improved = exchange_improve(&sol, &customers, &dm, &vehicle);
+        let improved = exchange_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         assert!(improved.total_distance() <= initial_dist + 1e-10);
         assert_eq!(improved.num_served(), 4);
     }
@@ -302,10 +690,28 @@ mod tests {
             Customer::new(4, -2.0, -1.0, 5, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 10);
         let vehicles = vec![Vehicle::new(0, 10), Vehicle::new(1, 10)];
         let initial = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = exchange_improve(&initial, &customers, &dm, &vehicle);
+        let improved = exchange_improve(&initial, &customers, &dm, &vehicles);
         assert_eq!(improved.num_served(), 4);
     }
+
+    #[test]
+    fn test_tail_lengths() {
+        let dm = DistanceMatrix::from_data(
+            4,
+            vec![
+                0.0, 5.0, 8.0, 12.0, 5.0, 0.0, 3.0, 7.0, 8.0, 3.0, 0.0, 4.0, 12.0, 7.0, 4.0, 0.0,
+            ],
+        )
+        .expect("valid");
+
+        // Tails of [1, 2, 3]: 1→2→3, 2→3, [3] and []
+        assert_eq!(tail_lengths(&[1, 2, 3], &dm), vec![7.0, 4.0, 0.0, 0.0]);
+        // From 0 through [2, 3] and back: 8 + 4 + 12
+        let dist = |a: usize, b: usize| dm.get(a, b);
+        let tails = tail_lengths(&[1, 2, 3], &dm);
+        assert!((tail_distance(0, (&[1, 2, 3], 1), &tails, dist, 0) - 24.0).abs() < 1e-10);
+        assert!((tail_distance(1, (&[1, 2, 3], 3), &tails, dist, 0) - 5.0).abs() < 1e-10);
+    }
 }
@@ -5,15 +5,23 @@
 //! - [`three_opt_improve()`] — Intra-route 3-opt reconnection (Lin 1965)
 //! - [`relocate_improve()`] — Inter-route customer relocation
 //! - [`exchange_improve()`] — Inter-route cross-exchange / 2-opt* (Potvin & Rousseau, 1995)
+//! - [`split_improve()`] / [`merge_improve()`] — Split or merge deliveries of
+//!   splittable customers across routes (Dror & Trudeau, 1989)
+//!
+//! [`relocate_improve_with()`] and [`exchange_improve_with()`] accept moves
+//! by a route-balancing [`Objective`](crate::evaluation::Objective) instead
+//! of total distance.
 
 mod exchange;
 mod or_opt;
 mod relocate;
+mod split_delivery;
 mod three_opt;
 mod two_opt;
 
-pub use exchange::exchange_improve;
+pub use exchange::{exchange_improve, exchange_improve_with};
 pub use or_opt::{or_opt_improve, route_distance};
-pub use relocate::relocate_improve;
+pub use relocate::{relocate_improve, relocate_improve_with};
+pub use split_delivery::{merge_improve, split_improve};
 pub use three_opt::three_opt_improve;
 pub use two_opt::two_opt_improve;
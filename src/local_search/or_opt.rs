@@ -9,6 +9,9 @@
 //! the cost change from removing the segment and reinserting it at every
 //! other position.
 //!
+//! Moving a segment can overload a route with pickups or put a paired
+//! delivery before its pickup; neither is checked here.
+//!
 //! # Complexity
 //!
 //! O(n²) per pass, O(n³) worst case for convergence.
@@ -19,6 +22,7 @@
 //! Relation to the Logistics of Blood Banking". PhD thesis.
 
 use crate::distance::DistanceMatrix;
+use crate::models::RouteEnds;
 
 /// Applies Or-opt improvement to a single route.
 ///
@@ -28,7 +32,7 @@ use crate::distance::DistanceMatrix;
 /// # Arguments
 ///
 /// * `route` — Ordered customer IDs (excluding depot)
-/// * `depot` — Depot location ID
+/// * `depot` — Depot location ID, or the [`RouteEnds`] of an open route
 /// * `distances` — Distance matrix
 ///
 /// # Examples
@@ -53,16 +57,12 @@ use crate::distance::DistanceMatrix;
 /// ```
 pub fn or_opt_improve(
     route: &[usize],
-    depot: usize,
+    depot: impl Into<RouteEnds>,
     distances: &DistanceMatrix,
 ) -> (Vec<usize>, f64) {
+    let ends = depot.into();
     if route.len() < 2 {
-        let dist = if route.is_empty() {
-            0.0
-        } else {
-            distances.get(depot, route[0]) + distances.get(route[0], depot)
-        };
-        return (route.to_vec(), dist);
+        return (route.to_vec(), ends.distance(route, distances));
     }
 
     let mut current = route.to_vec();
@@ -73,36 +73,35 @@ pub fn or_opt_improve(
 
         // Try segment sizes 1, 2, 3
         for seg_len in 1..=3.min(current.len()) {
-            if try_or_opt_pass(&mut current, depot, distances, seg_len) {
+            if try_or_opt_pass(&mut current, ends, distances, seg_len) {
                 improved = true;
             }
         }
     }
 
-    let dist = route_distance(&current, depot, distances);
+    let dist = ends.distance(&current, distances);
     (current, dist)
 }
 
-/// Computes the total distance: `depot → route[0] → ... → route[n-1] → depot`.
-pub fn route_distance(route: &[usize], depot: usize, distances: &DistanceMatrix) -> f64 {
-    if route.is_empty() {
-        return 0.0;
-    }
-    let mut dist = distances.get(depot, route[0]);
-    for w in route.windows(2) {
-        dist += distances.get(w[0], w[1]);
-    }
-    dist += distances.get(route[route.len() - 1], depot);
-    dist
+/// Computes the total distance: `depot → route[0] → ... → route[n-1] → depot`,
+/// without the final leg for an open route ([`RouteEnds::open`]).
+pub fn route_distance(
+    route: &[usize],
+    depot: impl Into<RouteEnds>,
+    distances: &DistanceMatrix,
+) -> f64 {
+    depot.into().distance(route, distances)
 }
 
 /// One pass of Or-opt for a given segment length. Returns true if improved.
 fn try_or_opt_pass(
     route: &mut Vec<usize>,
-    depot: usize,
+    ends: RouteEnds,
     distances: &DistanceMatrix,
     seg_len: usize,
 ) -> bool {
+    let (start, end) = (ends.start(), ends.end_node());
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
     let n = route.len();
     if n < seg_len + 1 {
         return false;
@@ -114,9 +113,9 @@ fn try_or_opt_pass(
 
     for from in 0..=(n - seg_len) {
         // Cost of removing segment [from..from+seg_len]
-        let prev = if from == 0 { depot } else { route[from - 1] };
+        let prev = if from == 0 { start } else { route[from - 1] };
         let after = if from + seg_len >= n {
-            depot
+            end
         } else {
             route[from + seg_len]
         };
@@ -125,8 +124,7 @@ fn try_or_opt_pass(
 
         // Old edges: prev→seg_first + seg_last→after
         // New edges (after removal): prev→after
-        let removal_gain = distances.get(prev, seg_first) + distances.get(seg_last, after)
-            - distances.get(prev, after);
+        let removal_gain = dist(prev, seg_first) + dist(seg_last, after) - dist(prev, after);
 
         // Try inserting the segment at each other position
         for to in 0..=n - seg_len {
@@ -138,7 +136,7 @@ fn try_or_opt_pass(
             // Position in the route *after* removing the segment
             // We need the insertion edges
             let (ins_prev, ins_next) = if to < from {
-                let p = if to == 0 { depot } else { route[to - 1] };
+                let p = if to == 0 { start } else { route[to - 1] };
                 let nx = route[to];
                 (p, nx)
             } else {
@@ -146,7 +144,7 @@ fn try_or_opt_pass(
                 let actual_to = to; // index in original route
                 let p = route[actual_to - 1];
                 let nx = if actual_to >= n {
-                    depot
+                    end
                 } else {
                     route[actual_to]
                 };
@@ -154,9 +152,8 @@ fn try_or_opt_pass(
             };
 
             // Insertion cost: ins_prev→seg_first + seg_last→ins_next - ins_prev→ins_next
-            let insertion_cost = distances.get(ins_prev, seg_first)
-                + distances.get(seg_last, ins_next)
-                - distances.get(ins_prev, ins_next);
+            let insertion_cost =
+                dist(ins_prev, seg_first) + dist(seg_last, ins_next) - dist(ins_prev, ins_next);
 
             let delta = insertion_cost - removal_gain;
 
@@ -271,4 +268,19 @@ mod tests {
         let d = route_distance(&[1, 2, 3], 0, &dm);
         assert!((d - 6.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_or_opt_open_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let (route, dist) = or_opt_improve(&[3, 1, 2], RouteEnds::open(0), &dm);
+        assert_eq!(route, vec![1, 2, 3]);
+        assert!((dist - 10.0).abs() < 1e-10);
+        assert!((route_distance(&[1, 2, 3], RouteEnds::open(0), &dm) - 10.0).abs() < 1e-10);
+    }
 }
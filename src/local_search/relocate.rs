@@ -3,12 +3,30 @@
 //! # Algorithm
 //!
 //! Tries moving each customer from its current route to the best insertion
-//! position in another route. Accepts moves that reduce total distance and
-//! maintain capacity feasibility.
+//! position in another route. Accepts moves that reduce the total
+//! [standard cost](crate::evaluation::StandardCost) of the two routes (each
+//! priced by its own vehicle type, an emptied route saving its fixed cost),
+//! keep the on-board load within capacity at every point of the target route,
+//! and let its vehicle finish within its shift. The target vehicle must
+//! provide every skill the customer requires. Each route is costed with the
+//! matrix of its vehicle's routing profile.
+//! Customers that belong to a paired pickup-and-delivery request are not
+//! moved, since relocating one half would split the pair. Neither are
+//! visits delivering part of a split demand (see
+//! [`split_improve`](super::split_improve)).
+//!
+//! [`relocate_improve_with`] prices moves with any [`CostFunction`] and
+//! accepts them by an [`Objective`]: with a route-balancing objective a
+//! move that lowers the makespan, the spread of route durations or the
+//! number of routes is taken even if it costs more.
 //!
 //! # Complexity
 //!
-//! O(n² × R) per pass where n = customers per route, R = number of routes.
+//! O(n² × R²) per pass where n = customers per route, R = number of routes,
+//! when the standard cost depends on distance alone (no cost of time or
+//! emissions, no time windows): each move is then priced by the change in
+//! distance in O(1). Otherwise, and always for [`relocate_improve_with`],
+//! each candidate route is priced in O(n), for O(n³ × R²).
 //!
 //! # Reference
 //!
@@ -16,7 +34,10 @@
 //! Relation to the Logistics of Blood Banking". PhD thesis.
 
 use crate::distance::DistanceMatrix;
-use crate::models::{Customer, Solution, Vehicle};
+use crate::evaluation::{
+    priced_by_distance, priced_route, Change, CostFunction, Objective, RouteEvaluator, StandardCost,
+};
+use crate::models::{route_vehicles, Customer, Load, RouteEnds, Solution, Vehicle};
 
 /// A relocate move: move customer from one route to another.
 #[derive(Debug, Clone)]
@@ -25,20 +46,24 @@ struct RelocateMove {
     from_pos: usize,
     to_route: usize,
     to_pos: usize,
-    delta: f64,
+    delta: Change,
 }
 
 /// Applies inter-route relocate improvement to a solution.
 ///
-/// Iteratively moves customers between routes to reduce total distance,
-/// while respecting vehicle capacity constraints.
+/// Iteratively moves customers between routes to reduce the total
+/// [standard cost](StandardCost), while respecting the capacity of the
+/// vehicle serving each route. Equivalent to [`relocate_improve_with`]
+/// under [`StandardCost`] and [`Objective::TotalCost`].
 ///
 /// # Arguments
 ///
 /// * `solution` — Current solution (modified in-place style, returns new)
 /// * `customers` — All locations
 /// * `distances` — Distance matrix
-/// * `vehicle` — Vehicle type (homogeneous fleet)
+/// * `fleet` — Vehicle types; each route uses the type whose ID matches
+///   its [`vehicle_id`](crate::models::Route::vehicle_id). The solution is
+///   returned unchanged if a route's vehicle is not in the fleet.
 ///
 /// # Examples
 ///
@@ -58,75 +83,255 @@ struct RelocateMove {
 /// let vehicles = vec![Vehicle::new(0, 20), Vehicle::new(1, 20)];
 ///
 /// let initial = nearest_neighbor(&customers, &dm, &vehicles);
-/// let improved = relocate_improve(&initial, &customers, &dm, &vehicles[0]);
+/// let improved = relocate_improve(&initial, &customers, &dm, &vehicles);
 /// assert!(improved.total_distance() <= initial.total_distance() + 1e-10);
 /// ```
 pub fn relocate_improve(
     solution: &Solution,
     customers: &[Customer],
     distances: &DistanceMatrix,
-    vehicle: &Vehicle,
+    fleet: &[Vehicle],
+) -> Solution {
+    let pricing = (!priced_by_distance(fleet, customers))
+        .then_some((&StandardCost as &dyn CostFunction, Objective::TotalCost));
+    relocate(solution, customers, distances, fleet, pricing)
+}
+
+/// Applies inter-route relocate improvement priced by `cost` and accepted
+/// by `objective`.
+///
+/// Each move is priced by the change in the cost of both routes under
+/// `cost` (an emptied route saves its whole cost, fixed cost included) and
+/// accepted if it lowers the objective.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Solution, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::{Objective, RouteEvaluator, StandardCost};
+/// use u_routing::local_search::relocate_improve_with;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 5.0),
+///     Customer::new(2, 1.0, 0.0, 10, 5.0),
+///     Customer::new(3, 0.0, 1.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let van = Vehicle::new(0, 100);
+/// let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+/// let mut unbalanced = Solution::new();
+/// unbalanced.add_route(evaluator.build_route(&[1, 2]).0);
+/// unbalanced.add_route(evaluator.build_route(&[3]).0);
+///
+/// // Routes of 12 and 2: moving a customer over evens them out at the
+/// // cost of some extra distance
+/// let balanced = relocate_improve_with(
+///     &unbalanced,
+///     &customers,
+///     &dm,
+///     &[van],
+///     &StandardCost,
+///     Objective::Makespan,
+/// );
+/// assert!(balanced.total_distance() > unbalanced.total_distance());
+/// assert!(balanced.routes().iter().all(|r| r.total_duration() < 9.0));
+/// ```
+pub fn relocate_improve_with(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    cost: &dyn CostFunction,
+    objective: Objective,
+) -> Solution {
+    relocate(
+        solution,
+        customers,
+        distances,
+        fleet,
+        Some((cost, objective)),
+    )
+}
+
+/// Relocate improvement by the change in standard cost computed from
+/// distances, or priced by a cost function and accepted by an objective.
+fn relocate(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    pricing: Option<(&dyn CostFunction, Objective)>,
 ) -> Solution {
     if solution.num_routes() < 2 {
         return solution.clone();
     }
 
-    // Extract route customer sequences
+    // Extract route customer sequences and the vehicle serving each route
     let mut routes: Vec<Vec<usize>> = solution.routes().iter().map(|r| r.customer_ids()).collect();
+    let mut quantities: Vec<Vec<i32>> = solution.routes().iter().map(|r| r.quantities()).collect();
+    let Some(vehicles) = route_vehicles(solution, fleet) else {
+        return solution.clone();
+    };
 
     let mut improved = true;
     while improved {
         improved = false;
-        let best_move = find_best_relocate(&routes, customers, distances, vehicle);
+        let best_move = find_best_relocate(
+            (&routes, &quantities),
+            customers,
+            distances,
+            &vehicles,
+            pricing,
+        );
 
         if let Some(mv) = best_move {
-            if mv.delta < -1e-10 {
-                let customer_id = routes[mv.from_route][mv.from_pos];
-                routes[mv.from_route].remove(mv.from_pos);
+            if mv.delta.improves() {
+                let customer_id = routes[mv.from_route].remove(mv.from_pos);
+                let quantity = quantities[mv.from_route].remove(mv.from_pos);
                 routes[mv.to_route].insert(mv.to_pos, customer_id);
+                quantities[mv.to_route].insert(mv.to_pos, quantity);
                 improved = true;
             }
         }
     }
 
     // Rebuild solution
-    rebuild_solution(&routes, solution, distances, customers, vehicle)
+    rebuild_solution(
+        &routes,
+        &quantities,
+        solution,
+        distances,
+        customers,
+        &vehicles,
+    )
 }
 
-/// Finds the best single relocate move across all route pairs.
+/// Finds the best single relocate move across all route pairs, by the
+/// change in standard cost computed from distances or, with `pricing`, in
+/// the objective.
 fn find_best_relocate(
-    routes: &[Vec<usize>],
+    (routes, quantities): (&[Vec<usize>], &[Vec<i32>]),
     customers: &[Customer],
     distances: &DistanceMatrix,
-    vehicle: &Vehicle,
+    vehicles: &[&Vehicle],
+    pricing: Option<(&dyn CostFunction, Objective)>,
 ) -> Option<RelocateMove> {
-    let depot = vehicle.depot_id();
     let mut best: Option<RelocateMove> = None;
+    // Cost and duration of each route under the cost function
+    let price = |route: &[usize], r: usize, cost: &dyn CostFunction| {
+        priced_route(route, customers, distances, vehicles[r], cost)
+    };
+    let current: Vec<(f64, Option<f64>)> = match pricing {
+        Some((cost, _)) => routes
+            .iter()
+            .enumerate()
+            .map(|(r, route)| price(route, r, cost))
+            .collect(),
+        None => Vec::new(),
+    };
+    let durations: Vec<Option<f64>> = current.iter().map(|&(_, d)| d).collect();
 
     for from_r in 0..routes.len() {
         for from_pos in 0..routes[from_r].len() {
             let cid = routes[from_r][from_pos];
-            let removal_delta = removal_cost(&routes[from_r], from_pos, depot, distances);
+            let quantity = quantities[from_r][from_pos];
+            if customers[cid].request_role().is_some() || quantity != customers[cid].demand() {
+                continue;
+            }
+            let from_vehicle = vehicles[from_r];
+            let removal_delta = if routes[from_r].len() == 1 {
+                // An emptied route saves its whole cost
+                -from_vehicle.route_cost(
+                    from_vehicle
+                        .route_ends()
+                        .distance(&routes[from_r], distances.for_vehicle(from_vehicle)),
+                )
+            } else {
+                from_vehicle.cost_per_distance()
+                    * removal_cost(
+                        &routes[from_r],
+                        from_pos,
+                        from_vehicle.route_ends(),
+                        distances.for_vehicle(from_vehicle),
+                    )
+            };
+            let shortened = pricing.map(|(cost, objective)| {
+                let mut shortened = routes[from_r].clone();
+                shortened.remove(from_pos);
+                (price(&shortened, from_r, cost), cost, objective)
+            });
 
             for (to_r, to_route) in routes.iter().enumerate() {
                 if to_r == from_r {
                     continue;
                 }
+                let vehicle = vehicles[to_r];
+                let ends = vehicle.route_ends();
 
-                // Check capacity
-                let to_load: i32 = to_route.iter().map(|&c| customers[c].demand()).sum();
-                if to_load + customers[cid].demand() > vehicle.capacity() {
+                // Quick reject: the target must provide the customer's skills
+                // and at least hold the extra demand
+                if !vehicle.can_serve(&customers[cid]) {
+                    continue;
+                }
+                let mut to_load = Load::of_route(to_route, customers);
+                to_load.add(customers[cid].demands());
+                // Less of a split demand on board
+                let shortfall: i32 = to_route
+                    .iter()
+                    .zip(&quantities[to_r])
+                    .map(|(&c, &q)| customers[c].demand() - q)
+                    .sum();
+                to_load.sub(&[shortfall]);
+                if !vehicle.can_carry(&to_load) {
                     continue;
                 }
 
                 // Try all insertion positions
                 for to_pos in 0..=to_route.len() {
-                    let insertion_delta = insertion_cost(to_route, to_pos, cid, depot, distances);
-                    let delta = removal_delta + insertion_delta;
+                    let delta = match shortened {
+                        None if to_route.is_empty() => Change::of_cost(
+                            removal_delta
+                                + vehicle.route_cost(
+                                    ends.distance(&[cid], distances.for_vehicle(vehicle)),
+                                ),
+                        ),
+                        None => Change::of_cost(
+                            removal_delta
+                                + vehicle.cost_per_distance()
+                                    * insertion_cost(
+                                        to_route,
+                                        to_pos,
+                                        cid,
+                                        ends,
+                                        distances.for_vehicle(vehicle),
+                                    ),
+                        ),
+                        Some(((from_cost, from_duration), cost, objective)) => {
+                            let mut extended = to_route.clone();
+                            extended.insert(to_pos, cid);
+                            let (to_cost, to_duration) = price(&extended, to_r, cost);
+                            objective.change_of_routes(
+                                from_cost + to_cost - current[from_r].0 - current[to_r].0,
+                                &durations,
+                                &[(from_r, from_duration), (to_r, to_duration)],
+                            )
+                        }
+                    };
 
-                    if delta < -1e-10 {
+                    if delta.improves() {
                         let is_better = best.as_ref().is_none_or(|b| delta < b.delta);
-                        if is_better {
+                        if is_better
+                            && fits_at(
+                                (to_route, &quantities[to_r]),
+                                to_pos,
+                                cid,
+                                customers,
+                                distances,
+                                vehicle,
+                            )
+                        {
                             best = Some(RelocateMove {
                                 from_route: from_r,
                                 from_pos,
@@ -144,19 +349,45 @@ fn find_best_relocate(
     best
 }
 
+/// Checks the on-board load profile and the shift end after inserting
+/// `customer_id` at `pos`.
+fn fits_at(
+    (route, quantities): (&[usize], &[i32]),
+    pos: usize,
+    customer_id: usize,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> bool {
+    let mut candidate = route.to_vec();
+    candidate.insert(pos, customer_id);
+    let mut candidate_quantities = quantities.to_vec();
+    candidate_quantities.insert(pos, customers[customer_id].demand());
+    vehicle.can_carry(&Load::peak_of_deliveries(
+        &candidate,
+        &candidate_quantities,
+        customers,
+    )) && RouteEvaluator::new(customers, distances, vehicle).within_shift(&candidate)
+}
+
 /// Cost of removing customer at `pos` from route.
-fn removal_cost(route: &[usize], pos: usize, depot: usize, distances: &DistanceMatrix) -> f64 {
-    let prev = if pos == 0 { depot } else { route[pos - 1] };
+fn removal_cost(route: &[usize], pos: usize, ends: RouteEnds, distances: &DistanceMatrix) -> f64 {
+    let prev = if pos == 0 {
+        ends.start()
+    } else {
+        route[pos - 1]
+    };
     let next = if pos == route.len() - 1 {
-        depot
+        ends.end_node()
     } else {
         route[pos + 1]
     };
     let cid = route[pos];
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
 
     // Old: prev → cid → next
     // New: prev → next
-    distances.get(prev, next) - distances.get(prev, cid) - distances.get(cid, next)
+    dist(prev, next) - dist(prev, cid) - dist(cid, next)
 }
 
 /// Cost of inserting `customer_id` at `pos` in route.
@@ -164,39 +395,45 @@ fn insertion_cost(
     route: &[usize],
     pos: usize,
     customer_id: usize,
-    depot: usize,
+    ends: RouteEnds,
     distances: &DistanceMatrix,
 ) -> f64 {
-    let prev = if pos == 0 { depot } else { route[pos - 1] };
+    let prev = if pos == 0 {
+        ends.start()
+    } else {
+        route[pos - 1]
+    };
     let next = if pos == route.len() {
-        depot
+        ends.end_node()
     } else {
         route[pos]
     };
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
 
     // Old: prev → next
     // New: prev → customer_id → next
-    distances.get(prev, customer_id) + distances.get(customer_id, next) - distances.get(prev, next)
+    dist(prev, customer_id) + dist(customer_id, next) - dist(prev, next)
 }
 
 /// Rebuilds a Solution from customer ID sequences.
 fn rebuild_solution(
     routes: &[Vec<usize>],
+    quantities: &[Vec<i32>],
     original: &Solution,
     distances: &DistanceMatrix,
     customers: &[Customer],
-    vehicle: &Vehicle,
+    vehicles: &[&Vehicle],
 ) -> Solution {
-    use crate::evaluation::RouteEvaluator;
-
-    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
     let mut solution = Solution::new();
 
-    for route_customers in routes {
+    for ((route_customers, route_quantities), vehicle) in
+        routes.iter().zip(quantities).zip(vehicles)
+    {
         if route_customers.is_empty() {
             continue;
         }
-        let (route, _) = evaluator.build_route(route_customers);
+        let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+        let (route, _) = evaluator.build_route_with_quantities(route_customers, route_quantities);
         solution.add_route(route);
     }
 
@@ -213,6 +450,7 @@ fn rebuild_solution(
 mod tests {
     use super::*;
     use crate::constructive::nearest_neighbor;
+    use crate::evaluation::StandardCost;
 
     #[test]
     fn test_relocate_single_route() {
@@ -224,7 +462,7 @@ mod tests {
         let vehicle = Vehicle::new(0, 100);
         let vehicles = vec![vehicle.clone()];
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = relocate_improve(&sol, &customers, &dm, &vehicle);
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         assert_eq!(improved.num_served(), 1);
     }
 
@@ -239,7 +477,7 @@ mod tests {
         let vehicle = Vehicle::new(0, 100);
         let vehicles = vec![vehicle.clone()];
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = relocate_improve(&sol, &customers, &dm, &vehicle);
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         assert!((improved.total_distance() - sol.total_distance()).abs() < 1e-10);
     }
 
@@ -254,15 +492,37 @@ mod tests {
             Customer::new(3, 0.0, 10.0, 10, 0.0),
         ];
         let dm = DistanceMatrix::from_customers(&customers);
-        let vehicle = Vehicle::new(0, 20);
         let vehicles = vec![Vehicle::new(0, 20), Vehicle::new(1, 20)];
 
         let initial = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = relocate_improve(&initial, &customers, &dm, &vehicle);
+        let improved = relocate_improve(&initial, &customers, &dm, &vehicles);
         assert!(improved.total_distance() <= initial.total_distance() + 1e-10);
         assert_eq!(improved.num_served(), 3);
     }
 
+    #[test]
+    fn test_relocate_unknown_vehicle() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let mut sol = Solution::new();
+        for (id, cid) in [(0, 1), (7, 2)] {
+            let vehicle = Vehicle::new(id, 100);
+            sol.add_route(
+                RouteEvaluator::new(&customers, &dm, &vehicle)
+                    .build_route(&[cid])
+                    .0,
+            );
+        }
+        // Route 2 is served by vehicle 7, which is not in the fleet
+        let improved = relocate_improve(&sol, &customers, &dm, &[Vehicle::new(0, 100)]);
+        assert_eq!(improved.num_routes(), 2);
+        assert!((improved.total_distance() - sol.total_distance()).abs() < 1e-10);
+    }
+
     #[test]
     fn test_relocate_respects_capacity() {
         let customers = vec![
@@ -279,13 +539,345 @@ mod tests {
             Vehicle::new(2, 15),
         ];
         let sol = nearest_neighbor(&customers, &dm, &vehicles);
-        let improved = relocate_improve(&sol, &customers, &dm, &vehicle);
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
         // Each route should have at most capacity 15 (1 customer each)
         for route in improved.routes() {
             assert!(route.total_load() <= 15);
         }
     }
 
+    #[test]
+    fn test_relocate_respects_every_capacity_dimension() {
+        // Moving 2 next to 3 would save distance but overflow the volume dimension
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 5, 0.0).with_demands(vec![5, 1]),
+            Customer::new(2, 5.0, 5.0, 5, 0.0).with_demands(vec![5, 6]),
+            Customer::new(3, 0.0, 10.0, 5, 0.0).with_demands(vec![5, 6]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 0).with_capacities(vec![100, 10]);
+        let evaluator = crate::evaluation::RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 2]).0);
+        sol.add_route(evaluator.build_route(&[3]).0);
+
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        for route in improved.routes() {
+            let load = Load::of_route(&route.customer_ids(), &customers);
+            assert!(vehicle.can_carry(&load));
+        }
+    }
+
+    #[test]
+    fn test_relocate_respects_load_profile() {
+        // Each customer either drops 10 or collects 10; the distance-saving
+        // relocation would make a route collect before it drops (peak 20)
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, -9.0, 3.0, 10, 0.0),
+            Customer::new(2, 7.0, 3.0, 0, 0.0).with_pickup(10),
+            Customer::new(3, -7.0, 3.0, 10, 0.0),
+            Customer::new(4, -10.0, 2.0, 0, 0.0).with_pickup(10),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 10);
+        let evaluator = crate::evaluation::RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 2]).0);
+        sol.add_route(evaluator.build_route(&[3, 4]).0);
+
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        for route in improved.routes() {
+            let (_, violations) = evaluator.build_route(&route.customer_ids());
+            assert!(violations.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_relocate_heterogeneous_fleet() {
+        // Customer 1 sits next to 3 but the van serving 3 cannot take it;
+        // the truck can absorb customer 3 instead
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 1.0, 5, 0.0),
+            Customer::new(2, 0.0, 10.0, 5, 0.0),
+            Customer::new(3, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 10), Vehicle::new(1, 30)];
+        let truck = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[1]);
+        let van = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let mut sol = Solution::new();
+        sol.add_route(truck.build_route(&[1, 2]).0);
+        sol.add_route(van.build_route(&[3]).0);
+
+        let improved = relocate_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.num_routes(), 1);
+        assert_eq!(improved.routes()[0].vehicle_id(), 1);
+        assert_eq!(improved.routes()[0].total_load(), 20);
+    }
+
+    #[test]
+    fn test_relocate_respects_skills() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0).with_skills(vec![1]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_skills(vec![1]),
+        ];
+        let plain = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let equipped = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[1]);
+        let mut sol = Solution::new();
+        sol.add_route(plain.build_route(&[1, 2]).0);
+        sol.add_route(equipped.build_route(&[3]).0);
+
+        // Customer 3 cannot join the plain route; 1 and 2 move to it instead
+        let improved = relocate_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.num_routes(), 1);
+        assert_eq!(improved.routes()[0].vehicle_id(), 1);
+        assert_eq!(improved.routes()[0].customer_ids(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_relocate_routing_profile() {
+        // On a line the truck absorbs customer 3, but the bike's own
+        // matrix is ten times shorter, so its route takes everyone
+        const BIKE: usize = 1;
+        let at = |scale: f64| {
+            vec![
+                Customer::depot(0.0, 0.0),
+                Customer::new(1, scale, 0.0, 10, 0.0),
+                Customer::new(2, 2.0 * scale, 0.0, 10, 0.0),
+                Customer::new(3, 3.0 * scale, 0.0, 10, 0.0),
+            ]
+        };
+        let customers = at(1.0);
+        let dm = DistanceMatrix::from_customers(&customers)
+            .with_routing_profile(BIKE, DistanceMatrix::from_customers(&at(0.1)))
+            .expect("valid");
+        let fleet = vec![
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_routing_profile(BIKE),
+        ];
+        let truck = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let bike = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[1]);
+        let mut sol = Solution::new();
+        sol.add_route(truck.build_route(&[1, 2]).0);
+        sol.add_route(bike.build_route(&[3]).0);
+
+        let improved = relocate_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.num_routes(), 1);
+        assert_eq!(improved.routes()[0].vehicle_id(), 1);
+        assert!((improved.routes()[0].total_distance() - 0.6).abs() < 1e-10);
+
+        let plain = DistanceMatrix::from_customers(&customers);
+        let improved = relocate_improve(&sol, &customers, &plain, &fleet);
+        assert_eq!(improved.routes()[0].vehicle_id(), 0);
+    }
+
+    #[test]
+    fn test_relocate_keeps_split_visits() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 5.0, 0.0, 150, 0.0).with_split_delivery(),
+            Customer::new(2, 5.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 200);
+        let eval = crate::evaluation::RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(eval.build_route_with_quantities(&[1], &[100]).0);
+        sol.add_route(eval.build_route_with_quantities(&[1, 2], &[50, 10]).0);
+
+        // Moving the first visit would put customer 1 twice on one route
+        let improved = relocate_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_routes(), 2);
+        assert_eq!(improved.delivered(1), 150);
+        assert_eq!(improved.routes()[1].quantities(), vec![50, 10]);
+    }
+
+    #[test]
+    fn test_relocate_open_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![Vehicle::new(0, 100).with_open_route()];
+        let eval = crate::evaluation::RouteEvaluator::new(&customers, &dm, &fleet[0]);
+        let mut sol = Solution::new();
+        sol.add_route(eval.build_route(&[3]).0);
+        sol.add_route(eval.build_route(&[1, 2]).0);
+        // Open: 3 + 2
+        assert!((sol.total_distance() - 5.0).abs() < 1e-10);
+
+        let improved = relocate_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.num_routes(), 1);
+        assert_eq!(improved.routes()[0].customer_ids(), vec![1, 2, 3]);
+        assert!((improved.total_distance() - 3.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_relocate_fewest_routes() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, -1.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1]).0);
+        sol.add_route(evaluator.build_route(&[2]).0);
+        let fleet = std::slice::from_ref(&van);
+
+        // Merging saves no distance
+        let improved = relocate_improve(&sol, &customers, &dm, fleet);
+        assert_eq!(improved.num_routes(), 2);
+        let improved = relocate_improve_with(
+            &sol,
+            &customers,
+            &dm,
+            fleet,
+            &StandardCost,
+            Objective::FewestRoutes,
+        );
+        assert_eq!(improved.num_routes(), 1);
+        assert!((improved.total_distance() - 4.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_relocate_makespan_outweighs_large_costs() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 5.0),
+            Customer::new(2, 1.0, 0.0, 10, 5.0),
+            Customer::new(3, 0.0, 1.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_cost_per_distance(1e7);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1, 2]).0);
+        sol.add_route(evaluator.build_route(&[3]).0);
+
+        // Moving 2 cuts the makespan from 12 to under 9 at a cost of 1.4e7
+        let improved = relocate_improve_with(
+            &sol,
+            &customers,
+            &dm,
+            std::slice::from_ref(&van),
+            &StandardCost,
+            Objective::Makespan,
+        );
+        assert!(improved.routes().iter().all(|r| r.total_duration() < 9.0));
+        assert!(improved.total_distance() > sol.total_distance() + 1.0);
+    }
+
+    #[test]
+    fn test_relocate_overtime() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 0.0, 1.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let van = Vehicle::new(0, 100).with_overtime(4.0, 100.0);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &van);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route(&[1]).0);
+        sol.add_route(evaluator.build_route(&[2]).0);
+        let fleet = std::slice::from_ref(&van);
+
+        // Merging saves some distance but takes the van into overtime
+        let improved = relocate_improve(&sol, &customers, &dm, fleet);
+        assert_eq!(improved.num_routes(), 2);
+        let no_overtime = Vehicle::new(0, 100);
+        let improved = relocate_improve(&sol, &customers, &dm, &[no_overtime]);
+        assert_eq!(improved.num_routes(), 1);
+    }
+
+    #[test]
+    fn test_relocate_distance_pricing_matches_standard_cost() {
+        let customers: Vec<Customer> = std::iter::once(Customer::depot(0.0, 0.0))
+            .chain((1..=12).map(|i| {
+                let angle = i as f64 * 2.4;
+                let radius = 1.0 + (i % 4) as f64;
+                Customer::new(i, radius * angle.cos(), radius * angle.sin(), 10, 0.0)
+            }))
+            .collect();
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(0, 100).with_fixed_cost(5.0),
+            Vehicle::new(1, 100)
+                .with_cost_per_distance(1.5)
+                .with_open_route(),
+        ];
+        let sol = nearest_neighbor(&customers, &dm, &fleet);
+        let total = |s: &Solution| -> f64 {
+            s.routes()
+                .iter()
+                .map(|r| {
+                    let vehicle = &fleet[r.vehicle_id()];
+                    priced_route(&r.customer_ids(), &customers, &dm, vehicle, &StandardCost).0
+                })
+                .sum()
+        };
+
+        // Distance deltas price every move exactly as the standard cost does
+        let fast = relocate_improve(&sol, &customers, &dm, &fleet);
+        let priced = relocate_improve_with(
+            &sol,
+            &customers,
+            &dm,
+            &fleet,
+            &StandardCost,
+            Objective::TotalCost,
+        );
+        assert!(total(&fast) < total(&sol));
+        assert!((total(&fast) - total(&priced)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_relocate_prices_vehicle_types() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 0.0, 1.0, 10, 0.0),
+            Customer::new(2, 0.0, 2.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let fleet = vec![
+            Vehicle::new(0, 100),
+            Vehicle::new(1, 100).with_cost_per_distance(5.0),
+        ];
+        let mut sol = Solution::new();
+        sol.add_route(
+            RouteEvaluator::new(&customers, &dm, &fleet[0])
+                .build_route(&[1])
+                .0,
+        );
+        sol.add_route(
+            RouteEvaluator::new(&customers, &dm, &fleet[1])
+                .build_route(&[2])
+                .0,
+        );
+
+        // Either move saves 2 in distance; emptying the costly truck saves more
+        let improved = relocate_improve(&sol, &customers, &dm, &fleet);
+        assert_eq!(improved.num_routes(), 1);
+        assert_eq!(improved.routes()[0].vehicle_id(), 0);
+        assert!((improved.total_distance() - 4.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_removal_cost() {
         let route = vec![1, 2, 3];
@@ -298,7 +890,7 @@ mod tests {
         .expect("valid");
 
         // Removing customer 2 (pos=1): was 1→2→3, becomes 1→3
-        let cost = removal_cost(&route, 1, 0, &dm);
+        let cost = removal_cost(&route, 1, RouteEnds::closed(0), &dm);
         // Old: d(1,2) + d(2,3) = 3 + 4 = 7
         // New: d(1,3) = 7
         // Delta: 7 - 7 = 0
@@ -317,7 +909,7 @@ mod tests {
         .expect("valid");
 
         // Inserting customer 2 at pos=1: route becomes [1, 2, 3]
-        let cost = insertion_cost(&route, 1, 2, 0, &dm);
+        let cost = insertion_cost(&route, 1, 2, RouteEnds::closed(0), &dm);
         // Old: d(1,3) = 7
         // New: d(1,2) + d(2,3) = 3 + 4 = 7
         // Delta: 7 - 7 = 0
@@ -0,0 +1,455 @@
+//! Split-delivery operators: spread a customer's demand over several routes
+//! or merge its visits back together.
+//!
+//! # Algorithm
+//!
+//! Both operators apply to customers that
+//! [allow split delivery](crate::models::Customer::allows_split_delivery).
+//! A visit of such a customer is removed from its route and its quantity is
+//! redistributed greedily, cheapest target first, over the spare capacity of
+//! the other routes:
+//!
+//! - [`merge_improve()`] only adds to other visits of the same customer,
+//!   which costs no extra distance.
+//! - [`split_improve()`] may also insert a new visit at the cheapest position
+//!   of a route that does not yet serve the customer, provided the vehicle
+//!   has the required skills and still finishes within its shift.
+//!
+//! The move is rejected unless the whole quantity is placed. The best move
+//! reducing total distance, measured with each route's vehicle matrix, is
+//! applied until none is left.
+//!
+//! # Complexity
+//!
+//! O(V × R × n) per pass, where V = visits of splittable customers,
+//! R = number of routes and n = customers per route.
+//!
+//! # Reference
+//!
+//! Dror, M. & Trudeau, P. (1989). "Savings by Split Delivery Routing",
+//! *Transportation Science* 23(2), 141-145.
+
+use crate::distance::DistanceMatrix;
+use crate::evaluation::RouteEvaluator;
+use crate::models::{route_vehicles, Customer, Load, RouteEnds, Solution, Vehicle};
+
+/// Part of a moved quantity placed on a target route.
+#[derive(Debug, Clone)]
+struct Piece {
+    route: usize,
+    /// Position of the existing visit to add to, or the insertion position.
+    pos: usize,
+    existing: bool,
+    quantity: i32,
+}
+
+/// A split move: remove one visit and place its quantity on other routes.
+#[derive(Debug, Clone)]
+struct SplitMove {
+    route: usize,
+    pos: usize,
+    pieces: Vec<Piece>,
+    delta: f64,
+}
+
+/// Spreads the demand of splittable customers over the spare capacity of
+/// several routes when this reduces total distance.
+///
+/// A typical use is removing a route that serves a single large customer by
+/// sharing its demand among routes passing nearby.
+///
+/// # Arguments
+///
+/// * `solution` — Current solution
+/// * `customers` — All locations
+/// * `distances` — Distance matrix
+/// * `fleet` — Vehicle types; each route uses the type whose ID matches
+///   its [`vehicle_id`](crate::models::Route::vehicle_id). The solution is
+///   returned unchanged if a route's vehicle is not in the fleet.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::RouteEvaluator;
+/// use u_routing::local_search::split_improve;
+/// use u_routing::models::Solution;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 10.0, 0.0, 70, 0.0),
+///     Customer::new(2, 10.0, 1.0, 70, 0.0),
+///     Customer::new(3, 10.0, 0.5, 60, 0.0).with_split_delivery(),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let vehicle = Vehicle::new(0, 100);
+/// let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+/// let mut sol = Solution::new();
+/// for cid in 1..=3 {
+///     sol.add_route(evaluator.build_route(&[cid]).0);
+/// }
+///
+/// // Customer 3 is served by the two other routes, 30 each
+/// let improved = split_improve(&sol, &customers, &dm, &[vehicle]);
+/// assert_eq!(improved.num_routes(), 2);
+/// assert_eq!(improved.delivered(3), 60);
+/// ```
+pub fn split_improve(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+) -> Solution {
+    improve(solution, customers, distances, fleet, true)
+}
+
+/// Merges visits of splittable customers into other visits of the same
+/// customer when this reduces total distance.
+///
+/// Unlike [`split_improve`], no new visits are created, so the number of
+/// visits never grows.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, Vehicle};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::evaluation::RouteEvaluator;
+/// use u_routing::local_search::merge_improve;
+/// use u_routing::models::Solution;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 10.0, 0.0, 60, 0.0).with_split_delivery(),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let vehicle = Vehicle::new(0, 100);
+/// let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+/// let mut sol = Solution::new();
+/// sol.add_route(evaluator.build_route_with_quantities(&[1], &[30]).0);
+/// sol.add_route(evaluator.build_route_with_quantities(&[1], &[30]).0);
+///
+/// let merged = merge_improve(&sol, &customers, &dm, &[vehicle]);
+/// assert_eq!(merged.num_routes(), 1);
+/// assert_eq!(merged.routes()[0].quantity_of(1), Some(60));
+/// ```
+pub fn merge_improve(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+) -> Solution {
+    improve(solution, customers, distances, fleet, false)
+}
+
+/// Shared engine of [`split_improve`] and [`merge_improve`].
+fn improve(
+    solution: &Solution,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    fleet: &[Vehicle],
+    new_visits: bool,
+) -> Solution {
+    if solution.num_routes() < 2 {
+        return solution.clone();
+    }
+
+    let mut routes: Vec<Vec<usize>> = solution.routes().iter().map(|r| r.customer_ids()).collect();
+    let mut quantities: Vec<Vec<i32>> = solution.routes().iter().map(|r| r.quantities()).collect();
+    let Some(vehicles) = route_vehicles(solution, fleet) else {
+        return solution.clone();
+    };
+
+    while let Some(mv) = find_best_split(
+        (&routes, &quantities),
+        customers,
+        distances,
+        &vehicles,
+        new_visits,
+    ) {
+        let customer_id = routes[mv.route].remove(mv.pos);
+        quantities[mv.route].remove(mv.pos);
+        for piece in mv.pieces {
+            if piece.existing {
+                quantities[piece.route][piece.pos] += piece.quantity;
+            } else {
+                routes[piece.route].insert(piece.pos, customer_id);
+                quantities[piece.route].insert(piece.pos, piece.quantity);
+            }
+        }
+    }
+
+    rebuild_solution(
+        &routes,
+        &quantities,
+        solution,
+        distances,
+        customers,
+        &vehicles,
+    )
+}
+
+/// Finds the split move reducing total distance the most, if any.
+fn find_best_split(
+    (routes, quantities): (&[Vec<usize>], &[Vec<i32>]),
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicles: &[&Vehicle],
+    new_visits: bool,
+) -> Option<SplitMove> {
+    // Spare first-dimension capacity of each route
+    let spare: Vec<i32> = routes
+        .iter()
+        .zip(quantities)
+        .zip(vehicles)
+        .map(|((route, q), vehicle)| {
+            vehicle.capacity() - Load::peak_of_deliveries(route, q, customers).get(0)
+        })
+        .collect();
+
+    let mut best: Option<SplitMove> = None;
+    for from_r in 0..routes.len() {
+        for from_pos in 0..routes[from_r].len() {
+            let cid = routes[from_r][from_pos];
+            if !customers[cid].allows_split_delivery() {
+                continue;
+            }
+            let from_vehicle = vehicles[from_r];
+            let removal = removal_cost(
+                &routes[from_r],
+                from_pos,
+                from_vehicle.route_ends(),
+                distances.for_vehicle(from_vehicle),
+            );
+
+            // Candidate targets with their cost, cheapest first
+            let mut targets: Vec<(f64, Piece)> = Vec::new();
+            for (to_r, to_route) in routes.iter().enumerate() {
+                if to_r == from_r || spare[to_r] <= 0 {
+                    continue;
+                }
+                let piece = |pos, existing| Piece {
+                    route: to_r,
+                    pos,
+                    existing,
+                    quantity: 0,
+                };
+                if let Some(pos) = to_route.iter().position(|&c| c == cid) {
+                    targets.push((0.0, piece(pos, true)));
+                } else if new_visits {
+                    let vehicle = vehicles[to_r];
+                    if let Some((pos, cost)) =
+                        cheapest_insertion(to_route, cid, customers, distances, vehicle)
+                    {
+                        targets.push((cost, piece(pos, false)));
+                    }
+                }
+            }
+            targets.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut remaining = quantities[from_r][from_pos];
+            let mut delta = removal;
+            let mut pieces = Vec::new();
+            for (cost, mut piece) in targets {
+                // Costs only grow from here, so stop once nothing is saved
+                if remaining == 0 || delta + cost >= -1e-10 {
+                    break;
+                }
+                piece.quantity = remaining.min(spare[piece.route]);
+                remaining -= piece.quantity;
+                delta += cost;
+                pieces.push(piece);
+            }
+
+            if remaining == 0 && delta < -1e-10 && best.as_ref().is_none_or(|b| delta < b.delta) {
+                best = Some(SplitMove {
+                    route: from_r,
+                    pos: from_pos,
+                    pieces,
+                    delta,
+                });
+            }
+        }
+    }
+    best
+}
+
+/// Cheapest position at which `vehicle` can add a visit to `customer_id`,
+/// with its insertion cost.
+fn cheapest_insertion(
+    route: &[usize],
+    customer_id: usize,
+    customers: &[Customer],
+    distances: &DistanceMatrix,
+    vehicle: &Vehicle,
+) -> Option<(usize, f64)> {
+    if !vehicle.can_serve(&customers[customer_id]) {
+        return None;
+    }
+    let ends = vehicle.route_ends();
+    let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+    let matrix = distances.for_vehicle(vehicle);
+    let mut best: Option<(usize, f64)> = None;
+    for pos in 0..=route.len() {
+        let cost = insertion_cost(route, pos, customer_id, ends, matrix);
+        if best.is_none_or(|(_, c)| cost < c) {
+            let mut candidate = route.to_vec();
+            candidate.insert(pos, customer_id);
+            if evaluator.within_shift(&candidate) {
+                best = Some((pos, cost));
+            }
+        }
+    }
+    best
+}
+
+/// Cost of removing customer at `pos` from route.
+fn removal_cost(route: &[usize], pos: usize, ends: RouteEnds, distances: &DistanceMatrix) -> f64 {
+    let prev = if pos == 0 {
+        ends.start()
+    } else {
+        route[pos - 1]
+    };
+    let next = if pos == route.len() - 1 {
+        ends.end_node()
+    } else {
+        route[pos + 1]
+    };
+    let cid = route[pos];
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
+    dist(prev, next) - dist(prev, cid) - dist(cid, next)
+}
+
+/// Cost of inserting `customer_id` at `pos` in route.
+fn insertion_cost(
+    route: &[usize],
+    pos: usize,
+    customer_id: usize,
+    ends: RouteEnds,
+    distances: &DistanceMatrix,
+) -> f64 {
+    let prev = if pos == 0 {
+        ends.start()
+    } else {
+        route[pos - 1]
+    };
+    let next = if pos == route.len() {
+        ends.end_node()
+    } else {
+        route[pos]
+    };
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
+    dist(prev, customer_id) + dist(customer_id, next) - dist(prev, next)
+}
+
+/// Rebuilds a Solution from customer ID sequences and delivered quantities.
+fn rebuild_solution(
+    routes: &[Vec<usize>],
+    quantities: &[Vec<i32>],
+    original: &Solution,
+    distances: &DistanceMatrix,
+    customers: &[Customer],
+    vehicles: &[&Vehicle],
+) -> Solution {
+    let mut solution = Solution::new();
+
+    for ((route_customers, route_quantities), vehicle) in
+        routes.iter().zip(quantities).zip(vehicles)
+    {
+        if route_customers.is_empty() {
+            continue;
+        }
+        let evaluator = RouteEvaluator::new(customers, distances, vehicle);
+        let (route, _) = evaluator.build_route_with_quantities(route_customers, route_quantities);
+        solution.add_route(route);
+    }
+
+    for &uid in original.unassigned() {
+        solution.add_unassigned(uid);
+    }
+
+    let total_dist = solution.total_distance();
+    solution.set_total_cost(total_dist);
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two routes with spare capacity passing next to customer 3, which
+    /// has a route of its own.
+    fn setup(demand: i32) -> (Vec<Customer>, DistanceMatrix, Vehicle, Solution) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, demand, 0.0),
+            Customer::new(2, 10.0, 1.0, demand, 0.0),
+            Customer::new(3, 10.0, 0.5, 60, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        for cid in 1..=3 {
+            sol.add_route(evaluator.build_route(&[cid]).0);
+        }
+        (customers, dm, vehicle, sol)
+    }
+
+    #[test]
+    fn test_split_improve_spreads_demand() {
+        let (customers, dm, vehicle, sol) = setup(70);
+        let improved = split_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_routes(), 2);
+        assert_eq!(improved.delivered(3), 60);
+        for route in improved.routes() {
+            assert_eq!(route.quantity_of(3), Some(30));
+            assert_eq!(route.total_load(), 100);
+        }
+        assert!(improved.total_distance() < sol.total_distance());
+    }
+
+    #[test]
+    fn test_split_improve_needs_room_for_whole_demand() {
+        // Only 20 + 20 of spare capacity for a demand of 60
+        let (customers, dm, vehicle, sol) = setup(80);
+        let improved = split_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_routes(), 3);
+        assert_eq!(improved.routes()[2].quantity_of(3), Some(60));
+    }
+
+    #[test]
+    fn test_merge_improve_creates_no_visits() {
+        let (customers, dm, vehicle, sol) = setup(70);
+        let merged = merge_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(merged.num_routes(), 3);
+        assert!((merged.total_distance() - sol.total_distance()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_merge_improve_respects_capacity() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 10.0, 0.0, 150, 0.0).with_split_delivery(),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let vehicle = Vehicle::new(0, 100);
+        let evaluator = RouteEvaluator::new(&customers, &dm, &vehicle);
+        let mut sol = Solution::new();
+        sol.add_route(evaluator.build_route_with_quantities(&[1], &[100]).0);
+        sol.add_route(evaluator.build_route_with_quantities(&[1], &[50]).0);
+
+        let merged = merge_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(merged.num_routes(), 2);
+        assert_eq!(merged.delivered(1), 150);
+    }
+
+    #[test]
+    fn test_split_improve_ignores_unsplittable() {
+        let (mut customers, dm, vehicle, sol) = setup(70);
+        customers[3] = Customer::new(3, 10.0, 0.5, 60, 0.0);
+        let improved = split_improve(&sol, &customers, &dm, std::slice::from_ref(&vehicle));
+        assert_eq!(improved.num_routes(), 3);
+    }
+}
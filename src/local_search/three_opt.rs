@@ -10,6 +10,9 @@
 //! Uses first-improvement strategy: applies the first improving move found
 //! and restarts the search.
 //!
+//! Like 2-opt, the reconnections ignore the on-board load and the order of
+//! paired pickups and deliveries.
+//!
 //! # Complexity
 //!
 //! O(n³) per pass, O(n⁴) worst case for convergence.
@@ -19,8 +22,8 @@
 //! Lin, S. (1965). "Computer Solutions of the Traveling Salesman Problem",
 //! *Bell System Technical Journal* 44(10), 2245-2269.
 
-use super::or_opt::route_distance;
 use crate::distance::DistanceMatrix;
+use crate::models::RouteEnds;
 
 /// Applies 3-opt improvement to a single route.
 ///
@@ -30,7 +33,7 @@ use crate::distance::DistanceMatrix;
 /// # Arguments
 ///
 /// * `route` — Ordered customer IDs (excluding depot)
-/// * `depot` — Depot location ID
+/// * `depot` — Depot location ID, or the [`RouteEnds`] of an open route
 /// * `distances` — Distance matrix
 ///
 /// # Examples
@@ -54,12 +57,13 @@ use crate::distance::DistanceMatrix;
 /// ```
 pub fn three_opt_improve(
     route: &[usize],
-    depot: usize,
+    depot: impl Into<RouteEnds>,
     distances: &DistanceMatrix,
 ) -> (Vec<usize>, f64) {
+    let ends = depot.into();
     if route.len() < 4 {
         // 3-opt needs at least 4 customers to have 3 non-adjacent edges
-        let dist = route_distance(route, depot, distances);
+        let dist = ends.distance(route, distances);
         return (route.to_vec(), dist);
     }
 
@@ -73,7 +77,7 @@ pub fn three_opt_improve(
         'outer: for i in 0..n - 2 {
             for j in (i + 1)..n - 1 {
                 for k in (j + 1)..n {
-                    if let Some(new_route) = try_three_opt_move(&current, depot, distances, i, j, k)
+                    if let Some(new_route) = try_three_opt_move(&current, ends, distances, i, j, k)
                     {
                         current = new_route;
                         improved = true;
@@ -84,7 +88,7 @@ pub fn three_opt_improve(
         }
     }
 
-    let dist = route_distance(&current, depot, distances);
+    let dist = ends.distance(&current, distances);
     (current, dist)
 }
 
@@ -98,13 +102,14 @@ pub fn three_opt_improve(
 /// Returns Some(new_route) if an improving reconnection is found.
 fn try_three_opt_move(
     route: &[usize],
-    depot: usize,
+    ends: RouteEnds,
     distances: &DistanceMatrix,
     i: usize,
     j: usize,
     k: usize,
 ) -> Option<Vec<usize>> {
     let n = route.len();
+    let dist = |a: usize, b: usize| ends.leg(a, b, distances);
 
     // Segment endpoints for cost calculation
     let a_end = route[i];
@@ -112,12 +117,14 @@ fn try_three_opt_move(
     let b_end = route[j];
     let c_start = route[j + 1];
     let c_end = route[k];
-    let d_start = if k + 1 < n { route[k + 1] } else { depot };
+    let d_start = if k + 1 < n {
+        route[k + 1]
+    } else {
+        ends.end_node()
+    };
 
     // Current cost of the three edges being replaced
-    let old_cost = distances.get(a_end, b_start)
-        + distances.get(b_end, c_start)
-        + distances.get(c_end, d_start);
+    let old_cost = dist(a_end, b_start) + dist(b_end, c_start) + dist(c_end, d_start);
 
     // Segments (as slices)
     let seg_a = &route[..=i];
@@ -129,9 +136,7 @@ fn try_three_opt_move(
     let mut best_pattern = 0u8;
 
     // Pattern 1: A - B - C' - D (reverse C only, = 2-opt on (j, k))
-    let cost1 = distances.get(a_end, b_start)
-        + distances.get(b_end, c_end)
-        + distances.get(c_start, d_start);
+    let cost1 = dist(a_end, b_start) + dist(b_end, c_end) + dist(c_start, d_start);
     let delta1 = cost1 - old_cost;
     if delta1 < best_delta {
         best_delta = delta1;
@@ -139,9 +144,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 2: A - B' - C - D (reverse B only, = 2-opt on (i, j))
-    let cost2 = distances.get(a_end, b_end)
-        + distances.get(b_start, c_start)
-        + distances.get(c_end, d_start);
+    let cost2 = dist(a_end, b_end) + dist(b_start, c_start) + dist(c_end, d_start);
     let delta2 = cost2 - old_cost;
     if delta2 < best_delta {
         best_delta = delta2;
@@ -149,9 +152,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 3: A - B' - C' - D (reverse both B and C)
-    let cost3 = distances.get(a_end, b_end)
-        + distances.get(b_start, c_end)
-        + distances.get(c_start, d_start);
+    let cost3 = dist(a_end, b_end) + dist(b_start, c_end) + dist(c_start, d_start);
     let delta3 = cost3 - old_cost;
     if delta3 < best_delta {
         best_delta = delta3;
@@ -159,9 +160,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 4: A - C - B - D (swap B and C)
-    let cost4 = distances.get(a_end, c_start)
-        + distances.get(c_end, b_start)
-        + distances.get(b_end, d_start);
+    let cost4 = dist(a_end, c_start) + dist(c_end, b_start) + dist(b_end, d_start);
     let delta4 = cost4 - old_cost;
     if delta4 < best_delta {
         best_delta = delta4;
@@ -169,9 +168,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 5: A - C - B' - D (swap, reverse B)
-    let cost5 = distances.get(a_end, c_start)
-        + distances.get(c_end, b_end)
-        + distances.get(b_start, d_start);
+    let cost5 = dist(a_end, c_start) + dist(c_end, b_end) + dist(b_start, d_start);
     let delta5 = cost5 - old_cost;
     if delta5 < best_delta {
         best_delta = delta5;
@@ -179,9 +176,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 6: A - C' - B - D (swap, reverse C)
-    let cost6 = distances.get(a_end, c_end)
-        + distances.get(c_start, b_start)
-        + distances.get(b_end, d_start);
+    let cost6 = dist(a_end, c_end) + dist(c_start, b_start) + dist(b_end, d_start);
     let delta6 = cost6 - old_cost;
     if delta6 < best_delta {
         best_delta = delta6;
@@ -189,9 +184,7 @@ fn try_three_opt_move(
     }
 
     // Pattern 7: A - C' - B' - D (swap, reverse both)
-    let cost7 = distances.get(a_end, c_end)
-        + distances.get(c_start, b_end)
-        + distances.get(b_start, d_start);
+    let cost7 = dist(a_end, c_end) + dist(c_start, b_end) + dist(b_start, d_start);
     let delta7 = cost7 - old_cost;
     if delta7 < best_delta {
         best_delta = delta7;
@@ -253,6 +246,7 @@ fn try_three_opt_move(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::local_search::route_distance;
     use crate::models::Customer;
 
     fn square_customers() -> (Vec<Customer>, DistanceMatrix) {
@@ -364,4 +358,20 @@ mod tests {
         let (_, improved_dist) = three_opt_improve(&initial, 0, &dm);
         assert!(improved_dist <= initial_dist + 1e-10);
     }
+
+    #[test]
+    fn test_3opt_open_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 3.0, 0.0, 10, 0.0),
+            Customer::new(4, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Without the return leg the far customer belongs at the end
+        let (route, dist) = three_opt_improve(&[1, 4, 2, 3], RouteEnds::open(0), &dm);
+        assert_eq!(route, vec![1, 2, 3, 4]);
+        assert!((dist - 10.0).abs() < 1e-10);
+    }
 }
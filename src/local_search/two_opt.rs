@@ -12,6 +12,12 @@
 //! If delta < 0, reverse the segment [i+1..=j] and accept the improvement.
 //! Repeat until no further improvements are found (first-improvement strategy).
 //!
+//! Only distance is considered: a reversal can raise the on-board load of a
+//! route with pickups or put a paired delivery before its pickup, so callers
+//! with such customers must check the result (see [`Load::peak_of_route`]).
+//!
+//! [`Load::peak_of_route`]: crate::models::Load::peak_of_route
+//!
 //! # Complexity
 //!
 //! O(n²) per pass, O(n³) worst case for convergence.
@@ -22,16 +28,18 @@
 //! *Operations Research* 6(6), 791-812.
 
 use crate::distance::DistanceMatrix;
+use crate::models::RouteEnds;
 
 /// Applies 2-opt improvement to a single route (given as a sequence of customer IDs).
 ///
-/// The route is assumed to start and end at `depot`. Returns the improved
-/// customer sequence and the total route distance.
+/// The route is assumed to start and end at `depot`; pass
+/// [`RouteEnds::open`] for a route that ends at its last customer. Returns
+/// the improved customer sequence and the total route distance.
 ///
 /// # Arguments
 ///
 /// * `route` — Ordered customer IDs (excluding depot)
-/// * `depot` — Depot location ID
+/// * `depot` — Depot location ID, or the [`RouteEnds`] of an open route
 /// * `distances` — Distance matrix
 ///
 /// # Examples
@@ -56,16 +64,12 @@ use crate::distance::DistanceMatrix;
 /// ```
 pub fn two_opt_improve(
     route: &[usize],
-    depot: usize,
+    depot: impl Into<RouteEnds>,
     distances: &DistanceMatrix,
 ) -> (Vec<usize>, f64) {
+    let ends = depot.into();
     if route.len() < 2 {
-        let dist = if route.is_empty() {
-            0.0
-        } else {
-            distances.get(depot, route[0]) + distances.get(route[0], depot)
-        };
-        return (route.to_vec(), dist);
+        return (route.to_vec(), ends.distance(route, distances));
     }
 
     let mut current = route.to_vec();
@@ -77,7 +81,7 @@ pub fn two_opt_improve(
 
         for i in 0..n - 1 {
             for j in i + 1..n {
-                let delta = two_opt_delta(&current, depot, distances, i, j);
+                let delta = two_opt_delta(&current, ends, distances, i, j);
                 if delta < -1e-10 {
                     // Reverse segment [i+1..=j] — but in our 0-indexed route
                     // that means reverse [i..=j] since i and j are customer indices
@@ -88,7 +92,7 @@ pub fn two_opt_improve(
         }
     }
 
-    let dist = route_distance(&current, depot, distances);
+    let dist = ends.distance(&current, distances);
     (current, dist)
 }
 
@@ -98,37 +102,29 @@ pub fn two_opt_improve(
 /// After:  ...-prev_i - route[j] - route[j-1] - ... - route[i] - next_j-...
 fn two_opt_delta(
     route: &[usize],
-    depot: usize,
+    ends: RouteEnds,
     distances: &DistanceMatrix,
     i: usize,
     j: usize,
 ) -> f64 {
     let n = route.len();
-    let prev_i = if i == 0 { depot } else { route[i - 1] };
-    let next_j = if j == n - 1 { depot } else { route[j + 1] };
+    let prev_i = if i == 0 { ends.start() } else { route[i - 1] };
+    let next_j = if j == n - 1 {
+        ends.end_node()
+    } else {
+        route[j + 1]
+    };
 
-    let old_cost = distances.get(prev_i, route[i]) + distances.get(route[j], next_j);
-    let new_cost = distances.get(prev_i, route[j]) + distances.get(route[i], next_j);
+    let old_cost = distances.get(prev_i, route[i]) + ends.leg(route[j], next_j, distances);
+    let new_cost = distances.get(prev_i, route[j]) + ends.leg(route[i], next_j, distances);
 
     new_cost - old_cost
 }
 
-/// Computes the total distance of a route: `depot → route[0] → ... → route[n-1] → depot`.
-fn route_distance(route: &[usize], depot: usize, distances: &DistanceMatrix) -> f64 {
-    if route.is_empty() {
-        return 0.0;
-    }
-    let mut dist = distances.get(depot, route[0]);
-    for i in 0..route.len() - 1 {
-        dist += distances.get(route[i], route[i + 1]);
-    }
-    dist += distances.get(route[route.len() - 1], depot);
-    dist
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::local_search::route_distance;
     use crate::models::Customer;
 
     fn line_customers() -> (Vec<Customer>, DistanceMatrix) {
@@ -206,4 +202,19 @@ mod tests {
         let (_, improved_dist) = two_opt_improve(&initial, 0, &dm);
         assert!(improved_dist <= initial_dist + 1e-10);
     }
+
+    #[test]
+    fn test_2opt_open_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 2.0, 0.0, 10, 0.0),
+            Customer::new(3, 10.0, 0.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        // Closed, 3→1→2 and 1→2→3 both cost 20; open, the far customer goes last
+        let (route, dist) = two_opt_improve(&[2, 1, 3], RouteEnds::open(0), &dm);
+        assert_eq!(route, vec![1, 2, 3]);
+        assert!((dist - 10.0).abs() < 1e-10);
+    }
 }
@@ -1,10 +1,19 @@
 //! Customer and time window types.
 
+use super::VisitPattern;
+
 /// A time window constraint for service at a customer location.
 ///
 /// The vehicle must arrive no later than `due` and may arrive as early as
 /// `ready` (waiting is allowed if early).
 ///
+/// A window can be made soft on either side: [`with_soft_late`](Self::with_soft_late)
+/// accepts arrivals up to a hard outer limit after `due` at a penalty per
+/// time unit late, and [`with_soft_early`](Self::with_soft_early) lets
+/// service start before `ready` at a penalty per time unit early. Within
+/// the soft early margin the vehicle starts service on arrival instead of
+/// waiting.
+///
 /// # Examples
 ///
 /// ```
@@ -14,11 +23,21 @@
 /// assert!(tw.ready() <= tw.due());
 /// assert!(tw.contains(150.0));
 /// assert!(!tw.contains(250.0));
+///
+/// // Up to 30 late at 2 per time unit
+/// let soft = tw.with_soft_late(30.0, 2.0);
+/// assert!(!soft.is_violated(220.0));
+/// assert_eq!(soft.penalty(220.0), 40.0);
+/// assert!(soft.is_violated(231.0));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TimeWindow {
     ready: f64,
     due: f64,
+    max_early: f64,
+    early_penalty: f64,
+    max_late: f64,
+    late_penalty: f64,
 }
 
 impl TimeWindow {
@@ -29,7 +48,30 @@ impl TimeWindow {
         if !ready.is_finite() || !due.is_finite() || ready > due {
             return None;
         }
-        Some(Self { ready, due })
+        Some(Self {
+            ready,
+            due,
+            max_early: 0.0,
+            early_penalty: 0.0,
+            max_late: 0.0,
+            late_penalty: 0.0,
+        })
+    }
+
+    /// Allows service to start up to `max_early` before `ready`, at
+    /// `penalty_per_unit` per time unit early.
+    pub fn with_soft_early(mut self, max_early: f64, penalty_per_unit: f64) -> Self {
+        self.max_early = max_early.max(0.0);
+        self.early_penalty = penalty_per_unit;
+        self
+    }
+
+    /// Allows arrival up to `max_late` after `due`, at `penalty_per_unit`
+    /// per time unit late.
+    pub fn with_soft_late(mut self, max_late: f64, penalty_per_unit: f64) -> Self {
+        self.max_late = max_late.max(0.0);
+        self.late_penalty = penalty_per_unit;
+        self
     }
 
     /// Earliest allowable arrival time.
@@ -42,6 +84,22 @@ impl TimeWindow {
         self.due
     }
 
+    /// Earliest service start, including any soft early margin.
+    pub fn earliest(&self) -> f64 {
+        self.ready - self.max_early
+    }
+
+    /// Latest allowable arrival, including any soft late margin (the hard
+    /// outer limit).
+    pub fn latest(&self) -> f64 {
+        self.due + self.max_late
+    }
+
+    /// Returns `true` if either side of the window is soft.
+    pub fn is_soft(&self) -> bool {
+        self.max_early > 0.0 || self.max_late > 0.0
+    }
+
     /// Returns `true` if the given time falls within this window.
     pub fn contains(&self, time: f64) -> bool {
         time >= self.ready && time <= self.due
@@ -49,25 +107,106 @@ impl TimeWindow {
 
     /// Returns the waiting time if arriving at the given time.
     ///
-    /// Zero if the vehicle arrives within or after the window.
+    /// Zero if the vehicle arrives within or after the window, or within its
+    /// soft early margin.
     pub fn waiting_time(&self, arrival: f64) -> f64 {
-        if arrival < self.ready {
-            self.ready - arrival
+        if arrival < self.earliest() {
+            self.earliest() - arrival
         } else {
             0.0
         }
     }
 
-    /// Returns `true` if arriving at the given time violates this window.
+    /// Returns `true` if arriving at the given time violates this window
+    /// (arrives after its hard outer limit).
     pub fn is_violated(&self, arrival: f64) -> bool {
-        arrival > self.due
+        arrival > self.latest()
+    }
+
+    /// Earliness or lateness penalty for starting service at `start`.
+    ///
+    /// Zero within `[ready, due]` and for hard windows.
+    pub fn penalty(&self, start: f64) -> f64 {
+        if start < self.ready {
+            (self.ready - start) * self.early_penalty
+        } else if start > self.due {
+            (start - self.due) * self.late_penalty
+        } else {
+            0.0
+        }
     }
 }
 
+/// Role of a customer in a paired pickup-and-delivery request.
+///
+/// Both halves of a request must be served by the same vehicle, with the
+/// pickup visited before the delivery. The pickup usually carries a positive
+/// demand and the delivery the matching negative demand, so the load rises
+/// and falls along the route.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, RequestRole};
+///
+/// let pickup = Customer::new(1, 0.0, 0.0, 10, 0.0)
+///     .with_request_role(RequestRole::Pickup { delivery_id: 2 });
+/// let delivery = Customer::new(2, 5.0, 0.0, -10, 0.0)
+///     .with_request_role(RequestRole::Delivery { pickup_id: 1 });
+/// assert_eq!(pickup.paired_with(), Some(2));
+/// assert_eq!(delivery.paired_with(), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestRole {
+    /// Pickup stop; the goods are dropped off at `delivery_id`.
+    Pickup {
+        /// ID of the paired delivery stop.
+        delivery_id: usize,
+    },
+    /// Delivery stop; the goods were loaded at `pickup_id`.
+    Delivery {
+        /// ID of the paired pickup stop.
+        pickup_id: usize,
+    },
+}
+
 /// A customer (or depot) in a routing problem.
 ///
-/// Customer 0 is conventionally the depot. Customers have a location
-/// (coordinates), a demand, an optional time window, and a service duration.
+/// Customer 0 is conventionally the depot. For multi-depot problems further
+/// depots are created with [`depot_with_id`](Self::depot_with_id) and are
+/// never routed as stops. Customers have a location
+/// (coordinates), a demand, zero or more time windows, and a service duration.
+///
+/// A customer may accept service in several disjoint time windows (e.g.
+/// 08:00–11:00 and 14:00–17:00). A vehicle arriving between two windows
+/// waits until the next one opens.
+///
+/// Demand may have several dimensions (e.g. weight, volume, pallets), set
+/// with [`with_demands`](Self::with_demands). [`demand`](Self::demand)
+/// returns the first dimension.
+///
+/// For simultaneous pickup and delivery (VRPSPD), the demand is the quantity
+/// delivered from the depot and [`with_pickup`](Self::with_pickup) sets the
+/// quantity collected at the stop and carried back (e.g. empty crates).
+///
+/// A customer may require [skills](Self::with_skills) (e.g. refrigeration,
+/// a tail lift or a hazmat-certified driver), identified by number; only
+/// vehicles providing all of them can serve it.
+///
+/// An [optional](Self::with_optional) customer may be left unserved, which
+/// forfeits its [prize](Self::with_prize) (prize-collecting VRP); solvers
+/// serve it only if that is cheaper than its routing cost.
+///
+/// A customer with [split delivery](Self::with_split_delivery) may have
+/// its demand delivered over several routes (split-delivery VRP), e.g. when
+/// it exceeds the capacity of a single vehicle.
+///
+/// In periodic routing, a customer is visited on one of its allowed
+/// [visit patterns](Self::with_visit_patterns) over the planning horizon.
+///
+/// A [charging station](Self::charging_station) is a location where
+/// electric vehicles recharge; like a depot it is never served as a stop,
+/// but solvers insert it into routes when the battery runs low.
 ///
 /// # Examples
 ///
@@ -87,9 +226,18 @@ pub struct Customer {
     id: usize,
     x: f64,
     y: f64,
-    demand: i32,
+    demand: Vec<i32>,
+    pickup: Vec<i32>,
     service_duration: f64,
-    time_window: Option<TimeWindow>,
+    time_windows: Vec<TimeWindow>,
+    request_role: Option<RequestRole>,
+    skills: Vec<usize>,
+    prize: f64,
+    optional: bool,
+    visit_patterns: Vec<VisitPattern>,
+    split_delivery: bool,
+    is_depot: bool,
+    charge_rate: Option<f64>,
 }
 
 impl Customer {
@@ -99,20 +247,253 @@ impl Customer {
             id,
             x,
             y,
-            demand,
+            demand: vec![demand],
+            pickup: Vec::new(),
             service_duration,
-            time_window: None,
+            time_windows: Vec::new(),
+            request_role: None,
+            skills: Vec::new(),
+            prize: 0.0,
+            optional: false,
+            visit_patterns: Vec::new(),
+            split_delivery: false,
+            is_depot: false,
+            charge_rate: None,
         }
     }
 
     /// Creates a depot at the given coordinates (id=0, demand=0).
     pub fn depot(x: f64, y: f64) -> Self {
-        Self::new(0, x, y, 0, 0.0)
+        Self::depot_with_id(0, x, y)
+    }
+
+    /// Creates an additional depot with the given ID (demand=0).
+    ///
+    /// Vehicles start and end at a depot chosen with
+    /// [`Vehicle::with_depot`](crate::models::Vehicle::with_depot).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    ///
+    /// let depot = Customer::depot_with_id(3, 10.0, 0.0);
+    /// assert_eq!(depot.id(), 3);
+    /// assert!(depot.is_depot());
+    /// assert!(!Customer::new(1, 0.0, 0.0, 5, 0.0).is_depot());
+    /// ```
+    pub fn depot_with_id(id: usize, x: f64, y: f64) -> Self {
+        Self {
+            is_depot: true,
+            ..Self::new(id, x, y, 0, 0.0)
+        }
+    }
+
+    /// Creates a charging station with the given ID, recharging
+    /// `charge_rate` units of energy per time unit.
+    ///
+    /// An electric vehicle (see [`Vehicle::with_battery`](crate::models::Vehicle::with_battery))
+    /// stopping here recharges its battery fully.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    ///
+    /// let station = Customer::charging_station(5, 10.0, 0.0, 2.0);
+    /// assert!(station.is_charging_station());
+    /// assert_eq!(station.charge_rate(), Some(2.0));
+    /// assert!(!station.is_depot());
+    /// ```
+    pub fn charging_station(id: usize, x: f64, y: f64, charge_rate: f64) -> Self {
+        Self {
+            charge_rate: Some(charge_rate),
+            ..Self::new(id, x, y, 0, 0.0)
+        }
     }
 
-    /// Sets a time window for this customer.
+    /// Locates this customer by latitude and longitude in degrees, stored as
+    /// `y` and `x` respectively (see
+    /// [`DistanceMatrix::from_lat_lon`](crate::distance::DistanceMatrix::from_lat_lon)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    ///
+    /// let depot = Customer::depot(0.0, 0.0).with_lat_lon(48.8584, 2.2945);
+    /// assert_eq!(depot.latitude(), 48.8584);
+    /// assert_eq!(depot.longitude(), 2.2945);
+    /// assert_eq!(depot.x(), 2.2945);
+    /// ```
+    pub fn with_lat_lon(mut self, latitude: f64, longitude: f64) -> Self {
+        self.x = longitude;
+        self.y = latitude;
+        self
+    }
+
+    /// Sets a single time window for this customer.
     pub fn with_time_window(mut self, tw: TimeWindow) -> Self {
-        self.time_window = Some(tw);
+        self.time_windows = vec![tw];
+        self
+    }
+
+    /// Sets several time windows for this customer.
+    ///
+    /// Windows are sorted by opening time; overlapping or touching windows
+    /// are merged so the stored set is ordered and disjoint.
+    pub fn with_time_windows(mut self, mut windows: Vec<TimeWindow>) -> Self {
+        windows.sort_by(|a, b| {
+            a.ready()
+                .partial_cmp(&b.ready())
+                .expect("time windows are finite")
+        });
+        let mut merged: Vec<TimeWindow> = Vec::with_capacity(windows.len());
+        for tw in windows {
+            match merged.last_mut() {
+                Some(last) if tw.ready() <= last.due() => {
+                    // The later closing time brings its own soft late margin
+                    if tw.due() > last.due() {
+                        last.due = tw.due;
+                        last.max_late = tw.max_late;
+                        last.late_penalty = tw.late_penalty;
+                    }
+                }
+                _ => merged.push(tw),
+            }
+        }
+        self.time_windows = merged;
+        self
+    }
+
+    /// Marks this customer as one half of a pickup-and-delivery request.
+    pub fn with_request_role(mut self, role: RequestRole) -> Self {
+        self.request_role = Some(role);
+        self
+    }
+
+    /// Sets a multi-dimensional demand, replacing the one given to [`new`](Self::new).
+    pub fn with_demands(mut self, demands: Vec<i32>) -> Self {
+        self.demand = demands;
+        self
+    }
+
+    /// Sets the quantity picked up at this customer and returned to the depot.
+    pub fn with_pickup(mut self, pickup: i32) -> Self {
+        self.pickup = vec![pickup];
+        self
+    }
+
+    /// Sets a multi-dimensional pickup quantity.
+    pub fn with_pickups(mut self, pickups: Vec<i32>) -> Self {
+        self.pickup = pickups;
+        self
+    }
+
+    /// Sets the skills a vehicle must provide to serve this customer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    ///
+    /// const REFRIGERATED: usize = 0;
+    /// const TAIL_LIFT: usize = 1;
+    ///
+    /// let c = Customer::new(1, 0.0, 0.0, 10, 0.0).with_skills(vec![REFRIGERATED]);
+    /// assert!(Vehicle::new(0, 100).with_skills(vec![REFRIGERATED, TAIL_LIFT]).can_serve(&c));
+    /// assert!(!Vehicle::new(1, 100).with_skills(vec![TAIL_LIFT]).can_serve(&c));
+    /// ```
+    pub fn with_skills(mut self, skills: Vec<usize>) -> Self {
+        self.skills = skills;
+        self
+    }
+
+    /// Sets the prize collected by serving this customer (0 by default).
+    ///
+    /// The prize is in the same units as route cost. It only matters for
+    /// [optional](Self::with_optional) customers, where it measures their
+    /// priority against the routing cost of serving them.
+    pub fn with_prize(mut self, prize: f64) -> Self {
+        self.prize = prize;
+        self
+    }
+
+    /// Marks this customer as optional: solvers may leave it unserved,
+    /// forfeiting its prize.
+    ///
+    /// The ALNS and the GA leave an optional customer out when its prize
+    /// does not cover its routing cost. Construction heuristics and local
+    /// search serve every customer they can.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Customer;
+    ///
+    /// let c = Customer::new(1, 0.0, 0.0, 10, 0.0).with_prize(25.0).with_optional();
+    /// assert!(c.is_optional());
+    /// assert_eq!(c.prize(), 25.0);
+    /// ```
+    pub fn with_optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// Allows the demand of this customer to be delivered over several
+    /// routes (split-delivery VRP).
+    ///
+    /// Splitting applies to plain deliveries with a single demand
+    /// dimension; customers with pickups, several demand dimensions or a
+    /// pickup-and-delivery role are always served in one visit.
+    ///
+    /// Demands are split by [`nearest_neighbor`](crate::constructive::nearest_neighbor)
+    /// and the [`split_improve`](crate::local_search::split_improve) and
+    /// [`merge_improve`](crate::local_search::merge_improve) local searches;
+    /// the GA and ALNS solvers still serve every customer in one visit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::constructive::nearest_neighbor;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 5.0, 0.0, 150, 0.0).with_split_delivery(),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let vehicles = vec![Vehicle::new(0, 100), Vehicle::new(1, 100)];
+    ///
+    /// let sol = nearest_neighbor(&customers, &dm, &vehicles);
+    /// assert_eq!(sol.num_routes(), 2);
+    /// assert_eq!(sol.delivered(1), 150);
+    /// ```
+    pub fn with_split_delivery(mut self) -> Self {
+        self.split_delivery = true;
+        self
+    }
+
+    /// Sets the day combinations on which this customer may be visited over
+    /// a periodic planning horizon (see [`crate::periodic`]).
+    ///
+    /// A customer without patterns is visited every day.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, VisitPattern};
+    ///
+    /// // Twice a week: Monday–Thursday or Tuesday–Friday
+    /// let c = Customer::new(1, 0.0, 0.0, 10, 0.0).with_visit_patterns(vec![
+    ///     VisitPattern::new(vec![0, 3]),
+    ///     VisitPattern::new(vec![1, 4]),
+    /// ]);
+    /// assert_eq!(c.visit_patterns().len(), 2);
+    /// ```
+    pub fn with_visit_patterns(mut self, patterns: Vec<VisitPattern>) -> Self {
+        self.visit_patterns = patterns;
         self
     }
 
@@ -131,9 +512,38 @@ impl Customer {
         self.y
     }
 
+    /// Latitude in degrees, for a customer located by
+    /// [latitude and longitude](Self::with_lat_lon).
+    pub fn latitude(&self) -> f64 {
+        self.y
+    }
+
+    /// Longitude in degrees, for a customer located by
+    /// [latitude and longitude](Self::with_lat_lon).
+    pub fn longitude(&self) -> f64 {
+        self.x
+    }
+
     /// Demand at this customer (units to deliver or pick up).
+    ///
+    /// For multi-dimensional demand, this is the first dimension.
     pub fn demand(&self) -> i32 {
-        self.demand
+        self.demand.first().copied().unwrap_or(0)
+    }
+
+    /// Demand in every dimension.
+    pub fn demands(&self) -> &[i32] {
+        &self.demand
+    }
+
+    /// Quantity picked up at this customer (first dimension, 0 if none).
+    pub fn pickup(&self) -> i32 {
+        self.pickup.first().copied().unwrap_or(0)
+    }
+
+    /// Pickup quantity in every dimension (empty if none).
+    pub fn pickups(&self) -> &[i32] {
+        &self.pickup
     }
 
     /// Service duration at this customer.
@@ -141,9 +551,124 @@ impl Customer {
         self.service_duration
     }
 
-    /// Time window constraint, if any.
+    /// Earliest time window constraint, if any.
     pub fn time_window(&self) -> Option<&TimeWindow> {
-        self.time_window.as_ref()
+        self.time_windows.first()
+    }
+
+    /// All time windows, ordered and disjoint (empty = no constraint).
+    pub fn time_windows(&self) -> &[TimeWindow] {
+        &self.time_windows
+    }
+
+    /// Pickup-and-delivery role, if this customer belongs to a paired request.
+    pub fn request_role(&self) -> Option<RequestRole> {
+        self.request_role
+    }
+
+    /// Skills required of the vehicle serving this customer (empty = any vehicle).
+    pub fn required_skills(&self) -> &[usize] {
+        &self.skills
+    }
+
+    /// Prize collected by serving this customer.
+    pub fn prize(&self) -> f64 {
+        self.prize
+    }
+
+    /// Returns `true` if solvers may leave this customer unserved.
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Returns `true` if the demand may be delivered over several routes.
+    ///
+    /// Only plain single-dimension deliveries are split (see
+    /// [`with_split_delivery`](Self::with_split_delivery)).
+    pub fn allows_split_delivery(&self) -> bool {
+        self.split_delivery
+            && self.demand.len() <= 1
+            && self.pickup.iter().all(|&p| p == 0)
+            && self.request_role.is_none()
+    }
+
+    /// Allowed visit patterns over a periodic horizon (empty = every day).
+    pub fn visit_patterns(&self) -> &[VisitPattern] {
+        &self.visit_patterns
+    }
+
+    /// Returns `true` if this location is a depot rather than a stop.
+    pub fn is_depot(&self) -> bool {
+        self.is_depot
+    }
+
+    /// Returns `true` if this location is a charging station.
+    pub fn is_charging_station(&self) -> bool {
+        self.charge_rate.is_some()
+    }
+
+    /// Energy recharged per time unit, if this is a charging station.
+    pub fn charge_rate(&self) -> Option<f64> {
+        self.charge_rate
+    }
+
+    /// ID of the other half of this customer's pickup-and-delivery request.
+    pub fn paired_with(&self) -> Option<usize> {
+        match self.request_role? {
+            RequestRole::Pickup { delivery_id } => Some(delivery_id),
+            RequestRole::Delivery { pickup_id } => Some(pickup_id),
+        }
+    }
+
+    /// Latest allowed arrival in the last time window (including any soft
+    /// late margin), if any.
+    pub fn latest_due(&self) -> Option<f64> {
+        self.time_windows.last().map(|tw| tw.latest())
+    }
+
+    /// Earliest feasible service start when arriving at `arrival`.
+    ///
+    /// Picks the earliest window that has not yet closed and waits until it
+    /// opens (or its soft early margin begins) if necessary. Returns `None`
+    /// if every window has closed. Without time windows, service starts on
+    /// arrival.
+    pub fn service_start(&self, arrival: f64) -> Option<f64> {
+        if self.time_windows.is_empty() {
+            return Some(arrival);
+        }
+        self.serving_window(arrival)
+            .map(|tw| arrival + tw.waiting_time(arrival))
+    }
+
+    /// Soft time-window penalty for arriving at `arrival`, served as in
+    /// [`service_start`](Self::service_start); infinite if every window has
+    /// closed, so that no soft penalty is ever cheaper than missing a hard
+    /// window.
+    pub fn time_window_penalty(&self, arrival: f64) -> f64 {
+        if self.time_windows.is_empty() {
+            return 0.0;
+        }
+        self.serving_window(arrival).map_or(f64::INFINITY, |tw| {
+            tw.penalty(arrival + tw.waiting_time(arrival))
+        })
+    }
+
+    /// Time past the due time of its window at which service starts when
+    /// arriving at `arrival`, served as in [`service_start`](Self::service_start);
+    /// measured from the last window's due time if every window has closed.
+    pub fn lateness(&self, arrival: f64) -> f64 {
+        match self.serving_window(arrival) {
+            Some(tw) => (arrival + tw.waiting_time(arrival) - tw.due()).max(0.0),
+            None => self
+                .time_windows
+                .last()
+                .map_or(0.0, |tw| (arrival - tw.due()).max(0.0)),
+        }
+    }
+
+    /// The earliest window still open at `arrival`.
+    fn serving_window(&self, arrival: f64) -> Option<&TimeWindow> {
+        self.time_windows.iter().find(|tw| !tw.is_violated(arrival))
     }
 
     /// Euclidean distance to another customer.
@@ -154,6 +679,26 @@ impl Customer {
     }
 }
 
+/// IDs of the routable stops, i.e. every location except index 0, the
+/// depots and the charging stations.
+pub(crate) fn stop_ids(customers: &[Customer]) -> impl Iterator<Item = usize> + '_ {
+    (1..customers.len())
+        .filter(|&i| !customers[i].is_depot() && !customers[i].is_charging_station())
+}
+
+/// Returns `true` if every paired delivery on `route` comes after its
+/// pickup on the same route, and every paired pickup before its delivery.
+pub(crate) fn precedence_holds(route: &[usize], customers: &[Customer]) -> bool {
+    route
+        .iter()
+        .enumerate()
+        .all(|(pos, &cid)| match customers[cid].request_role() {
+            Some(RequestRole::Pickup { delivery_id }) => route[pos + 1..].contains(&delivery_id),
+            Some(RequestRole::Delivery { pickup_id }) => route[..pos].contains(&pickup_id),
+            None => true,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +743,26 @@ mod tests {
         assert!(tw.is_violated(20.1));
     }
 
+    #[test]
+    fn test_time_window_soft() {
+        let hard = TimeWindow::new(10.0, 20.0).expect("valid");
+        assert!(!hard.is_soft());
+        assert_eq!(hard.penalty(25.0), 0.0);
+
+        let tw = hard.with_soft_early(4.0, 1.0).with_soft_late(5.0, 3.0);
+        assert!(tw.is_soft());
+        assert_eq!(tw.earliest(), 6.0);
+        assert_eq!(tw.latest(), 25.0);
+        // Within the early margin service starts on arrival
+        assert!((tw.waiting_time(8.0)).abs() < 1e-10);
+        assert!((tw.waiting_time(5.0) - 1.0).abs() < 1e-10);
+        assert!((tw.penalty(8.0) - 2.0).abs() < 1e-10);
+        assert_eq!(tw.penalty(15.0), 0.0);
+        assert!((tw.penalty(22.0) - 6.0).abs() < 1e-10);
+        assert!(!tw.is_violated(25.0));
+        assert!(tw.is_violated(25.1));
+    }
+
     #[test]
     fn test_customer_new() {
         let c = Customer::new(1, 10.0, 20.0, 5, 3.0);
@@ -225,6 +790,117 @@ mod tests {
         assert_eq!(c.time_window().expect("has tw").ready(), 100.0);
     }
 
+    #[test]
+    fn test_customer_multi_dimensional_demand() {
+        let c = Customer::new(1, 0.0, 0.0, 5, 0.0).with_demands(vec![40, 3, 1]);
+        assert_eq!(c.demand(), 40);
+        assert_eq!(c.demands(), &[40, 3, 1]);
+    }
+
+    #[test]
+    fn test_customer_multiple_time_windows() {
+        let morning = TimeWindow::new(8.0, 11.0).expect("valid");
+        let afternoon = TimeWindow::new(14.0, 17.0).expect("valid");
+        let c = Customer::new(1, 0.0, 0.0, 5, 1.0).with_time_windows(vec![afternoon, morning]);
+        assert_eq!(c.time_windows(), &[morning, afternoon]);
+        assert_eq!(c.time_window(), Some(&morning));
+        assert_eq!(c.latest_due(), Some(17.0));
+    }
+
+    #[test]
+    fn test_customer_overlapping_time_windows_merged() {
+        let c = Customer::new(1, 0.0, 0.0, 5, 1.0).with_time_windows(vec![
+            TimeWindow::new(10.0, 20.0).expect("valid"),
+            TimeWindow::new(15.0, 25.0).expect("valid"),
+            TimeWindow::new(30.0, 40.0).expect("valid"),
+        ]);
+        assert_eq!(c.time_windows().len(), 2);
+        assert_eq!(c.time_windows()[0].due(), 25.0);
+    }
+
+    #[test]
+    fn test_customer_soft_time_window_penalty() {
+        let tw = TimeWindow::new(10.0, 20.0).expect("valid");
+        let c = Customer::new(1, 0.0, 0.0, 5, 1.0).with_time_windows(vec![
+            tw.with_soft_early(2.0, 1.0),
+            TimeWindow::new(15.0, 30.0)
+                .expect("valid")
+                .with_soft_late(10.0, 2.0),
+        ]);
+        // Merged: the later window's soft late margin is kept
+        assert_eq!(c.time_windows().len(), 1);
+        assert_eq!(c.latest_due(), Some(40.0));
+        assert_eq!(c.service_start(9.0), Some(9.0));
+        assert!((c.time_window_penalty(9.0) - 1.0).abs() < 1e-10);
+        assert_eq!(c.time_window_penalty(12.0), 0.0);
+        assert!((c.time_window_penalty(35.0) - 10.0).abs() < 1e-10);
+        assert_eq!(c.service_start(41.0), None);
+        assert_eq!(c.time_window_penalty(41.0), f64::INFINITY);
+        assert_eq!(c.lateness(25.0), 0.0);
+        assert!((c.lateness(35.0) - 5.0).abs() < 1e-10);
+        assert!((c.lateness(41.0) - 11.0).abs() < 1e-10);
+        assert_eq!(Customer::new(2, 0.0, 0.0, 5, 1.0).lateness(99.0), 0.0);
+    }
+
+    #[test]
+    fn test_customer_service_start() {
+        let c = Customer::new(1, 0.0, 0.0, 5, 1.0).with_time_windows(vec![
+            TimeWindow::new(8.0, 11.0).expect("valid"),
+            TimeWindow::new(14.0, 17.0).expect("valid"),
+        ]);
+        assert_eq!(c.service_start(5.0), Some(8.0)); // wait for first window
+        assert_eq!(c.service_start(9.0), Some(9.0)); // inside first window
+        assert_eq!(c.service_start(12.0), Some(14.0)); // lunch gap: wait
+        assert_eq!(c.service_start(17.5), None); // all windows closed
+
+        let open = Customer::new(2, 0.0, 0.0, 5, 1.0);
+        assert_eq!(open.service_start(99.0), Some(99.0));
+    }
+
+    #[test]
+    fn test_customer_pickup() {
+        let c = Customer::new(1, 0.0, 0.0, 10, 1.0).with_pickup(4);
+        assert_eq!(c.demand(), 10);
+        assert_eq!(c.pickup(), 4);
+        assert_eq!(Customer::new(2, 0.0, 0.0, 10, 1.0).pickups(), &[] as &[i32]);
+    }
+
+    #[test]
+    fn test_customer_skills() {
+        let c = Customer::new(1, 0.0, 0.0, 10, 1.0);
+        assert!(c.required_skills().is_empty());
+        let c = c.with_skills(vec![2, 5]);
+        assert_eq!(c.required_skills(), &[2, 5]);
+    }
+
+    #[test]
+    fn test_customer_prize() {
+        let c = Customer::new(1, 0.0, 0.0, 10, 1.0);
+        assert_eq!(c.prize(), 0.0);
+        assert!(!c.is_optional());
+        let c = c.with_prize(40.0);
+        assert_eq!(c.prize(), 40.0);
+        assert!(!c.is_optional());
+        assert!(c.with_optional().is_optional());
+    }
+
+    #[test]
+    fn test_customer_split_delivery() {
+        let c = Customer::new(1, 0.0, 0.0, 10, 1.0);
+        assert!(!c.allows_split_delivery());
+        assert!(c.clone().with_split_delivery().allows_split_delivery());
+        // Only plain single-dimension deliveries are split
+        assert!(!c
+            .clone()
+            .with_pickup(3)
+            .with_split_delivery()
+            .allows_split_delivery());
+        assert!(!c
+            .with_demands(vec![10, 2])
+            .with_split_delivery()
+            .allows_split_delivery());
+    }
+
     #[test]
     fn test_customer_distance() {
         let a = Customer::new(0, 0.0, 0.0, 0, 0.0);
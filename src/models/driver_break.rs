@@ -0,0 +1,90 @@
+//! Mandatory driver breaks.
+//!
+//! A vehicle may require its driver to rest during a route, either once
+//! within a time window (e.g. a lunch break) or whenever the continuous
+//! driving time would exceed a limit (e.g. 30 minutes after 4.5 hours).
+//!
+//! Breaks are taken at a stop, just before departing on the leg that would
+//! otherwise run into the window or over the driving limit. Service and
+//! waiting time do not count as driving.
+
+use super::TimeWindow;
+
+/// A break the driver of a vehicle must take.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{DriverBreak, TimeWindow, Vehicle};
+///
+/// let lunch = DriverBreak::in_window(TimeWindow::new(720.0, 780.0).expect("valid"), 30.0);
+/// let rest = DriverBreak::after_driving(270.0, 30.0);
+/// assert_eq!(lunch.duration(), 30.0);
+///
+/// let v = Vehicle::new(0, 100).with_break(rest);
+/// assert_eq!(v.driver_break(), Some(&rest));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriverBreak {
+    /// A single break that must start within `window`.
+    Window {
+        /// Window the break must start in.
+        window: TimeWindow,
+        /// Length of the break.
+        duration: f64,
+    },
+    /// A break due before the continuous driving time exceeds `max_driving`;
+    /// taken again after each further `max_driving` of driving.
+    AfterDriving {
+        /// Maximum driving time between breaks.
+        max_driving: f64,
+        /// Length of the break.
+        duration: f64,
+    },
+}
+
+impl DriverBreak {
+    /// A break of `duration` that must start within `window`.
+    pub fn in_window(window: TimeWindow, duration: f64) -> Self {
+        Self::Window { window, duration }
+    }
+
+    /// A break of `duration` due after at most `max_driving` of driving.
+    pub fn after_driving(max_driving: f64, duration: f64) -> Self {
+        Self::AfterDriving {
+            max_driving,
+            duration,
+        }
+    }
+
+    /// Length of the break.
+    pub fn duration(&self) -> f64 {
+        match *self {
+            Self::Window { duration, .. } | Self::AfterDriving { duration, .. } => duration,
+        }
+    }
+}
+
+/// A break placed in a route's schedule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduledBreak {
+    /// Index of the visit the break precedes; equal to the number of visits
+    /// for a break taken before the return to the depot.
+    pub before_visit: usize,
+    /// Time the break starts.
+    pub start: f64,
+    /// Time the break ends.
+    pub end: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_driver_break_duration() {
+        let tw = TimeWindow::new(10.0, 20.0).expect("valid");
+        assert_eq!(DriverBreak::in_window(tw, 5.0).duration(), 5.0);
+        assert_eq!(DriverBreak::after_driving(270.0, 30.0).duration(), 30.0);
+    }
+}
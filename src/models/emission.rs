@@ -0,0 +1,106 @@
+//! Load-dependent fuel consumption and CO2 emissions.
+//!
+//! # Algorithm
+//!
+//! The emission rate per distance unit grows linearly with the share of the
+//! vehicle's capacity on board, from the rate of the empty vehicle to that of
+//! the fully loaded one. Each leg emits its distance times the rate for the
+//! load carried along it, so delivering heavy customers first lowers the
+//! emissions of a route of the same length.
+//!
+//! # Complexity
+//!
+//! O(n) per route of n customers.
+//!
+//! # Reference
+//!
+//! Xiao, Y., Zhao, Q., Kaku, I. & Xu, Y. (2012). "Development of a fuel
+//! consumption optimization model for the capacitated vehicle routing
+//! problem", *Computers & Operations Research* 39(7), 1419-1431.
+
+/// Emission rates of a vehicle type, per distance unit.
+///
+/// The rates can measure fuel (litres) or CO2 (kg) alike; multiply fuel by
+/// the fuel's emission factor to report CO2.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::EmissionModel;
+///
+/// // 0.8 kg CO2 per km empty, 1.2 kg fully loaded
+/// let model = EmissionModel::new(0.8, 1.2).expect("valid");
+/// assert!((model.rate(50, 100) - 1.0).abs() < 1e-10);
+/// assert!((model.emissions(10.0, 100, 100) - 12.0).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmissionModel {
+    empty_rate: f64,
+    full_rate: f64,
+}
+
+impl EmissionModel {
+    /// Creates a model from the rates of the empty and fully loaded vehicle.
+    ///
+    /// Returns `None` if either rate is negative or not finite.
+    pub fn new(empty_rate: f64, full_rate: f64) -> Option<Self> {
+        let valid = |rate: f64| rate.is_finite() && rate >= 0.0;
+        if !valid(empty_rate) || !valid(full_rate) {
+            return None;
+        }
+        Some(Self {
+            empty_rate,
+            full_rate,
+        })
+    }
+
+    /// Emission rate of the empty vehicle.
+    pub fn empty_rate(&self) -> f64 {
+        self.empty_rate
+    }
+
+    /// Emission rate of the fully loaded vehicle.
+    pub fn full_rate(&self) -> f64 {
+        self.full_rate
+    }
+
+    /// Emission rate per distance unit carrying `load` out of `capacity`.
+    ///
+    /// The rate is extrapolated beyond full load; a vehicle without capacity
+    /// always emits at the empty rate.
+    pub fn rate(&self, load: i32, capacity: i32) -> f64 {
+        if capacity <= 0 {
+            return self.empty_rate;
+        }
+        let share = f64::from(load) / f64::from(capacity);
+        self.empty_rate + (self.full_rate - self.empty_rate) * share
+    }
+
+    /// Emissions of driving `distance` while carrying `load` out of
+    /// `capacity`.
+    pub fn emissions(&self, distance: f64, load: i32, capacity: i32) -> f64 {
+        distance * self.rate(load, capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emission_model_validation() {
+        assert!(EmissionModel::new(-1.0, 1.0).is_none());
+        assert!(EmissionModel::new(1.0, f64::NAN).is_none());
+        assert!(EmissionModel::new(0.0, 0.0).is_some());
+    }
+
+    #[test]
+    fn test_emission_model_rate() {
+        let model = EmissionModel::new(1.0, 2.0).expect("valid");
+        assert!((model.rate(0, 100) - 1.0).abs() < 1e-10);
+        assert!((model.rate(25, 100) - 1.25).abs() < 1e-10);
+        assert!((model.rate(100, 100) - 2.0).abs() < 1e-10);
+        assert!((model.rate(50, 0) - 1.0).abs() < 1e-10);
+        assert!((model.emissions(4.0, 50, 100) - 6.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,384 @@
+//! Multi-dimensional load type.
+
+use super::Customer;
+
+/// A multi-dimensional load (e.g. weight, volume, pallets).
+///
+/// Each dimension is tracked independently. Dimensions that are not present
+/// are treated as zero, so loads of different lengths can be combined.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Load;
+///
+/// let mut load = Load::new();
+/// load.add(&[10, 2]);
+/// load.add(&[5]);
+/// assert_eq!(load.amounts(), &[15, 2]);
+///
+/// assert!(load.fits(&[20, 2]));
+/// assert_eq!(load.exceeded_dimension(&[20, 1]), Some(1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Load {
+    amounts: Vec<i32>,
+}
+
+impl Load {
+    /// Creates an empty (zero) load.
+    pub fn new() -> Self {
+        Self {
+            amounts: Vec::new(),
+        }
+    }
+
+    /// Sums the demands of the given customers.
+    pub fn of_route(route: &[usize], customers: &[Customer]) -> Self {
+        let mut load = Self::new();
+        for &cid in route {
+            load.add(customers[cid].demands());
+        }
+        load
+    }
+
+    /// Adds the given amounts dimension by dimension.
+    pub fn add(&mut self, amounts: &[i32]) {
+        if self.amounts.len() < amounts.len() {
+            self.amounts.resize(amounts.len(), 0);
+        }
+        for (a, &b) in self.amounts.iter_mut().zip(amounts) {
+            *a += b;
+        }
+    }
+
+    /// Subtracts the given amounts dimension by dimension.
+    pub fn sub(&mut self, amounts: &[i32]) {
+        if self.amounts.len() < amounts.len() {
+            self.amounts.resize(amounts.len(), 0);
+        }
+        for (a, &b) in self.amounts.iter_mut().zip(amounts) {
+            *a -= b;
+        }
+    }
+
+    /// Raises each dimension to at least the matching dimension of `other`.
+    pub fn max_assign(&mut self, other: &Load) {
+        if self.amounts.len() < other.amounts.len() {
+            self.amounts.resize(other.amounts.len(), 0);
+        }
+        for (a, &b) in self.amounts.iter_mut().zip(&other.amounts) {
+            *a = (*a).max(b);
+        }
+    }
+
+    /// On-board load at every point of the given route.
+    ///
+    /// Entry 0 is the load leaving the depot, entry `k` the load after the
+    /// `k`-th stop. The vehicle leaves the depot with the demands of all
+    /// unpaired customers on the route; at each stop it drops that demand
+    /// and collects the customer's pickup quantity. Paired pickup-and-delivery
+    /// stops instead change the load by their (signed) demand, so the load
+    /// can rise and fall along the route.
+    pub fn profile(route: &[usize], customers: &[Customer]) -> Vec<Self> {
+        let mut load = Self::new();
+        for &cid in route {
+            if customers[cid].request_role().is_none() {
+                load.add(customers[cid].demands());
+            }
+        }
+
+        let mut profile = Vec::with_capacity(route.len() + 1);
+        profile.push(load.clone());
+        for &cid in route {
+            let customer = &customers[cid];
+            if customer.request_role().is_none() {
+                load.sub(customer.demands());
+            } else {
+                load.add(customer.demands());
+            }
+            load.add(customer.pickups());
+            profile.push(load.clone());
+        }
+        profile
+    }
+
+    /// Peak on-board load per dimension while driving the given route.
+    ///
+    /// See [`profile`](Self::profile). For routes without pickups this equals
+    /// [`of_route`](Self::of_route).
+    pub fn peak_of_route(route: &[usize], customers: &[Customer]) -> Self {
+        let mut peak = Self::new();
+        for load in Self::profile(route, customers) {
+            peak.max_assign(&load);
+        }
+        peak
+    }
+
+    /// On-board load at every point of a route on which the stop at position
+    /// `k` receives `quantities[k]` of its first demand dimension (a
+    /// [split delivery](crate::models::Customer::with_split_delivery) may
+    /// deliver less than the full demand).
+    ///
+    /// Equals [`profile`](Self::profile) when every quantity is the
+    /// customer's full demand.
+    pub fn profile_of_deliveries(
+        route: &[usize],
+        quantities: &[i32],
+        customers: &[Customer],
+    ) -> Vec<Self> {
+        // A smaller delivery lightens the vehicle until that stop
+        let shortfall: Vec<i32> = route
+            .iter()
+            .zip(quantities)
+            .map(|(&cid, &q)| customers[cid].demand() - q)
+            .collect();
+        let mut pending: i32 = shortfall.iter().sum();
+        let mut profile = Self::profile(route, customers);
+        for (k, load) in profile.iter_mut().enumerate() {
+            load.sub(&[pending]);
+            if let Some(s) = shortfall.get(k) {
+                pending -= s;
+            }
+        }
+        profile
+    }
+
+    /// Peak on-board load per dimension while driving a route with the given
+    /// delivered quantities (see [`profile_of_deliveries`](Self::profile_of_deliveries)).
+    ///
+    /// Equals [`peak_of_route`](Self::peak_of_route) when every quantity is
+    /// the customer's full demand.
+    pub fn peak_of_deliveries(route: &[usize], quantities: &[i32], customers: &[Customer]) -> Self {
+        let mut peak = Self::new();
+        for load in Self::profile_of_deliveries(route, quantities, customers) {
+            peak.max_assign(&load);
+        }
+        peak
+    }
+
+    /// Returns a copy of this load with the given amounts added.
+    pub fn plus(&self, amounts: &[i32]) -> Self {
+        let mut load = self.clone();
+        load.add(amounts);
+        load
+    }
+
+    /// Amount in the given dimension (zero if not present).
+    pub fn get(&self, dimension: usize) -> i32 {
+        self.amounts.get(dimension).copied().unwrap_or(0)
+    }
+
+    /// Returns the amounts per dimension.
+    pub fn amounts(&self) -> &[i32] {
+        &self.amounts
+    }
+
+    /// Returns the first dimension in which this load exceeds `capacity`.
+    ///
+    /// Dimensions beyond the length of `capacity` are unconstrained.
+    pub fn exceeded_dimension(&self, capacity: &[i32]) -> Option<usize> {
+        self.amounts
+            .iter()
+            .zip(capacity)
+            .position(|(&load, &cap)| load > cap)
+    }
+
+    /// Returns `true` if this load fits within `capacity` in every dimension.
+    pub fn fits(&self, capacity: &[i32]) -> bool {
+        self.exceeded_dimension(capacity).is_none()
+    }
+}
+
+/// Returns `true` if some customer picks goods up or belongs to a paired
+/// request, so that the on-board load of a route varies along it and must be
+/// checked with [`Load::profile`] rather than [`Load::of_route`].
+pub(crate) fn has_load_profile(customers: &[Customer]) -> bool {
+    customers
+        .iter()
+        .any(|c| c.request_role().is_some() || c.pickups().iter().any(|&p| p != 0))
+}
+
+/// Peak and final on-board load of a route built one stop at a time.
+///
+/// Appending an unpaired stop raises every earlier load by what is
+/// delivered there, since the vehicle leaves the depot carrying it, and the
+/// new final load is the previous one plus what is picked up; a paired stop
+/// only changes the final load. Keeps [`Load::peak_of_route`] of the route
+/// in O(d) per stop for d dimensions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LoadProfile {
+    peak: Load,
+    last: Load,
+}
+
+impl LoadProfile {
+    /// Profile of an empty route.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `customer` receiving its full demand.
+    pub(crate) fn push(&mut self, customer: &Customer) {
+        self.push_delivery(customer, customer.demands());
+    }
+
+    /// Appends `customer` receiving `delivered` (less than its demand for a
+    /// split delivery).
+    pub(crate) fn push_delivery(&mut self, customer: &Customer, delivered: &[i32]) {
+        if customer.request_role().is_none() {
+            self.peak.add(delivered);
+        } else {
+            self.last.add(delivered);
+        }
+        self.last.add(customer.pickups());
+        self.peak.max_assign(&self.last);
+    }
+
+    /// Profile after appending `customer` with its full demand.
+    pub(crate) fn with(&self, customer: &Customer) -> Self {
+        let mut profile = self.clone();
+        profile.push(customer);
+        profile
+    }
+
+    /// Peak on-board load of the route so far.
+    pub(crate) fn peak(&self) -> &Load {
+        &self.peak
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_add_grows_dimensions() {
+        let mut load = Load::new();
+        load.add(&[3]);
+        load.add(&[1, 4, 2]);
+        assert_eq!(load.amounts(), &[4, 4, 2]);
+        assert_eq!(load.get(5), 0);
+    }
+
+    #[test]
+    fn test_load_sub() {
+        let mut load = Load::new();
+        load.add(&[10, 5]);
+        load.sub(&[4, 5]);
+        assert_eq!(load.amounts(), &[6, 0]);
+    }
+
+    #[test]
+    fn test_load_exceeded_dimension() {
+        let mut load = Load::new();
+        load.add(&[10, 8, 3]);
+        assert_eq!(load.exceeded_dimension(&[10, 8, 3]), None);
+        assert_eq!(load.exceeded_dimension(&[10, 7, 3]), Some(1));
+        assert_eq!(load.exceeded_dimension(&[9, 7, 3]), Some(0));
+    }
+
+    #[test]
+    fn test_load_missing_capacity_dimension_unconstrained() {
+        let mut load = Load::new();
+        load.add(&[10, 1000]);
+        assert!(load.fits(&[10]));
+    }
+
+    #[test]
+    fn test_load_peak_of_route_paired() {
+        use crate::models::RequestRole;
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 2 }),
+            Customer::new(2, 2.0, 0.0, -10, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 1 }),
+            Customer::new(3, 3.0, 0.0, 8, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 4.0, 0.0, -8, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+        ];
+        // Load goes 10 → 0 → 8 → 0: peak 10
+        assert_eq!(
+            Load::peak_of_route(&[1, 2, 3, 4], &customers).amounts(),
+            &[10]
+        );
+        // Picking up both before dropping off raises the peak
+        assert_eq!(
+            Load::peak_of_route(&[1, 3, 2, 4], &customers).amounts(),
+            &[18]
+        );
+    }
+
+    #[test]
+    fn test_load_profile_simultaneous_pickup_delivery() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_pickup(2),
+            Customer::new(2, 2.0, 0.0, 3, 0.0).with_pickup(12),
+        ];
+        // Leave with 13, then 13 - 10 + 2 = 5, then 5 - 3 + 12 = 14
+        let profile: Vec<i32> = Load::profile(&[1, 2], &customers)
+            .iter()
+            .map(|l| l.get(0))
+            .collect();
+        assert_eq!(profile, vec![13, 5, 14]);
+        assert_eq!(Load::peak_of_route(&[1, 2], &customers).amounts(), &[14]);
+        // Serving the big pickup first peaks at 13 - 3 + 12 = 22
+        assert_eq!(Load::peak_of_route(&[2, 1], &customers).amounts(), &[22]);
+    }
+
+    #[test]
+    fn test_load_profile_matches_peak_of_route() {
+        use crate::models::RequestRole;
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_pickup(2),
+            Customer::new(2, 2.0, 0.0, 3, 0.0).with_pickup(12),
+            Customer::new(3, 3.0, 0.0, 8, 0.0)
+                .with_request_role(RequestRole::Pickup { delivery_id: 4 }),
+            Customer::new(4, 4.0, 0.0, -8, 0.0)
+                .with_request_role(RequestRole::Delivery { pickup_id: 3 }),
+        ];
+        assert!(has_load_profile(&customers));
+        assert!(!has_load_profile(&customers[..1]));
+        for route in [vec![1, 2], vec![2, 1], vec![3, 1, 4, 2], vec![2, 3, 4]] {
+            let mut profile = LoadProfile::new();
+            for &cid in &route {
+                profile.push(&customers[cid]);
+            }
+            assert_eq!(profile.peak(), &Load::peak_of_route(&route, &customers));
+        }
+    }
+
+    #[test]
+    fn test_load_peak_of_deliveries() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 150, 0.0).with_split_delivery(),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_pickup(30),
+        ];
+        // Only 60 of customer 1's demand is loaded: leave with 70
+        let peak = Load::peak_of_deliveries(&[1, 2], &[60, 10], &customers);
+        assert_eq!(peak.amounts(), &[70]);
+        // Picking up 30 before the drop peaks at 70 - 10 + 30 = 90
+        let peak = Load::peak_of_deliveries(&[2, 1], &[10, 60], &customers);
+        assert_eq!(peak.amounts(), &[90]);
+        assert_eq!(
+            Load::peak_of_deliveries(&[2, 1], &[10, 150], &customers),
+            Load::peak_of_route(&[2, 1], &customers)
+        );
+    }
+
+    #[test]
+    fn test_load_of_route() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0).with_demands(vec![10, 2]),
+            Customer::new(2, 2.0, 0.0, 5, 0.0),
+        ];
+        let load = Load::of_route(&[1, 2], &customers);
+        assert_eq!(load.amounts(), &[15, 2]);
+    }
+}
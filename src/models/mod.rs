@@ -1,17 +1,29 @@
 //! Domain model types for vehicle routing problems.
 //!
-//! Provides the core abstractions: customers with demands and time windows,
-//! vehicles with capacity constraints, routes as ordered sequences of visits,
-//! and a problem trait that ties everything together.
+//! Provides the core abstractions: customers with (multi-dimensional) demands,
+//! time windows and optional pickup-and-delivery pairing, vehicles with capacity constraints,
+//! driver breaks and load-dependent emissions, visit patterns for periodic routing, routes as
+//! ordered sequences of visits, and a problem trait that ties everything together.
 
 mod customer;
+mod driver_break;
+mod emission;
+mod load;
 mod problem;
 mod route;
 mod solution;
 mod vehicle;
+mod visit_pattern;
 
-pub use customer::{Customer, TimeWindow};
+pub(crate) use customer::{precedence_holds, stop_ids};
+pub use customer::{Customer, RequestRole, TimeWindow};
+pub use driver_break::{DriverBreak, ScheduledBreak};
+pub use emission::EmissionModel;
+pub use load::Load;
+pub(crate) use load::{has_load_profile, LoadProfile};
 pub use problem::RoutingProblem;
-pub use route::{Route, Visit};
+pub use route::{Route, RouteEnds, Visit};
 pub use solution::{Solution, Violation, ViolationType};
 pub use vehicle::Vehicle;
+pub(crate) use vehicle::{cheapest_vehicle, fleet_index, route_vehicles};
+pub use visit_pattern::VisitPattern;
@@ -55,6 +55,15 @@ pub trait RoutingProblem: Send + Sync {
         self.distance(from, to)
     }
 
+    /// Travel time from `from` to `to` when leaving at `departure`.
+    ///
+    /// Defaults to the static [`travel_time`](Self::travel_time); override
+    /// it for time-dependent travel times, e.g. with
+    /// [`DistanceMatrix::travel_time`](crate::distance::DistanceMatrix::travel_time).
+    fn travel_time_at(&self, from: usize, to: usize, _departure: f64) -> f64 {
+        self.travel_time(from, to)
+    }
+
     /// Evaluates a solution, returning `(cost, violations)`.
     ///
     /// A feasible solution has an empty violations list.
@@ -112,6 +121,7 @@ mod tests {
 
         assert_eq!(problem.num_customers(), 2);
         assert!((problem.distance(0, 1) - 5.0).abs() < 1e-10);
+        assert!((problem.travel_time_at(0, 1, 8.0) - 5.0).abs() < 1e-10);
 
         let sol = Solution::new();
         let (cost, violations) = problem.evaluate(&sol);
@@ -1,8 +1,15 @@
 //! Route and visit types.
 
+use super::ScheduledBreak;
+use crate::distance::DistanceMatrix;
+
+/// Sentinel node standing for the end of an open route.
+const OPEN_END: usize = usize::MAX;
+
 /// A single visit to a customer within a route.
 ///
-/// Tracks the customer ID along with computed timing and load state.
+/// Tracks the customer ID along with computed timing, load state and the
+/// quantity delivered.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Visit {
     /// Customer ID being visited.
@@ -11,13 +18,129 @@ pub struct Visit {
     pub arrival_time: f64,
     /// Departure time (arrival + waiting + service duration).
     pub departure_time: f64,
-    /// Cumulative load after this visit.
+    /// On-board load (first dimension) when leaving this visit: what is
+    /// still to be delivered further on plus what has been picked up so far.
+    ///
+    /// Falls along a delivery-only route and is zero after its last visit;
+    /// up to 0.3.0 this was the cumulative quantity delivered so far.
     pub load_after: i32,
+    /// Quantity delivered on this visit: the customer's demand, or part of
+    /// it for a [split delivery](crate::models::Customer::with_split_delivery).
+    pub quantity: i32,
+}
+
+/// Start and end locations of a route.
+///
+/// A closed route returns to its depot, or to a separate end location such
+/// as a driver's home ([`new`](Self::new)). An open route ends at its last
+/// customer and the return leg is neither travelled nor costed (open VRP).
+/// A depot ID converts into a closed route, so functions taking
+/// `impl Into<RouteEnds>` also accept a plain depot.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, RouteEnds};
+/// use u_routing::distance::DistanceMatrix;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 3.0, 4.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+///
+/// assert_eq!(RouteEnds::from(0), RouteEnds::closed(0));
+/// assert!((RouteEnds::closed(0).distance(&[1], &dm) - 10.0).abs() < 1e-10);
+/// assert!((RouteEnds::open(0).distance(&[1], &dm) - 5.0).abs() < 1e-10);
+/// assert!((RouteEnds::new(0, 1).distance(&[1], &dm) - 5.0).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteEnds {
+    start: usize,
+    end: Option<usize>,
+}
+
+impl RouteEnds {
+    /// A route that starts at `start` and ends at `end`.
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end: Some(end),
+        }
+    }
+
+    /// A route that starts and ends at `depot`.
+    pub fn closed(depot: usize) -> Self {
+        Self {
+            start: depot,
+            end: Some(depot),
+        }
+    }
+
+    /// A route that starts at `depot` and ends at its last customer.
+    pub fn open(depot: usize) -> Self {
+        Self {
+            start: depot,
+            end: None,
+        }
+    }
+
+    /// Location the route starts at.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Location the route returns to, or `None` for an open route.
+    pub fn end(&self) -> Option<usize> {
+        self.end
+    }
+
+    /// Returns `true` if the route does not return to an end location.
+    pub fn is_open(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// Node following the last customer; a sentinel for open routes that
+    /// [`leg`](Self::leg) treats as free to reach.
+    pub(crate) fn end_node(&self) -> usize {
+        self.end.unwrap_or(OPEN_END)
+    }
+
+    /// Distance between two route nodes, where any leg to or from the open
+    /// end costs nothing.
+    pub(crate) fn leg(&self, from: usize, to: usize, distances: &DistanceMatrix) -> f64 {
+        if from == OPEN_END || to == OPEN_END {
+            0.0
+        } else {
+            distances.get(from, to)
+        }
+    }
+
+    /// Distance travelled on `route`: from the start through every customer
+    /// and, for closed routes, back to the depot. Empty routes cost nothing.
+    pub fn distance(&self, route: &[usize], distances: &DistanceMatrix) -> f64 {
+        let (Some(&first), Some(&last)) = (route.first(), route.last()) else {
+            return 0.0;
+        };
+        let mut total = distances.get(self.start, first);
+        for w in route.windows(2) {
+            total += distances.get(w[0], w[1]);
+        }
+        total + self.leg(last, self.end_node(), distances)
+    }
+}
+
+impl From<usize> for RouteEnds {
+    fn from(depot: usize) -> Self {
+        Self::closed(depot)
+    }
 }
 
 /// An ordered sequence of customer visits assigned to a single vehicle.
 ///
-/// A route starts and ends at the vehicle's depot (not stored in `visits`).
+/// A route starts at the vehicle's depot and, unless the vehicle runs open
+/// routes, ends there too (the depot is not stored in `visits`). Driver
+/// breaks placed by the evaluator are listed in [`breaks`](Self::breaks).
 ///
 /// # Examples
 ///
@@ -29,18 +152,24 @@ pub struct Visit {
 ///     customer_id: 1,
 ///     arrival_time: 10.0,
 ///     departure_time: 20.0,
-///     load_after: 10,
+///     load_after: 0,
+///     quantity: 10,
 /// });
 /// assert_eq!(route.len(), 1);
+/// assert_eq!(route.total_load(), 10);
 /// assert_eq!(route.vehicle_id(), 0);
 /// ```
 #[derive(Debug, Clone)]
 pub struct Route {
     vehicle_id: usize,
+    vehicle_index: Option<usize>,
     visits: Vec<Visit>,
+    breaks: Vec<ScheduledBreak>,
     total_distance: f64,
     total_duration: f64,
+    time_window_penalty: f64,
     total_load: i32,
+    emissions: f64,
 }
 
 impl Route {
@@ -48,24 +177,58 @@ impl Route {
     pub fn new(vehicle_id: usize) -> Self {
         Self {
             vehicle_id,
+            vehicle_index: None,
             visits: Vec::new(),
+            breaks: Vec::new(),
             total_distance: 0.0,
             total_duration: 0.0,
+            time_window_penalty: 0.0,
             total_load: 0,
+            emissions: 0.0,
         }
     }
 
     /// Appends a visit to the end of this route.
     pub fn push_visit(&mut self, visit: Visit) {
-        self.total_load = visit.load_after;
+        self.total_load += visit.quantity;
         self.visits.push(visit);
     }
 
+    /// Adds a driver break to the schedule.
+    pub fn push_break(&mut self, scheduled: ScheduledBreak) {
+        self.breaks.push(scheduled);
+    }
+
+    /// Returns the driver breaks in schedule order.
+    pub fn breaks(&self) -> &[ScheduledBreak] {
+        &self.breaks
+    }
+
+    /// Returns the break taken just before visit `visit_index`, if any
+    /// (`visit_index == len()` for the return to the depot).
+    pub fn break_before(&self, visit_index: usize) -> Option<&ScheduledBreak> {
+        self.breaks.iter().find(|b| b.before_visit == visit_index)
+    }
+
     /// Returns the vehicle assigned to this route.
     pub fn vehicle_id(&self) -> usize {
         self.vehicle_id
     }
 
+    /// Returns the vehicle running this route as one of its trips, if set.
+    ///
+    /// Routes of a [multi-trip](crate::models::Vehicle::with_reload_time)
+    /// vehicle type sharing an index are run in sequence by one vehicle;
+    /// a route without an index has a vehicle of its own.
+    pub fn vehicle_index(&self) -> Option<usize> {
+        self.vehicle_index
+    }
+
+    /// Assigns this route to a vehicle of its type as one of its trips.
+    pub fn set_vehicle_index(&mut self, index: usize) {
+        self.vehicle_index = Some(index);
+    }
+
     /// Returns the ordered sequence of visits.
     pub fn visits(&self) -> &[Visit] {
         &self.visits
@@ -86,6 +249,19 @@ impl Route {
         self.visits.iter().map(|v| v.customer_id).collect()
     }
 
+    /// Returns the quantity delivered on each visit, in visit order.
+    pub fn quantities(&self) -> Vec<i32> {
+        self.visits.iter().map(|v| v.quantity).collect()
+    }
+
+    /// Quantity delivered to `customer_id` on this route, if it is visited.
+    pub fn quantity_of(&self, customer_id: usize) -> Option<i32> {
+        self.visits
+            .iter()
+            .find(|v| v.customer_id == customer_id)
+            .map(|v| v.quantity)
+    }
+
     /// Total distance of this route (set by evaluator).
     pub fn total_distance(&self) -> f64 {
         self.total_distance
@@ -96,7 +272,24 @@ impl Route {
         self.total_duration
     }
 
-    /// Total load served by this route.
+    /// Soft time-window penalty for early or late service (set by evaluator);
+    /// infinite if a customer is reached after its last window has closed.
+    pub fn time_window_penalty(&self) -> f64 {
+        self.time_window_penalty
+    }
+
+    /// Load-dependent emissions of this route (set by evaluator; zero for a
+    /// vehicle without an [emission model](crate::models::Vehicle::with_emissions)).
+    pub fn emissions(&self) -> f64 {
+        self.emissions
+    }
+
+    /// Total quantity (first dimension) delivered by this route: the sum of
+    /// its visits' [`quantity`](Visit::quantity).
+    ///
+    /// This is not the on-board load, which varies along the route (see
+    /// [`Visit::load_after`]); up to 0.3.0 it was read from the last visit's
+    /// `load_after`.
     pub fn total_load(&self) -> i32 {
         self.total_load
     }
@@ -110,6 +303,16 @@ impl Route {
     pub fn set_total_duration(&mut self, d: f64) {
         self.total_duration = d;
     }
+
+    /// Sets the emissions (used by evaluator).
+    pub fn set_emissions(&mut self, emissions: f64) {
+        self.emissions = emissions;
+    }
+
+    /// Sets the soft time-window penalty (used by evaluator).
+    pub fn set_time_window_penalty(&mut self, penalty: f64) {
+        self.time_window_penalty = penalty;
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +325,7 @@ mod tests {
         assert!(r.is_empty());
         assert_eq!(r.len(), 0);
         assert_eq!(r.vehicle_id(), 0);
+        assert_eq!(r.vehicle_index(), None);
         assert_eq!(r.total_distance(), 0.0);
         assert_eq!(r.total_load(), 0);
     }
@@ -133,16 +337,21 @@ mod tests {
             customer_id: 5,
             arrival_time: 10.0,
             departure_time: 15.0,
-            load_after: 20,
+            load_after: 15,
+            quantity: 20,
         });
         r.push_visit(Visit {
             customer_id: 3,
             arrival_time: 20.0,
             departure_time: 25.0,
-            load_after: 35,
+            load_after: 0,
+            quantity: 15,
         });
         assert_eq!(r.len(), 2);
         assert_eq!(r.customer_ids(), vec![5, 3]);
+        assert_eq!(r.quantities(), vec![20, 15]);
+        assert_eq!(r.quantity_of(3), Some(15));
+        assert_eq!(r.quantity_of(4), None);
         assert_eq!(r.total_load(), 35);
     }
 
@@ -153,8 +362,25 @@ mod tests {
             arrival_time: 10.0,
             departure_time: 20.0,
             load_after: 5,
+            quantity: 5,
         };
         let b = a.clone();
         assert_eq!(a, b);
     }
+
+    #[test]
+    fn test_route_ends_leg() {
+        let customers = vec![
+            crate::models::Customer::depot(0.0, 0.0),
+            crate::models::Customer::new(1, 3.0, 4.0, 10, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let open = RouteEnds::open(0);
+        assert!(open.is_open());
+        assert_eq!(open.end(), None);
+        assert_eq!(open.leg(1, open.end_node(), &dm), 0.0);
+        let closed = RouteEnds::closed(0);
+        assert!((closed.leg(1, closed.end_node(), &dm) - 5.0).abs() < 1e-10);
+        assert_eq!(closed.distance(&[], &dm), 0.0);
+    }
 }
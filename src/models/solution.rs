@@ -9,6 +9,8 @@ pub enum ViolationType {
     CapacityExceeded {
         /// Route index in the solution.
         route_index: usize,
+        /// Capacity dimension that was exceeded (0 for single-dimension loads).
+        dimension: usize,
         /// Load that exceeded capacity.
         load: i32,
         /// Vehicle capacity.
@@ -23,6 +25,18 @@ pub enum ViolationType {
         /// Time window due date.
         due: f64,
     },
+    /// Pickup and delivery of a request are not on the same route in order.
+    ///
+    /// Raised when the delivery is missing from the pickup's route, comes
+    /// before the pickup, or is served without its pickup.
+    PrecedenceViolated {
+        /// Route index.
+        route_index: usize,
+        /// Pickup stop of the request.
+        pickup_id: usize,
+        /// Delivery stop of the request.
+        delivery_id: usize,
+    },
     /// Route distance exceeds vehicle's maximum.
     MaxDistanceExceeded {
         /// Route index.
@@ -41,6 +55,37 @@ pub enum ViolationType {
         /// Maximum allowed duration.
         max_duration: f64,
     },
+    /// Route finishes after the end of the vehicle's shift.
+    ShiftEndExceeded {
+        /// Route index.
+        route_index: usize,
+        /// Time the route reaches its end location (or its last customer,
+        /// for an open route).
+        finish_time: f64,
+        /// End of the vehicle's shift.
+        shift_end: f64,
+    },
+    /// A driver break could not be taken within its window, or a single
+    /// leg exceeds the maximum driving time between breaks.
+    BreakMissed {
+        /// Route index.
+        route_index: usize,
+    },
+    /// The route's vehicle lacks a skill the customer requires.
+    SkillMismatch {
+        /// Route index.
+        route_index: usize,
+        /// Customer that cannot be served.
+        customer_id: usize,
+    },
+    /// An electric vehicle runs out of energy before reaching a location.
+    BatteryDepleted {
+        /// Route index.
+        route_index: usize,
+        /// Location the vehicle cannot reach (a stop, charging station or
+        /// the route's end location).
+        location_id: usize,
+    },
 }
 
 /// A constraint violation in a solution.
@@ -138,10 +183,27 @@ impl Solution {
         self.routes.iter().map(|r| r.total_distance()).sum()
     }
 
-    /// Total number of customers served (across all routes).
+    /// Total emissions across all routes.
+    pub fn total_emissions(&self) -> f64 {
+        self.routes.iter().map(|r| r.emissions()).sum()
+    }
+
+    /// Total number of customer visits (across all routes); a split
+    /// delivery counts once per visit.
     pub fn num_served(&self) -> usize {
         self.routes.iter().map(|r| r.len()).sum()
     }
+
+    /// Total quantity delivered to `customer_id` over all routes.
+    ///
+    /// Equals the customer's demand once it is fully served, possibly over
+    /// several routes for a [split delivery](crate::models::Customer::with_split_delivery).
+    pub fn delivered(&self, customer_id: usize) -> i32 {
+        self.routes
+            .iter()
+            .filter_map(|r| r.quantity_of(customer_id))
+            .sum()
+    }
 }
 
 impl Default for Solution {
@@ -174,6 +236,7 @@ mod tests {
             arrival_time: 0.0,
             departure_time: 0.0,
             load_after: 10,
+            quantity: 10,
         });
         r1.set_total_distance(50.0);
 
@@ -183,12 +246,14 @@ mod tests {
             arrival_time: 0.0,
             departure_time: 0.0,
             load_after: 5,
+            quantity: 5,
         });
         r2.push_visit(Visit {
             customer_id: 3,
             arrival_time: 0.0,
             departure_time: 0.0,
             load_after: 15,
+            quantity: 10,
         });
         r2.set_total_distance(80.0);
 
@@ -199,6 +264,8 @@ mod tests {
         assert_eq!(sol.num_routes(), 2);
         assert_eq!(sol.num_served(), 3);
         assert_eq!(sol.num_unassigned(), 1);
+        assert_eq!(sol.delivered(3), 10);
+        assert_eq!(sol.delivered(4), 0);
         assert!((sol.total_distance() - 130.0).abs() < 1e-10);
     }
 
@@ -206,6 +273,7 @@ mod tests {
     fn test_violation_types() {
         let v = Violation::new(ViolationType::CapacityExceeded {
             route_index: 0,
+            dimension: 0,
             load: 250,
             capacity: 200,
         });
@@ -213,6 +281,7 @@ mod tests {
             v.kind,
             ViolationType::CapacityExceeded {
                 route_index: 0,
+                dimension: 0,
                 load: 250,
                 capacity: 200,
             }
@@ -1,7 +1,49 @@
 //! Vehicle type with capacity and cost parameters.
 
+use super::{Customer, DriverBreak, EmissionModel, Load, RouteEnds, Solution};
+use crate::distance::DistanceMatrix;
+
 /// A vehicle that services routes in a routing problem.
 ///
+/// Capacity may have several dimensions (e.g. weight, volume, pallets), set
+/// with [`with_capacities`](Self::with_capacities). A load dimension with no
+/// matching capacity dimension is unconstrained.
+///
+/// Fleet-aware solvers take a `&[Vehicle]` where each entry describes a
+/// vehicle *type*; [`with_available`](Self::with_available) limits how many
+/// routes that type may serve (unlimited by default).
+///
+/// A vehicle with [`with_open_route`](Self::with_open_route) ends its route
+/// at the last customer; the return leg to the depot is not costed.
+///
+/// A route starts at the depot at the shift start (time 0 by default) and
+/// returns to the [end location](Self::with_end_location) (the depot by
+/// default), which it must reach by the [shift](Self::with_shift) end.
+/// The driver may have to take a [break](Self::with_break) on the way.
+///
+/// A [multi-trip](Self::with_reload_time) vehicle runs several routes
+/// (trips) in sequence within its shift, reloading at the depot between
+/// them.
+///
+/// A vehicle only [serves](Self::can_serve) customers whose required skills
+/// are all among its own [skills](Self::with_skills).
+///
+/// A vehicle with a [routing profile](Self::with_routing_profile) (bike,
+/// van, truck, …) travels with that profile's distances and travel times.
+///
+/// An [electric](Self::with_battery) vehicle leaves each trip fully
+/// charged, uses energy in proportion to the distance driven and recharges
+/// at [charging stations](Customer::charging_station) on its route.
+///
+/// A vehicle with an [emission model](Self::with_emissions) reports the
+/// load-dependent emissions of its routes; pricing them with
+/// [`with_cost_per_emission`](Self::with_cost_per_emission) lets solvers
+/// minimize emissions alone or mixed with distance.
+///
+/// Drivers can be paid for the duration of their routes
+/// ([`with_cost_per_time`](Self::with_cost_per_time)), at a higher rate for
+/// [overtime](Self::with_overtime).
+///
 /// # Examples
 ///
 /// ```
@@ -14,12 +56,27 @@
 #[derive(Debug, Clone)]
 pub struct Vehicle {
     id: usize,
-    capacity: i32,
+    capacity: Vec<i32>,
     depot_id: usize,
+    end_location: Option<usize>,
+    shift_start: f64,
+    shift_end: Option<f64>,
+    driver_break: Option<DriverBreak>,
     cost_per_distance: f64,
     fixed_cost: f64,
+    cost_per_time: f64,
+    overtime: Option<(f64, f64)>,
     max_distance: Option<f64>,
     max_duration: Option<f64>,
+    available: Option<usize>,
+    skills: Vec<usize>,
+    open_route: bool,
+    reload_time: Option<f64>,
+    routing_profile: Option<usize>,
+    emission_model: Option<EmissionModel>,
+    cost_per_emission: f64,
+    battery_capacity: Option<f64>,
+    energy_per_distance: f64,
 }
 
 impl Vehicle {
@@ -30,21 +87,63 @@ impl Vehicle {
     pub fn new(id: usize, capacity: i32) -> Self {
         Self {
             id,
-            capacity,
+            capacity: vec![capacity],
             depot_id: 0,
+            end_location: None,
+            shift_start: 0.0,
+            shift_end: None,
+            driver_break: None,
             cost_per_distance: 1.0,
             fixed_cost: 0.0,
+            cost_per_time: 0.0,
+            overtime: None,
             max_distance: None,
             max_duration: None,
+            available: None,
+            skills: Vec::new(),
+            open_route: false,
+            reload_time: None,
+            routing_profile: None,
+            emission_model: None,
+            cost_per_emission: 0.0,
+            battery_capacity: None,
+            energy_per_distance: 0.0,
         }
     }
 
+    /// Sets a multi-dimensional capacity, replacing the one given to [`new`](Self::new).
+    pub fn with_capacities(mut self, capacities: Vec<i32>) -> Self {
+        self.capacity = capacities;
+        self
+    }
+
     /// Sets the depot for this vehicle.
     pub fn with_depot(mut self, depot_id: usize) -> Self {
         self.depot_id = depot_id;
         self
     }
 
+    /// Sets a location the route ends at other than the depot (e.g. the
+    /// driver's home or an overnight parking lot).
+    pub fn with_end_location(mut self, location_id: usize) -> Self {
+        self.end_location = Some(location_id);
+        self
+    }
+
+    /// Sets the working hours: routes leave the depot at `start` and must
+    /// reach their end location by `end`.
+    pub fn with_shift(mut self, start: f64, end: f64) -> Self {
+        self.shift_start = start;
+        self.shift_end = Some(end);
+        self
+    }
+
+    /// Requires the driver to take the given break during each route.
+    pub fn with_break(mut self, driver_break: DriverBreak) -> Self {
+        self.driver_break = Some(driver_break);
+        self
+    }
+
     /// Sets cost per unit distance.
     pub fn with_cost_per_distance(mut self, cost: f64) -> Self {
         self.cost_per_distance = cost;
@@ -57,6 +156,34 @@ impl Vehicle {
         self
     }
 
+    /// Sets cost per time unit of route duration, e.g. the driver's hourly
+    /// wage when times are in hours.
+    pub fn with_cost_per_time(mut self, cost: f64) -> Self {
+        self.cost_per_time = cost;
+        self
+    }
+
+    /// Charges `cost_per_time` on top of the regular
+    /// [cost per time](Self::with_cost_per_time) for the part of a route's
+    /// duration beyond `regular_time`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Vehicle;
+    ///
+    /// // 20 per hour, plus 10 per hour beyond 8 hours
+    /// let van = Vehicle::new(0, 100)
+    ///     .with_cost_per_time(20.0)
+    ///     .with_overtime(8.0, 10.0);
+    /// assert!((van.time_cost(6.0) - 120.0).abs() < 1e-10);
+    /// assert!((van.time_cost(10.0) - 220.0).abs() < 1e-10);
+    /// ```
+    pub fn with_overtime(mut self, regular_time: f64, cost_per_time: f64) -> Self {
+        self.overtime = Some((regular_time, cost_per_time));
+        self
+    }
+
     /// Sets maximum route distance.
     pub fn with_max_distance(mut self, max: f64) -> Self {
         self.max_distance = Some(max);
@@ -69,26 +196,279 @@ impl Vehicle {
         self
     }
 
+    /// Limits the number of vehicles of this type (routes it may serve, or
+    /// vehicles its trips are packed onto if it is
+    /// [multi-trip](Self::with_reload_time)).
+    pub fn with_available(mut self, count: usize) -> Self {
+        self.available = Some(count);
+        self
+    }
+
+    /// Sets the skills this vehicle (and its driver) provides.
+    pub fn with_skills(mut self, skills: Vec<usize>) -> Self {
+        self.skills = skills;
+        self
+    }
+
+    /// Makes routes of this vehicle open: they end at the last customer
+    /// instead of returning to the depot (open VRP).
+    pub fn with_open_route(mut self) -> Self {
+        self.open_route = true;
+        self
+    }
+
+    /// Lets the vehicle run several trips in sequence within its shift,
+    /// spending `reload_time` at the depot between two trips.
+    ///
+    /// Each trip but the last returns to the depot; the last one ends at the
+    /// vehicle's end location. The fixed cost is charged once per vehicle
+    /// rather than per trip (see [`RouteEvaluator::pack_trips`]). Driver
+    /// breaks carry over from one trip to the next.
+    /// [`with_available`](Self::with_available) limits the number of
+    /// vehicles the trips are packed onto, not the number of trips.
+    ///
+    /// [`RouteEvaluator::pack_trips`]: crate::evaluation::RouteEvaluator::pack_trips
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Vehicle;
+    ///
+    /// let van = Vehicle::new(0, 50).with_shift(0.0, 480.0).with_reload_time(20.0);
+    /// assert!(van.is_multi_trip());
+    /// assert_eq!(van.reload_time(), Some(20.0));
+    /// ```
+    pub fn with_reload_time(mut self, reload_time: f64) -> Self {
+        self.reload_time = Some(reload_time);
+        self
+    }
+
+    /// Routes this vehicle with the distance and travel-time matrices of
+    /// routing profile `profile` (see
+    /// [`DistanceMatrix::with_routing_profile`](crate::distance::DistanceMatrix::with_routing_profile)).
+    ///
+    /// Without a profile, or if the matrix has none with this ID, the
+    /// vehicle uses the main matrices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Vehicle;
+    ///
+    /// const BIKE: usize = 1;
+    /// let courier = Vehicle::new(0, 10).with_routing_profile(BIKE);
+    /// assert_eq!(courier.routing_profile(), Some(BIKE));
+    /// assert_eq!(Vehicle::new(1, 100).routing_profile(), None);
+    /// ```
+    pub fn with_routing_profile(mut self, profile: usize) -> Self {
+        self.routing_profile = Some(profile);
+        self
+    }
+
+    /// Makes this vehicle electric, with a battery holding `capacity` units
+    /// of energy and using `energy_per_distance` per distance unit.
+    ///
+    /// The battery is full at the start of every trip. A route that would
+    /// run it empty is reported as
+    /// [`ViolationType::BatteryDepleted`](crate::models::ViolationType::BatteryDepleted)
+    /// unless it stops at a [charging station](Customer::charging_station)
+    /// on the way (see [`insert_charging_stops`](crate::evaluation::insert_charging_stops)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::Vehicle;
+    ///
+    /// // 60 kWh battery, 0.2 kWh per km: 300 km range
+    /// let van = Vehicle::new(0, 100).with_battery(60.0, 0.2);
+    /// assert!(van.is_electric());
+    /// assert!((van.range().expect("electric") - 300.0).abs() < 1e-10);
+    /// ```
+    pub fn with_battery(mut self, capacity: f64, energy_per_distance: f64) -> Self {
+        self.battery_capacity = Some(capacity);
+        self.energy_per_distance = energy_per_distance;
+        self
+    }
+
+    /// Sets the emission rates of this vehicle, used to report the
+    /// emissions of its routes (see [`Route::emissions`](crate::models::Route::emissions)).
+    ///
+    /// The load is measured in the first capacity dimension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use u_routing::models::{Customer, EmissionModel, Vehicle};
+    /// use u_routing::distance::DistanceMatrix;
+    ///
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 10.0, 0.0, 100, 0.0),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    /// let truck = Vehicle::new(0, 100)
+    ///     .with_emissions(EmissionModel::new(1.0, 2.0).expect("valid"));
+    ///
+    /// // 10 fully loaded out, 10 empty back
+    /// assert!((truck.route_emissions(&[1], &customers, &dm) - 30.0).abs() < 1e-10);
+    /// ```
+    pub fn with_emissions(mut self, model: EmissionModel) -> Self {
+        self.emission_model = Some(model);
+        self
+    }
+
+    /// Sets the cost per unit of emissions, added to the route cost that
+    /// solvers minimize (zero by default).
+    ///
+    /// Set the cost per distance to zero to minimize emissions alone, or keep
+    /// both to minimize a weighted mix.
+    pub fn with_cost_per_emission(mut self, cost: f64) -> Self {
+        self.cost_per_emission = cost;
+        self
+    }
+
     /// Vehicle ID.
     pub fn id(&self) -> usize {
         self.id
     }
 
     /// Maximum load capacity.
+    ///
+    /// For multi-dimensional capacity, this is the first dimension.
     pub fn capacity(&self) -> i32 {
-        self.capacity
+        self.capacity.first().copied().unwrap_or(i32::MAX)
+    }
+
+    /// Maximum load capacity in every dimension.
+    pub fn capacities(&self) -> &[i32] {
+        &self.capacity
+    }
+
+    /// Returns `true` if `load` fits within this vehicle's capacity in every dimension.
+    pub fn can_carry(&self, load: &Load) -> bool {
+        load.fits(&self.capacity)
+    }
+
+    /// Skills this vehicle provides.
+    pub fn skills(&self) -> &[usize] {
+        &self.skills
     }
 
-    /// Depot location ID (start and end of route).
+    /// Returns `true` if this vehicle provides every skill `customer` requires.
+    pub fn can_serve(&self, customer: &Customer) -> bool {
+        customer
+            .required_skills()
+            .iter()
+            .all(|skill| self.skills.contains(skill))
+    }
+
+    /// Returns `true` if this vehicle can serve every customer on `route`.
+    pub fn can_serve_route(&self, route: &[usize], customers: &[Customer]) -> bool {
+        route.iter().all(|&cid| self.can_serve(&customers[cid]))
+    }
+
+    /// Depot location ID (start of route, and its end unless an end
+    /// location is set).
     pub fn depot_id(&self) -> usize {
         self.depot_id
     }
 
+    /// Location ID the route ends at (the depot unless set otherwise).
+    pub fn end_location(&self) -> usize {
+        self.end_location.unwrap_or(self.depot_id)
+    }
+
+    /// Time the vehicle leaves the depot (0 unless a shift is set).
+    pub fn shift_start(&self) -> f64 {
+        self.shift_start
+    }
+
+    /// Time by which the route must be finished, if any.
+    pub fn shift_end(&self) -> Option<f64> {
+        self.shift_end
+    }
+
+    /// Returns `true` if routes of this vehicle do not return to the depot.
+    pub fn is_open_route(&self) -> bool {
+        self.open_route
+    }
+
+    /// Start and end of this vehicle's routes.
+    pub fn route_ends(&self) -> RouteEnds {
+        if self.open_route {
+            RouteEnds::open(self.depot_id)
+        } else {
+            RouteEnds::new(self.depot_id, self.end_location())
+        }
+    }
+
+    /// Time spent reloading at the depot between two trips, if the vehicle
+    /// runs several trips.
+    pub fn reload_time(&self) -> Option<f64> {
+        self.reload_time
+    }
+
+    /// Returns `true` if the vehicle may run several trips in sequence.
+    pub fn is_multi_trip(&self) -> bool {
+        self.reload_time.is_some()
+    }
+
+    /// Routing profile whose matrices this vehicle travels with, if any.
+    pub fn routing_profile(&self) -> Option<usize> {
+        self.routing_profile
+    }
+
+    /// Break the driver must take during each route, if any.
+    pub fn driver_break(&self) -> Option<&DriverBreak> {
+        self.driver_break.as_ref()
+    }
+
     /// Cost per unit distance traveled.
     pub fn cost_per_distance(&self) -> f64 {
         self.cost_per_distance
     }
 
+    /// Cost per time unit of route duration.
+    pub fn cost_per_time(&self) -> f64 {
+        self.cost_per_time
+    }
+
+    /// Regular time and extra cost per time unit of overtime, if set.
+    pub fn overtime(&self) -> Option<(f64, f64)> {
+        self.overtime
+    }
+
+    /// Battery capacity, if the vehicle is electric.
+    pub fn battery_capacity(&self) -> Option<f64> {
+        self.battery_capacity
+    }
+
+    /// Energy used per distance unit (zero for a vehicle without battery).
+    pub fn energy_per_distance(&self) -> f64 {
+        self.energy_per_distance
+    }
+
+    /// Returns `true` if the vehicle runs on a battery.
+    pub fn is_electric(&self) -> bool {
+        self.battery_capacity.is_some()
+    }
+
+    /// Distance the vehicle can drive on a full battery, if it is electric.
+    pub fn range(&self) -> Option<f64> {
+        self.battery_capacity
+            .map(|capacity| capacity / self.energy_per_distance)
+    }
+
+    /// Emission rates, if set.
+    pub fn emission_model(&self) -> Option<&EmissionModel> {
+        self.emission_model.as_ref()
+    }
+
+    /// Cost per unit of emissions.
+    pub fn cost_per_emission(&self) -> f64 {
+        self.cost_per_emission
+    }
+
     /// Fixed cost for using this vehicle (independent of distance).
     pub fn fixed_cost(&self) -> f64 {
         self.fixed_cost
@@ -103,6 +483,172 @@ impl Vehicle {
     pub fn max_duration(&self) -> Option<f64> {
         self.max_duration
     }
+
+    /// Number of vehicles of this type, if limited.
+    pub fn available(&self) -> Option<usize> {
+        self.available
+    }
+
+    /// Returns `true` if another route can use this type when `used` routes
+    /// already do.
+    pub fn is_available(&self, used: usize) -> bool {
+        self.available.is_none_or(|n| used < n)
+    }
+
+    /// Cost of one route of the given distance: fixed cost plus distance cost.
+    ///
+    /// Excludes the cost of emissions (see [`emission_cost`](Self::emission_cost)).
+    pub fn route_cost(&self, distance: f64) -> f64 {
+        self.fixed_cost + self.cost_per_distance * distance
+    }
+
+    /// Cost of a route of the given duration: the cost per time plus any
+    /// overtime.
+    pub fn time_cost(&self, duration: f64) -> f64 {
+        let overtime = self
+            .overtime
+            .map_or(0.0, |(regular, cost)| cost * (duration - regular).max(0.0));
+        self.cost_per_time * duration + overtime
+    }
+
+    /// Emissions of this vehicle driving `route` from its depot to its end
+    /// location with the matrix of its routing profile, delivering each
+    /// customer's full demand.
+    ///
+    /// Zero without an [emission model](Self::with_emissions).
+    pub fn route_emissions(
+        &self,
+        route: &[usize],
+        customers: &[Customer],
+        distances: &DistanceMatrix,
+    ) -> f64 {
+        self.emissions_between(
+            route,
+            self.route_ends(),
+            customers,
+            distances.for_vehicle(self),
+        )
+    }
+
+    /// Cost of the emissions of `route` (see [`route_emissions`](Self::route_emissions)).
+    pub fn emission_cost(
+        &self,
+        route: &[usize],
+        customers: &[Customer],
+        distances: &DistanceMatrix,
+    ) -> f64 {
+        self.emission_cost_between(
+            route,
+            self.route_ends(),
+            customers,
+            distances.for_vehicle(self),
+        )
+    }
+
+    /// Cost of the emissions of one trip between `ends`, with `distances`
+    /// already those of this vehicle; skips the computation when emissions
+    /// are not priced.
+    pub(crate) fn emission_cost_between(
+        &self,
+        route: &[usize],
+        ends: RouteEnds,
+        customers: &[Customer],
+        distances: &DistanceMatrix,
+    ) -> f64 {
+        if self.cost_per_emission == 0.0 {
+            return 0.0;
+        }
+        self.cost_per_emission * self.emissions_between(route, ends, customers, distances)
+    }
+
+    /// Emissions of one trip between `ends` delivering each customer's full
+    /// demand, with `distances` already those of this vehicle.
+    pub(crate) fn emissions_between(
+        &self,
+        route: &[usize],
+        ends: RouteEnds,
+        customers: &[Customer],
+        distances: &DistanceMatrix,
+    ) -> f64 {
+        if self.emission_model.is_none() {
+            return 0.0;
+        }
+        let quantities: Vec<i32> = route.iter().map(|&c| customers[c].demand()).collect();
+        self.trip_emissions(route, &quantities, ends, customers, distances)
+    }
+
+    /// Emissions of one trip between `ends` delivering `quantities[k]` to
+    /// `route[k]`, with `distances` already those of this vehicle.
+    pub(crate) fn trip_emissions(
+        &self,
+        route: &[usize],
+        quantities: &[i32],
+        ends: RouteEnds,
+        customers: &[Customer],
+        distances: &DistanceMatrix,
+    ) -> f64 {
+        let Some(model) = self.emission_model else {
+            return 0.0;
+        };
+        if route.is_empty() {
+            return 0.0;
+        }
+        let capacity = self.capacity();
+        let profile = Load::profile_of_deliveries(route, quantities, customers);
+        let mut prev = ends.start();
+        let mut total = 0.0;
+        for (&cid, load) in route.iter().chain([&ends.end_node()]).zip(&profile) {
+            total += model.emissions(ends.leg(prev, cid, distances), load.get(0), capacity);
+            prev = cid;
+        }
+        total
+    }
+}
+
+/// Index of the cheapest vehicle type that can serve a route.
+///
+/// Considers types that carry `load` and still have a vehicle available
+/// given `used[k]` routes per type; ties go to the earlier type.
+/// `distance` gives the route distance for a type, or `None` if the type
+/// cannot serve the route (e.g. it is based at another depot).
+pub(crate) fn cheapest_vehicle(
+    fleet: &[Vehicle],
+    used: &[usize],
+    load: &Load,
+    distance: impl Fn(&Vehicle) -> Option<f64>,
+) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (k, vehicle) in fleet.iter().enumerate() {
+        if !vehicle.can_carry(load) || !vehicle.is_available(used.get(k).copied().unwrap_or(0)) {
+            continue;
+        }
+        let Some(distance) = distance(vehicle) else {
+            continue;
+        };
+        let cost = vehicle.route_cost(distance);
+        if best.is_none_or(|(_, c)| cost < c) {
+            best = Some((k, cost));
+        }
+    }
+    best.map(|(k, _)| k)
+}
+
+/// Index in `fleet` of the vehicle type with the given ID.
+pub(crate) fn fleet_index(fleet: &[Vehicle], vehicle_id: usize) -> Option<usize> {
+    fleet.iter().position(|v| v.id() == vehicle_id)
+}
+
+/// Vehicle type serving each route of `solution`, or `None` if a route's
+/// vehicle ID is not in `fleet`.
+pub(crate) fn route_vehicles<'a>(
+    solution: &Solution,
+    fleet: &'a [Vehicle],
+) -> Option<Vec<&'a Vehicle>> {
+    solution
+        .routes()
+        .iter()
+        .map(|r| fleet_index(fleet, r.vehicle_id()).map(|k| &fleet[k]))
+        .collect()
 }
 
 #[cfg(test)]
@@ -119,6 +665,7 @@ mod tests {
         assert_eq!(v.fixed_cost(), 0.0);
         assert!(v.max_distance().is_none());
         assert!(v.max_duration().is_none());
+        assert!(!v.is_multi_trip());
     }
 
     #[test]
@@ -137,4 +684,138 @@ mod tests {
         assert_eq!(v.max_distance(), Some(500.0));
         assert_eq!(v.max_duration(), Some(480.0));
     }
+
+    #[test]
+    fn test_vehicle_shift_and_end_location() {
+        let v = Vehicle::new(0, 100);
+        assert_eq!(v.end_location(), 0);
+        assert_eq!(v.shift_start(), 0.0);
+        assert!(v.shift_end().is_none());
+
+        let v = v
+            .with_depot(2)
+            .with_end_location(5)
+            .with_shift(480.0, 1020.0);
+        assert_eq!(v.end_location(), 5);
+        assert_eq!(v.shift_start(), 480.0);
+        assert_eq!(v.shift_end(), Some(1020.0));
+        assert_eq!(v.route_ends(), RouteEnds::new(2, 5));
+    }
+
+    #[test]
+    fn test_vehicle_skills() {
+        let plain = Customer::new(1, 0.0, 0.0, 10, 0.0);
+        let hazmat = Customer::new(2, 0.0, 0.0, 10, 0.0).with_skills(vec![3]);
+        let v = Vehicle::new(0, 100);
+        assert!(v.skills().is_empty());
+        assert!(v.can_serve(&plain));
+        assert!(!v.can_serve(&hazmat));
+
+        let v = v.with_skills(vec![1, 3]);
+        assert_eq!(v.skills(), &[1, 3]);
+        assert!(v.can_serve(&plain));
+        assert!(v.can_serve(&hazmat));
+
+        let customers = [Customer::depot(0.0, 0.0), plain, hazmat];
+        assert!(v.can_serve_route(&[1, 2], &customers));
+        assert!(!Vehicle::new(1, 100).can_serve_route(&[1, 2], &customers));
+        assert!(Vehicle::new(1, 100).can_serve_route(&[1], &customers));
+    }
+
+    #[test]
+    fn test_vehicle_availability_and_cost() {
+        let v = Vehicle::new(0, 100)
+            .with_available(2)
+            .with_fixed_cost(50.0)
+            .with_cost_per_distance(2.0);
+        assert_eq!(v.available(), Some(2));
+        assert!(v.is_available(1));
+        assert!(!v.is_available(2));
+        assert!((v.route_cost(10.0) - 70.0).abs() < 1e-10);
+        assert!(Vehicle::new(1, 100).is_available(1_000));
+    }
+
+    #[test]
+    fn test_vehicle_time_cost() {
+        let v = Vehicle::new(0, 100);
+        assert_eq!(v.cost_per_time(), 0.0);
+        assert!(v.overtime().is_none());
+        assert_eq!(v.time_cost(100.0), 0.0);
+
+        let v = v.with_cost_per_time(2.0).with_overtime(8.0, 1.0);
+        assert_eq!(v.overtime(), Some((8.0, 1.0)));
+        assert!((v.time_cost(5.0) - 10.0).abs() < 1e-10);
+        assert!((v.time_cost(10.0) - 22.0).abs() < 1e-10);
+        // Overtime alone
+        let v = Vehicle::new(1, 100).with_overtime(4.0, 3.0);
+        assert!((v.time_cost(6.0) - 6.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cheapest_vehicle() {
+        let fleet = vec![
+            Vehicle::new(0, 50).with_fixed_cost(10.0).with_available(1),
+            Vehicle::new(1, 200).with_fixed_cost(100.0),
+        ];
+        let mut load = Load::new();
+        load.add(&[40]);
+        let distance = |_: &Vehicle| Some(5.0);
+        assert_eq!(cheapest_vehicle(&fleet, &[0, 0], &load, distance), Some(0));
+        // Van used up: fall back to the truck
+        assert_eq!(cheapest_vehicle(&fleet, &[1, 0], &load, distance), Some(1));
+        // Only the truck can serve this route
+        let truck_only = |v: &Vehicle| (v.id() == 1).then_some(5.0);
+        assert_eq!(
+            cheapest_vehicle(&fleet, &[0, 0], &load, truck_only),
+            Some(1)
+        );
+        load.add(&[200]);
+        assert_eq!(cheapest_vehicle(&fleet, &[0, 0], &load, distance), None);
+        assert_eq!(fleet_index(&fleet, 1), Some(1));
+        assert_eq!(fleet_index(&fleet, 7), None);
+    }
+
+    #[test]
+    fn test_vehicle_route_emissions() {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0),
+            Customer::new(2, 0.0, 1.0, 90, 0.0),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        let model = EmissionModel::new(1.0, 2.0).expect("valid");
+        let v = Vehicle::new(0, 100).with_emissions(model);
+        let diagonal = 2f64.sqrt();
+
+        // Dropping the heavy customer first lightens the longer leg
+        let light_first = v.route_emissions(&[1, 2], &customers, &dm);
+        let heavy_first = v.route_emissions(&[2, 1], &customers, &dm);
+        assert!((light_first - (2.0 + 1.9 * diagonal + 1.0)).abs() < 1e-10);
+        assert!((heavy_first - (2.0 + 1.1 * diagonal + 1.0)).abs() < 1e-10);
+
+        // The empty return leg is not driven on an open route
+        let open = v.clone().with_open_route();
+        assert!((open.route_emissions(&[2, 1], &customers, &dm) - heavy_first + 1.0).abs() < 1e-10);
+
+        assert_eq!(v.emission_cost(&[1, 2], &customers, &dm), 0.0);
+        let priced = v.with_cost_per_emission(0.5);
+        assert!((priced.emission_cost(&[1, 2], &customers, &dm) - light_first / 2.0).abs() < 1e-10);
+        assert_eq!(
+            Vehicle::new(1, 100).route_emissions(&[1, 2], &customers, &dm),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_vehicle_multi_dimensional_capacity() {
+        let v = Vehicle::new(0, 0).with_capacities(vec![1000, 30]);
+        assert_eq!(v.capacity(), 1000);
+        assert_eq!(v.capacities(), &[1000, 30]);
+
+        let mut load = Load::new();
+        load.add(&[900, 30]);
+        assert!(v.can_carry(&load));
+        load.add(&[0, 1]);
+        assert!(!v.can_carry(&load));
+    }
 }
@@ -0,0 +1,76 @@
+//! Visit patterns for periodic routing.
+//!
+//! Over a multi-day planning horizon, a customer is visited on one of a set
+//! of allowed day combinations (e.g. Monday–Thursday or Tuesday–Friday for
+//! two visits a week). Days are numbered from 0.
+
+/// A combination of days on which a customer is visited.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::VisitPattern;
+///
+/// // Monday and Thursday of a week starting on Monday
+/// let p = VisitPattern::new(vec![3, 0]);
+/// assert_eq!(p.days(), &[0, 3]);
+/// assert_eq!(p.frequency(), 2);
+/// assert!(p.includes(3));
+/// assert!(!p.includes(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisitPattern {
+    days: Vec<usize>,
+}
+
+impl VisitPattern {
+    /// Creates a pattern visiting on the given days (sorted, duplicates removed).
+    pub fn new(mut days: Vec<usize>) -> Self {
+        days.sort_unstable();
+        days.dedup();
+        Self { days }
+    }
+
+    /// A pattern visiting on every day of a horizon of `num_days`.
+    pub fn every_day(num_days: usize) -> Self {
+        Self {
+            days: (0..num_days).collect(),
+        }
+    }
+
+    /// Days of the visits, in increasing order.
+    pub fn days(&self) -> &[usize] {
+        &self.days
+    }
+
+    /// Number of visits.
+    pub fn frequency(&self) -> usize {
+        self.days.len()
+    }
+
+    /// Returns `true` if the pattern visits on `day`.
+    pub fn includes(&self, day: usize) -> bool {
+        self.days.binary_search(&day).is_ok()
+    }
+
+    /// Returns `true` if every day of the pattern is within a horizon of
+    /// `num_days`.
+    pub fn fits_horizon(&self, num_days: usize) -> bool {
+        self.days.last().is_none_or(|&d| d < num_days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visit_pattern() {
+        let p = VisitPattern::new(vec![4, 1, 4]);
+        assert_eq!(p.days(), &[1, 4]);
+        assert_eq!(p.frequency(), 2);
+        assert!(p.fits_horizon(5));
+        assert!(!p.fits_horizon(4));
+        assert_eq!(VisitPattern::every_day(3).days(), &[0, 1, 2]);
+    }
+}
@@ -0,0 +1,36 @@
+//! Periodic vehicle routing over a multi-day planning horizon (PVRP).
+//!
+//! Each customer needs a number of visits over the horizon, on one of its
+//! allowed day combinations ([`VisitPattern`](crate::models::VisitPattern)).
+//! A periodic solution chooses a pattern per customer and routes every day
+//! for the customers visited that day.
+//!
+//! - [`PeriodicSolution`] — Visit pattern per customer and one
+//!   [`RoutingSolution`](crate::alns::RoutingSolution) per day
+//! - [`PeriodicProblem`] — [`AlnsProblem`](u_metaheur::alns::AlnsProblem)
+//!   implementation
+//! - [`PatternRemoval`] / [`PatternInsertion`] — ALNS operators that change
+//!   visit patterns and daily routes together
+//!
+//! # Algorithm
+//!
+//! 1. Assign patterns, balancing demand across days
+//!    ([`PeriodicProblem::assign_patterns`]).
+//! 2. Route each day with greedy insertion or a caller-supplied day solver
+//!    ([`PeriodicProblem::route_days`]).
+//! 3. Improve with ALNS: remove customers from all their visit days, then
+//!    reinsert each with its cheapest pattern.
+//!
+//! # Reference
+//!
+//! Hemmelmayr, V. C., Doerner, K. F. & Hartl, R. F. (2009). "A variable
+//! neighborhood search heuristic for periodic routing problems",
+//! *European Journal of Operational Research* 195(3), 791-802.
+
+mod operators;
+mod problem;
+mod solution;
+
+pub use operators::{PatternInsertion, PatternRemoval};
+pub use problem::PeriodicProblem;
+pub use solution::PeriodicSolution;
@@ -0,0 +1,275 @@
+//! ALNS operators that change the visit patterns of customers.
+//!
+//! # Operators
+//!
+//! - [`PatternRemoval`] — Removes random customers from every day they are visited
+//! - [`PatternInsertion`] — Gives each unassigned customer its cheapest visit
+//!   pattern, inserting it greedily into the routes of each pattern day
+//!
+//! Together they move customers between visit patterns: a removed customer
+//! is reinserted with whichever of its patterns is cheapest given the
+//! routes of the other customers.
+
+use rand::Rng;
+use u_metaheur::alns::{DestroyOperator, RepairOperator};
+
+use crate::alns::repair::GreedyInsertion;
+use crate::alns::{RoutingAlnsProblem, RoutingSolution};
+use crate::distance::DistanceMatrix;
+use crate::models::{Customer, Vehicle, VisitPattern};
+
+use super::problem::allowed_patterns;
+use super::solution::PeriodicSolution;
+
+/// Removes random customers, with all their visits, and clears their
+/// visit patterns.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::Customer;
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::periodic::{PatternRemoval, PeriodicProblem};
+/// use u_metaheur::alns::{AlnsProblem, DestroyOperator};
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+///     Customer::new(2, 2.0, 0.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let problem = PeriodicProblem::new(customers, dm, 30, 2);
+/// let mut rng = u_numflow::random::create_rng(42);
+/// let sol = problem.initial_solution(&mut rng);
+///
+/// let destroyed = PatternRemoval.destroy(&sol, 0.5, &mut rng);
+/// assert_eq!(destroyed.unassigned().len(), 1);
+/// ```
+pub struct PatternRemoval;
+
+impl DestroyOperator<PeriodicSolution> for PatternRemoval {
+    fn name(&self) -> &str {
+        "pattern_removal"
+    }
+
+    fn destroy<R: Rng>(
+        &self,
+        solution: &PeriodicSolution,
+        degree: f64,
+        rng: &mut R,
+    ) -> PeriodicSolution {
+        let mut sol = solution.clone();
+        let mut assigned: Vec<usize> = sol.assigned().collect();
+        let num_remove = ((assigned.len() as f64 * degree).round() as usize).max(1);
+
+        for _ in 0..num_remove {
+            if assigned.is_empty() {
+                break;
+            }
+            let pick = rng.random_range(0..assigned.len() as u64) as usize;
+            sol.unassign(assigned.swap_remove(pick));
+        }
+        sol
+    }
+}
+
+/// Pattern insertion: reinserts each unassigned customer with its cheapest
+/// visit pattern.
+///
+/// Customers are taken in random order. For each allowed pattern, the
+/// customer is inserted greedily into the routes of every pattern day (see
+/// [`GreedyInsertion`]); the pattern adding the least routing cost wins.
+/// A pattern is only used if the customer can be routed on all its days,
+/// so a customer with no such pattern stays unassigned.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, VisitPattern};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::periodic::{PatternInsertion, PatternRemoval, PeriodicProblem};
+/// use u_metaheur::alns::{AlnsProblem, DestroyOperator, RepairOperator};
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+///     Customer::new(2, 2.0, 0.0, 10, 0.0).with_visit_patterns(vec![
+///         VisitPattern::new(vec![0]),
+///         VisitPattern::new(vec![1]),
+///     ]),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let problem = PeriodicProblem::new(customers.clone(), dm.clone(), 30, 2);
+/// let mut rng = u_numflow::random::create_rng(42);
+/// let destroyed = PatternRemoval.destroy(&problem.initial_solution(&mut rng), 1.0, &mut rng);
+///
+/// let op = PatternInsertion::new(dm, customers, 30);
+/// let repaired = op.repair(&destroyed, &mut rng);
+/// assert!(repaired.unassigned().is_empty());
+/// assert_eq!(repaired.pattern_of(1), Some(&VisitPattern::every_day(2)));
+/// ```
+pub struct PatternInsertion {
+    customers: Vec<Customer>,
+    daily: RoutingAlnsProblem,
+    insertion: GreedyInsertion,
+}
+
+impl PatternInsertion {
+    /// Creates a new pattern insertion operator.
+    pub fn new(distances: DistanceMatrix, customers: Vec<Customer>, capacity: i32) -> Self {
+        Self {
+            daily: RoutingAlnsProblem::new(customers.clone(), distances.clone(), capacity),
+            insertion: GreedyInsertion::new(distances, customers.clone(), capacity),
+            customers,
+        }
+    }
+
+    /// Sets a heterogeneous fleet of vehicle types, available again every
+    /// day, replacing the single vehicle given to [`new`](Self::new).
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.daily = self.daily.with_fleet(fleet);
+        self.insertion = self.insertion.with_fleet(fleet);
+        self
+    }
+
+    /// Routes of `day` with `customer_id` inserted greedily, and the routing
+    /// cost this adds, or `None` if it cannot be inserted.
+    fn insert_on_day<R: Rng>(
+        &self,
+        day: &RoutingSolution,
+        customer_id: usize,
+        rng: &mut R,
+    ) -> Option<(RoutingSolution, f64)> {
+        let mut trial = day.clone();
+        let missed = std::mem::replace(trial.unassigned_mut(), vec![customer_id]);
+        let mut trial = self.insertion.repair(&trial, rng);
+        if !trial.unassigned().is_empty() {
+            return None;
+        }
+        *trial.unassigned_mut() = missed;
+        let delta = self.daily.routes_cost(&trial) - self.daily.routes_cost(day);
+        Some((trial, delta))
+    }
+
+    /// Cheapest pattern for `customer_id` with the routes of its days.
+    fn best_pattern<R: Rng>(
+        &self,
+        sol: &PeriodicSolution,
+        customer_id: usize,
+        rng: &mut R,
+    ) -> Option<(VisitPattern, Vec<RoutingSolution>)> {
+        let mut best: Option<(VisitPattern, Vec<RoutingSolution>, f64)> = None;
+        for pattern in allowed_patterns(&self.customers[customer_id], sol.num_days()) {
+            let mut days = Vec::with_capacity(pattern.frequency());
+            let mut cost = 0.0;
+            for &d in pattern.days() {
+                match self.insert_on_day(sol.day(d), customer_id, rng) {
+                    Some((day, delta)) => {
+                        days.push(day);
+                        cost += delta;
+                    }
+                    None => break,
+                }
+            }
+            if days.len() == pattern.frequency() && best.as_ref().is_none_or(|b| cost < b.2) {
+                best = Some((pattern, days, cost));
+            }
+        }
+        best.map(|(pattern, days, _)| (pattern, days))
+    }
+}
+
+impl RepairOperator<PeriodicSolution> for PatternInsertion {
+    fn name(&self) -> &str {
+        "pattern_insertion"
+    }
+
+    fn repair<R: Rng>(&self, solution: &PeriodicSolution, rng: &mut R) -> PeriodicSolution {
+        let mut sol = solution.clone();
+        let mut pending = std::mem::take(sol.unassigned_mut());
+
+        // Fisher-Yates shuffle
+        for i in (1..pending.len()).rev() {
+            let j = rng.random_range(0..=i as u64) as usize;
+            pending.swap(i, j);
+        }
+
+        for cid in pending {
+            match self.best_pattern(&sol, cid, rng) {
+                Some((pattern, days)) => {
+                    for (&d, day) in pattern.days().iter().zip(days) {
+                        *sol.day_mut(d) = day;
+                    }
+                    sol.assign(cid, pattern);
+                }
+                None => sol.unassigned_mut().push(cid),
+            }
+        }
+        sol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periodic::PeriodicProblem;
+    use u_metaheur::alns::AlnsProblem;
+
+    fn setup() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 10, 0.0)
+                .with_visit_patterns(vec![VisitPattern::new(vec![0])]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0)
+                .with_visit_patterns(vec![VisitPattern::new(vec![1]), VisitPattern::new(vec![0])]),
+            Customer::new(3, 0.0, 5.0, 10, 0.0)
+                .with_visit_patterns(vec![VisitPattern::new(vec![1])]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_pattern_removal_clears_all_visits() {
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust, dm, 30, 2);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = problem.initial_solution(&mut rng);
+        let destroyed = PatternRemoval.destroy(&sol, 1.0, &mut rng);
+        assert_eq!(destroyed.unassigned().len(), 3);
+        assert_eq!(destroyed.assigned().count(), 0);
+        assert!(destroyed.days().iter().all(|d| d.num_routes() == 0));
+    }
+
+    #[test]
+    fn test_pattern_insertion_picks_cheapest_pattern() {
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust.clone(), dm.clone(), 30, 2);
+        let mut rng = u_numflow::random::create_rng(42);
+        let mut sol = problem.initial_solution(&mut rng);
+        sol.unassign(2);
+
+        let op = PatternInsertion::new(dm, cust, 30);
+        let repaired = op.repair(&sol, &mut rng);
+        // Next to customer 1 on day 0 rather than on its own on day 1
+        assert_eq!(repaired.pattern_of(2), Some(&VisitPattern::new(vec![0])));
+        assert_eq!(repaired.day(0).num_routes(), 1);
+        assert!(repaired.day(0).routes()[0].contains(&2));
+        assert_eq!(repaired.day(1).routes(), &[vec![3]]);
+    }
+
+    #[test]
+    fn test_pattern_insertion_leaves_unservable_unassigned() {
+        let (mut cust, dm) = setup();
+        cust[2] = cust[2].clone().with_skills(vec![7]);
+        let problem = PeriodicProblem::new(cust.clone(), dm.clone(), 30, 2);
+        let mut rng = u_numflow::random::create_rng(42);
+        let mut sol = problem.initial_solution(&mut rng);
+        sol.unassign(2);
+
+        let op = PatternInsertion::new(dm, cust, 30);
+        let repaired = op.repair(&sol, &mut rng);
+        assert_eq!(repaired.unassigned(), &[2]);
+        assert!(repaired.pattern_of(2).is_none());
+    }
+}
@@ -0,0 +1,399 @@
+//! Periodic VRP problem definition.
+//!
+//! Implements the [`AlnsProblem`](u_metaheur::alns::AlnsProblem) trait over
+//! a [`PeriodicSolution`]. The cost of a solution is the sum of the daily
+//! routing costs, as in [`RoutingAlnsProblem`], plus a penalty for every
+//! visit missed: the visits of a customer left unrouted on one of its
+//! pattern days, and the visits of its least frequent pattern for a
+//! customer with no pattern assigned.
+
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+use u_metaheur::alns::{AlnsProblem, RepairOperator};
+
+use crate::alns::repair::GreedyInsertion;
+use crate::alns::{RoutingAlnsProblem, RoutingSolution};
+use crate::distance::DistanceMatrix;
+use crate::models::{stop_ids, Customer, Vehicle, VisitPattern};
+
+use super::solution::PeriodicSolution;
+
+/// Visit patterns of `customer` that fit a horizon of `num_days`; every day
+/// if it has no patterns.
+pub(crate) fn allowed_patterns(customer: &Customer, num_days: usize) -> Vec<VisitPattern> {
+    if customer.visit_patterns().is_empty() {
+        return vec![VisitPattern::every_day(num_days)];
+    }
+    customer
+        .visit_patterns()
+        .iter()
+        .filter(|p| p.fits_horizon(num_days))
+        .cloned()
+        .collect()
+}
+
+/// Routes one day: takes a solution with no routes whose unassigned
+/// customers are the day's visits, and returns the routed day.
+type DaySolver = dyn Fn(&RoutingSolution, &mut dyn RngCore) -> RoutingSolution + Send + Sync;
+
+/// Periodic vehicle routing problem over a multi-day planning horizon.
+///
+/// Each customer is visited on the days of one of its
+/// [visit patterns](Customer::with_visit_patterns); the routes of each day
+/// are planned with greedy insertion, or with the single-day heuristic set
+/// by [`with_day_solver`](Self::with_day_solver).
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::models::{Customer, VisitPattern};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::periodic::{PatternInsertion, PatternRemoval, PeriodicProblem};
+/// use u_metaheur::alns::{AlnsConfig, AlnsProblem, AlnsRunner};
+///
+/// let twice = vec![VisitPattern::new(vec![0, 2]), VisitPattern::new(vec![1, 3])];
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0).with_visit_patterns(twice.clone()),
+///     Customer::new(2, 2.0, 0.0, 10, 0.0).with_visit_patterns(twice),
+///     Customer::new(3, 0.0, 3.0, 10, 0.0)
+///         .with_visit_patterns(vec![VisitPattern::new(vec![0])]),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+///
+/// let problem = PeriodicProblem::new(customers.clone(), dm.clone(), 30, 4);
+/// let destroy_ops = vec![PatternRemoval];
+/// let repair_ops = vec![PatternInsertion::new(dm, customers, 30)];
+/// let config = AlnsConfig::default().with_max_iterations(100).with_seed(42);
+///
+/// let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).unwrap();
+/// assert!(result.best.unassigned().is_empty());
+/// assert!(result.best_cost < f64::INFINITY);
+/// ```
+pub struct PeriodicProblem {
+    customers: Vec<Customer>,
+    distances: DistanceMatrix,
+    num_days: usize,
+    daily: RoutingAlnsProblem,
+    insertion: GreedyInsertion,
+    day_solver: Option<Arc<DaySolver>>,
+}
+
+impl PeriodicProblem {
+    /// Creates a periodic problem over `num_days` days.
+    pub fn new(
+        customers: Vec<Customer>,
+        distances: DistanceMatrix,
+        capacity: i32,
+        num_days: usize,
+    ) -> Self {
+        Self {
+            daily: RoutingAlnsProblem::new(customers.clone(), distances.clone(), capacity),
+            insertion: GreedyInsertion::new(distances.clone(), customers.clone(), capacity),
+            day_solver: None,
+            customers,
+            distances,
+            num_days,
+        }
+    }
+
+    /// Uses a heterogeneous fleet, available again every day, replacing the
+    /// capacity given to [`new`](Self::new).
+    pub fn with_fleet(mut self, fleet: &[Vehicle]) -> Self {
+        self.daily = self.daily.with_fleet(fleet);
+        self.insertion = self.insertion.with_fleet(fleet);
+        self
+    }
+
+    /// Sets the penalty for each missed visit of a mandatory customer
+    /// (10,000 by default).
+    ///
+    /// Missed visits of optional customers forfeit their prize instead.
+    pub fn with_unassigned_penalty(mut self, penalty: f64) -> Self {
+        self.daily = self.daily.with_unassigned_penalty(penalty);
+        self
+    }
+
+    /// Routes each day of the initial solution with `solver` instead of
+    /// greedy insertion.
+    ///
+    /// The solver receives a [`RoutingSolution`] with no routes whose
+    /// unassigned customers are the visits of the day, and returns the
+    /// routed day; customers it leaves unassigned count as missed visits.
+    ///
+    /// # Examples
+    ///
+    /// Greedy insertion followed by 2-opt on every route:
+    ///
+    /// ```
+    /// use u_routing::alns::repair::GreedyInsertion;
+    /// use u_routing::alns::RoutingSolution;
+    /// use u_routing::distance::DistanceMatrix;
+    /// use u_routing::local_search::two_opt_improve;
+    /// use u_routing::models::{Customer, VisitPattern};
+    /// use u_routing::periodic::PeriodicProblem;
+    /// use u_metaheur::alns::{AlnsProblem, RepairOperator};
+    ///
+    /// let daily = vec![VisitPattern::every_day(2)];
+    /// let customers = vec![
+    ///     Customer::depot(0.0, 0.0),
+    ///     Customer::new(1, 1.0, 0.0, 10, 0.0).with_visit_patterns(daily.clone()),
+    ///     Customer::new(2, 2.0, 0.0, 10, 0.0).with_visit_patterns(daily),
+    /// ];
+    /// let dm = DistanceMatrix::from_customers(&customers);
+    ///
+    /// let insertion = GreedyInsertion::new(dm.clone(), customers.clone(), 30);
+    /// let (cust, distances) = (customers.clone(), dm.clone());
+    /// let problem = PeriodicProblem::new(customers, dm, 30, 2).with_day_solver(
+    ///     move |day: &RoutingSolution, mut rng| {
+    ///         let routed = insertion.repair(day, &mut rng);
+    ///         let routes = routed
+    ///             .routes()
+    ///             .iter()
+    ///             .map(|r| two_opt_improve(r, 0, &distances).0)
+    ///             .collect();
+    ///         RoutingSolution::new(routes, routed.unassigned().to_vec(), &cust, &distances)
+    ///     },
+    /// );
+    ///
+    /// let mut rng = u_numflow::random::create_rng(42);
+    /// let sol = problem.initial_solution(&mut rng);
+    /// assert!((problem.cost(&sol) - 8.0).abs() < 1e-10);
+    /// ```
+    pub fn with_day_solver(
+        mut self,
+        solver: impl Fn(&RoutingSolution, &mut dyn RngCore) -> RoutingSolution + Send + Sync + 'static,
+    ) -> Self {
+        self.day_solver = Some(Arc::new(solver));
+        self
+    }
+
+    /// Number of days in the planning horizon.
+    pub fn num_days(&self) -> usize {
+        self.num_days
+    }
+
+    /// Chooses a visit pattern for each customer, balancing demand across
+    /// days.
+    ///
+    /// Customers with the fewest patterns go first, larger demands first
+    /// among them; each takes the pattern that keeps the busiest of its days
+    /// least loaded. Returns `None` for depots and for customers with no
+    /// pattern within the horizon.
+    pub fn assign_patterns(&self) -> Vec<Option<VisitPattern>> {
+        let mut order: Vec<(usize, Vec<VisitPattern>)> = stop_ids(&self.customers)
+            .map(|cid| (cid, allowed_patterns(&self.customers[cid], self.num_days)))
+            .collect();
+        order.sort_by_key(|(cid, patterns)| {
+            (
+                patterns.len(),
+                std::cmp::Reverse(self.customers[*cid].demand()),
+            )
+        });
+
+        let mut day_load = vec![0i64; self.num_days];
+        let mut assigned = vec![None; self.customers.len()];
+        for (cid, patterns) in order {
+            let demand = i64::from(self.customers[cid].demand());
+            let busiest = |p: &VisitPattern| {
+                let loads = p.days().iter().map(|&d| day_load[d]);
+                (loads.clone().max().unwrap_or(0), loads.sum::<i64>())
+            };
+            let Some(best) = patterns.into_iter().min_by_key(busiest) else {
+                continue;
+            };
+            for &d in best.days() {
+                day_load[d] += demand;
+            }
+            assigned[cid] = Some(best);
+        }
+        assigned
+    }
+
+    /// Routes each day for the customers whose pattern includes it, with
+    /// greedy insertion or the [day solver](Self::with_day_solver).
+    pub fn route_days<R: Rng>(
+        &self,
+        mut patterns: Vec<Option<VisitPattern>>,
+        rng: &mut R,
+    ) -> PeriodicSolution {
+        let days = (0..self.num_days)
+            .map(|d| {
+                let stops: Vec<usize> = (0..patterns.len())
+                    .filter(|&cid| patterns[cid].as_ref().is_some_and(|p| p.includes(d)))
+                    .collect();
+                let empty =
+                    RoutingSolution::new(Vec::new(), stops, &self.customers, &self.distances);
+                match &self.day_solver {
+                    Some(solver) => solver(&empty, &mut *rng),
+                    None => self.insertion.repair(&empty, rng),
+                }
+            })
+            .collect();
+
+        let mut sol = PeriodicSolution::new(days, self.customers.len());
+        for cid in stop_ids(&self.customers) {
+            match patterns.get_mut(cid).and_then(Option::take) {
+                Some(p) => sol.assign(cid, p),
+                None => sol.unassigned_mut().push(cid),
+            }
+        }
+        sol
+    }
+
+    /// Penalty for leaving `customer_id` without a visit pattern.
+    fn unassigned_cost(&self, customer_id: usize) -> f64 {
+        let visits = allowed_patterns(&self.customers[customer_id], self.num_days)
+            .iter()
+            .map(VisitPattern::frequency)
+            .min()
+            .unwrap_or(1);
+        self.daily.unassigned_cost(customer_id) * visits as f64
+    }
+}
+
+impl AlnsProblem for PeriodicProblem {
+    type Solution = PeriodicSolution;
+
+    fn initial_solution<R: Rng>(&self, rng: &mut R) -> PeriodicSolution {
+        self.route_days(self.assign_patterns(), rng)
+    }
+
+    fn cost(&self, solution: &PeriodicSolution) -> f64 {
+        let daily: f64 = solution.days().iter().map(|d| self.daily.cost(d)).sum();
+        let unassigned: f64 = solution
+            .unassigned()
+            .iter()
+            .map(|&cid| self.unassigned_cost(cid))
+            .sum();
+        daily + unassigned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Vec<Customer>, DistanceMatrix) {
+        let customers = vec![
+            Customer::depot(0.0, 0.0),
+            Customer::new(1, 1.0, 0.0, 20, 0.0)
+                .with_visit_patterns(vec![VisitPattern::new(vec![0, 2])]),
+            Customer::new(2, 2.0, 0.0, 10, 0.0).with_visit_patterns(vec![
+                VisitPattern::new(vec![0, 2]),
+                VisitPattern::new(vec![1, 3]),
+            ]),
+            Customer::new(3, 0.0, 1.0, 10, 0.0).with_visit_patterns(vec![
+                VisitPattern::new(vec![0]),
+                VisitPattern::new(vec![1]),
+                VisitPattern::new(vec![2]),
+                VisitPattern::new(vec![3]),
+            ]),
+        ];
+        let dm = DistanceMatrix::from_customers(&customers);
+        (customers, dm)
+    }
+
+    #[test]
+    fn test_assign_patterns_balances_days() {
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust, dm, 30, 4);
+        let patterns = problem.assign_patterns();
+        assert!(patterns[0].is_none());
+        assert_eq!(patterns[1], Some(VisitPattern::new(vec![0, 2])));
+        // Days 0 and 2 already carry customer 1
+        assert_eq!(patterns[2], Some(VisitPattern::new(vec![1, 3])));
+        assert_eq!(patterns[3], Some(VisitPattern::new(vec![1])));
+    }
+
+    #[test]
+    fn test_patterns_outside_horizon() {
+        let (mut cust, dm) = setup();
+        cust[1] = cust[1]
+            .clone()
+            .with_visit_patterns(vec![VisitPattern::new(vec![5])]);
+        cust[3] = cust[3].clone().with_visit_patterns(Vec::new());
+        let problem = PeriodicProblem::new(cust, dm, 30, 4);
+        let patterns = problem.assign_patterns();
+        assert!(patterns[1].is_none());
+        // No patterns: visited every day
+        assert_eq!(patterns[3], Some(VisitPattern::every_day(4)));
+    }
+
+    #[test]
+    fn test_initial_solution_routes_each_day() {
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust, dm, 30, 4);
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = problem.initial_solution(&mut rng);
+        assert_eq!(sol.num_days(), 4);
+        assert!(sol.unassigned().is_empty());
+        let day = |d: usize| {
+            let mut ids: Vec<usize> = sol.day(d).routes().concat();
+            ids.sort();
+            ids
+        };
+        assert_eq!(day(0), vec![1]);
+        assert_eq!(day(1), vec![2, 3]);
+        assert_eq!(day(2), vec![1]);
+        assert_eq!(day(3), vec![2]);
+        // 2 + (2 + 1 + √5) + 2 + 4
+        let expected = 11.0 + 5.0_f64.sqrt();
+        assert!((problem.cost(&sol) - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_day_solver_routes_each_day() {
+        let (cust, dm) = setup();
+        let (customers, distances) = (cust.clone(), dm.clone());
+        // One route per day, stops in decreasing id order
+        let problem = PeriodicProblem::new(cust, dm, 30, 4).with_day_solver(
+            move |day: &RoutingSolution, _: &mut dyn RngCore| {
+                let mut route = day.unassigned().to_vec();
+                route.sort_by(|a, b| b.cmp(a));
+                RoutingSolution::new(vec![route], Vec::new(), &customers, &distances)
+            },
+        );
+        let mut rng = u_numflow::random::create_rng(42);
+        let sol = problem.initial_solution(&mut rng);
+        assert_eq!(sol.day(0).routes(), &[vec![1]]);
+        assert_eq!(sol.day(1).routes(), &[vec![3, 2]]);
+        assert_eq!(sol.day(3).routes(), &[vec![2]]);
+        assert!(sol.unassigned().is_empty());
+    }
+
+    #[test]
+    fn test_cost_penalizes_missed_visits() {
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust, dm, 30, 4).with_unassigned_penalty(100.0);
+        let mut rng = u_numflow::random::create_rng(42);
+        let mut sol = problem.initial_solution(&mut rng);
+        let before = problem.cost(&sol);
+        // Customer 1 is visited twice (2 each time)
+        sol.unassign(1);
+        assert!((problem.cost(&sol) - (before - 4.0 + 200.0)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_alns_runner_serves_all_visits() {
+        use super::super::{PatternInsertion, PatternRemoval};
+        use u_metaheur::alns::{AlnsConfig, AlnsRunner};
+
+        let (cust, dm) = setup();
+        let problem = PeriodicProblem::new(cust.clone(), dm.clone(), 30, 4);
+        let destroy_ops = vec![PatternRemoval];
+        let repair_ops = vec![PatternInsertion::new(dm, cust, 30)];
+        let config = AlnsConfig::default().with_max_iterations(200).with_seed(42);
+        let result = AlnsRunner::run(&problem, &destroy_ops, &repair_ops, &config).expect("valid");
+        assert!(result.best.unassigned().is_empty());
+        let mut rng = u_numflow::random::create_rng(42);
+        assert!(result.best_cost <= problem.cost(&problem.initial_solution(&mut rng)) + 1e-10);
+        // Customer 2 is visited on the days of its pattern only
+        let pattern = result.best.pattern_of(2).expect("valid").clone();
+        for d in 0..4 {
+            let visited = result.best.day(d).routes().iter().any(|r| r.contains(&2));
+            assert_eq!(visited, pattern.includes(d));
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Solution representation for periodic routing.
+//!
+//! A periodic solution records the visit pattern chosen for each customer
+//! and one [`RoutingSolution`] per day of the planning horizon, holding the
+//! routes that serve the customers visited that day.
+
+use crate::alns::RoutingSolution;
+use crate::models::VisitPattern;
+
+/// Multi-day solution: a visit pattern per customer and the routes of each day.
+///
+/// # Examples
+///
+/// ```
+/// use u_routing::alns::RoutingSolution;
+/// use u_routing::models::{Customer, VisitPattern};
+/// use u_routing::distance::DistanceMatrix;
+/// use u_routing::periodic::PeriodicSolution;
+///
+/// let customers = vec![
+///     Customer::depot(0.0, 0.0),
+///     Customer::new(1, 1.0, 0.0, 10, 0.0),
+/// ];
+/// let dm = DistanceMatrix::from_customers(&customers);
+/// let days = vec![
+///     RoutingSolution::new(vec![vec![1]], vec![], &customers, &dm),
+///     RoutingSolution::new(vec![], vec![], &customers, &dm),
+///     RoutingSolution::new(vec![vec![1]], vec![], &customers, &dm),
+/// ];
+/// let mut sol = PeriodicSolution::new(days, customers.len());
+/// sol.assign(1, VisitPattern::new(vec![0, 2]));
+/// assert_eq!(sol.num_days(), 3);
+/// assert_eq!(sol.pattern_of(1).map(|p| p.days()), Some(&[0, 2][..]));
+///
+/// sol.unassign(1);
+/// assert_eq!(sol.unassigned(), &[1]);
+/// assert_eq!(sol.day(0).num_routes(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PeriodicSolution {
+    days: Vec<RoutingSolution>,
+    patterns: Vec<Option<VisitPattern>>,
+    unassigned: Vec<usize>,
+}
+
+impl PeriodicSolution {
+    /// Creates a solution from the routes of each day, with no visit
+    /// pattern assigned yet to any of the `num_customers` locations.
+    pub fn new(days: Vec<RoutingSolution>, num_customers: usize) -> Self {
+        Self {
+            days,
+            patterns: vec![None; num_customers],
+            unassigned: Vec::new(),
+        }
+    }
+
+    /// Records `pattern` as the visit pattern of `customer_id`.
+    ///
+    /// The day routes are left unchanged.
+    pub fn assign(&mut self, customer_id: usize, pattern: VisitPattern) {
+        self.patterns[customer_id] = Some(pattern);
+        self.unassigned.retain(|&c| c != customer_id);
+    }
+
+    /// Removes `customer_id` from the routes of every day (dropping routes
+    /// left empty), clears its visit pattern and marks it unassigned.
+    pub fn unassign(&mut self, customer_id: usize) {
+        for day in &mut self.days {
+            for route in day.routes_mut() {
+                route.retain(|&c| c != customer_id);
+            }
+            day.unassigned_mut().retain(|&c| c != customer_id);
+            day.remove_empty_routes();
+        }
+        self.patterns[customer_id] = None;
+        if !self.unassigned.contains(&customer_id) {
+            self.unassigned.push(customer_id);
+        }
+    }
+
+    /// Returns the routes of every day.
+    pub fn days(&self) -> &[RoutingSolution] {
+        &self.days
+    }
+
+    /// Returns the routes of `day`.
+    pub fn day(&self, day: usize) -> &RoutingSolution {
+        &self.days[day]
+    }
+
+    /// Returns mutable routes of `day`.
+    pub fn day_mut(&mut self, day: usize) -> &mut RoutingSolution {
+        &mut self.days[day]
+    }
+
+    /// Number of days in the planning horizon.
+    pub fn num_days(&self) -> usize {
+        self.days.len()
+    }
+
+    /// Visit pattern assigned to `customer_id`, if any.
+    pub fn pattern_of(&self, customer_id: usize) -> Option<&VisitPattern> {
+        self.patterns.get(customer_id)?.as_ref()
+    }
+
+    /// Customers with a visit pattern assigned, in ID order.
+    pub fn assigned(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.patterns.len()).filter(|&cid| self.patterns[cid].is_some())
+    }
+
+    /// Customers with no visit pattern assigned.
+    pub fn unassigned(&self) -> &[usize] {
+        &self.unassigned
+    }
+
+    /// Returns a mutable reference to the unassigned list.
+    pub fn unassigned_mut(&mut self) -> &mut Vec<usize> {
+        &mut self.unassigned
+    }
+
+    /// Total distance over all days.
+    pub fn total_distance(&self) -> f64 {
+        self.days.iter().map(RoutingSolution::total_distance).sum()
+    }
+}
@@ -40,21 +40,53 @@
 //!   method: "alns",
 //!   config: { max_iterations: 1000 },
 //! });
+//!
+//! // Vehicle costs: fixed cost, cost per distance, driver wages per time
+//! // unit and overtime ([regular time, extra cost per time unit])
+//! const costResult = solve_vrp({
+//!   customers: [...],
+//!   vehicles: [{ capacity: 100.0, fixed_cost: 50.0, cost_per_time: 20.0,
+//!                overtime: [8.0, 10.0] }],
+//!   depot: { x: 0.0, y: 0.0 },
+//!   method: "alns",
+//!   config: { unassigned_penalty: 1000.0 },
+//! });
+//! console.log(costResult.total_cost);
+//!
+//! // CVRPLIB benchmark: distances rounded as TSPLIB EUC_2D
+//! const benchmark = solve_vrp({
+//!   customers: [...],
+//!   vehicles: [{ capacity: 6000.0 }],
+//!   depot: { x: 145.0, y: 215.0 },
+//!   method: "alns",
+//!   metric: "euc_2d",   // "euclidean" | "manhattan" | "chebyshev" | "euc_2d" | "ceil_2d" | "geo"
+//! });
 //! ```
+//!
+//! Every method plans with the whole fleet. Each entry of `vehicles` is one
+//! vehicle, serving at most one route; without `vehicles` the fleet is an
+//! unlimited number of uncapacitated vehicles. The GA routes either every
+//! customer or, if the fleet cannot serve them all, none; the other methods
+//! leave out the customers that do not fit. `total_cost` is the
+//! [standard cost](crate::evaluation::StandardCost) of the routes, each on
+//! its vehicle type, plus `unassigned_penalty` (default: 10,000) for each
+//! customer left unrouted, as minimized by the ALNS.
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 use crate::alns::destroy::RandomRemoval;
 use crate::alns::repair::GreedyInsertion;
-use crate::alns::RoutingAlnsProblem;
+use crate::alns::{RoutingAlnsProblem, RoutingSolution};
 use crate::constructive::{clarke_wright_savings, nearest_neighbor};
-use crate::distance::DistanceMatrix;
-use crate::ga::split;
+use crate::distance::{DistanceMatrix, Metric};
+use crate::ga::split_fleet;
 use crate::ga::RoutingGaProblem;
 use crate::local_search::{or_opt_improve, two_opt_improve};
-use crate::models::{Customer, TimeWindow, Vehicle};
-use u_metaheur::alns::{AlnsConfig, AlnsRunner};
+use crate::models::{
+    fleet_index, has_load_profile, stop_ids, Customer, RouteEnds, Solution, TimeWindow, Vehicle,
+};
+use u_metaheur::alns::{AlnsConfig, AlnsProblem, AlnsRunner};
 use u_metaheur::ga::{GaConfig, GaRunner};
 
 // ============================================================================
@@ -106,12 +138,28 @@ struct InputCustomer {
 struct InputVehicle {
     #[serde(default = "default_capacity")]
     capacity: f64,
+    /// Cost of using the vehicle (default: 0).
+    #[serde(default)]
+    fixed_cost: f64,
+    /// Cost per unit distance (default: 1).
+    #[serde(default = "default_cost_per_distance")]
+    cost_per_distance: f64,
+    /// Driver wages per unit time (default: 0).
+    #[serde(default)]
+    cost_per_time: f64,
+    /// Optional overtime as `[regular_time, extra_cost_per_time]`.
+    #[serde(default)]
+    overtime: Option<[f64; 2]>,
 }
 
 fn default_capacity() -> f64 {
     1e9
 }
 
+fn default_cost_per_distance() -> f64 {
+    1.0
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 struct InputDepot {
@@ -143,6 +191,10 @@ struct InputConfig {
     /// Maximum iterations for ALNS (default: 500).
     #[serde(default)]
     max_iterations: Option<usize>,
+    /// Cost of leaving a customer unassigned, in the ALNS and in
+    /// `total_cost` (default: 10,000).
+    #[serde(default)]
+    unassigned_penalty: Option<f64>,
 
     // --- Shared ---
     /// Random seed for reproducibility.
@@ -159,6 +211,9 @@ struct VrpInput {
     depot: InputDepot,
     #[serde(default = "default_method")]
     method: String,
+    /// Distance metric on the coordinates (default: `"euclidean"`).
+    #[serde(default = "default_metric")]
+    metric: String,
     #[serde(default)]
     config: Option<InputConfig>,
 }
@@ -167,10 +222,15 @@ fn default_method() -> String {
     "nn".to_string()
 }
 
+fn default_metric() -> String {
+    "euclidean".to_string()
+}
+
 #[derive(Debug, Serialize)]
 struct VrpOutput {
     routes: Vec<Vec<usize>>,
     total_distance: f64,
+    total_cost: f64,
     num_vehicles: usize,
     method_used: String,
     computation_time_ms: f64,
@@ -223,7 +283,21 @@ fn map_routes(routes: &[Vec<usize>], id_map: &[usize]) -> Vec<Vec<usize>> {
 }
 
 /// Applies intra-route 2-opt + or-opt local search to improve routes.
-fn apply_local_search(routes: &[Vec<usize>], dm: &DistanceMatrix) -> (Vec<Vec<usize>>, f64) {
+///
+/// The routes are kept as they are when some customer picks goods up, since
+/// neither operator checks the on-board load along the route.
+fn apply_local_search(
+    routes: &[Vec<usize>],
+    customers: &[Customer],
+    dm: &DistanceMatrix,
+) -> (Vec<Vec<usize>>, f64) {
+    if has_load_profile(customers) {
+        let total = routes
+            .iter()
+            .map(|r| RouteEnds::from(0).distance(r, dm))
+            .sum();
+        return (routes.to_vec(), total);
+    }
     let mut improved_routes = Vec::with_capacity(routes.len());
     let mut total = 0.0;
     for route in routes {
@@ -243,40 +317,106 @@ fn build_vehicles(input_vehicles: &[InputVehicle]) -> Vec<Vehicle> {
         input_vehicles
             .iter()
             .enumerate()
-            .map(|(i, v)| Vehicle::new(i, v.capacity.round().min(i32::MAX as f64) as i32))
+            .map(|(i, v)| {
+                let mut vehicle = Vehicle::new(i, v.capacity.round().min(i32::MAX as f64) as i32)
+                    .with_fixed_cost(v.fixed_cost)
+                    .with_cost_per_distance(v.cost_per_distance)
+                    .with_cost_per_time(v.cost_per_time);
+                if let Some([regular_time, cost_per_time]) = v.overtime {
+                    vehicle = vehicle.with_overtime(regular_time, cost_per_time);
+                }
+                // Each input vehicle drives one route
+                vehicle.with_available(1)
+            })
             .collect()
     }
 }
 
-/// Returns the vehicle capacity as i32, using the first vehicle or i32::MAX.
-fn vehicle_capacity(input_vehicles: &[InputVehicle]) -> i32 {
-    if input_vehicles.is_empty() {
-        i32::MAX
-    } else {
-        input_vehicles[0].capacity.round().min(i32::MAX as f64) as i32
+/// ALNS problem over the fleet, also used to price the result of every
+/// method.
+fn routing_problem(
+    customers: &[Customer],
+    dm: &DistanceMatrix,
+    vehicles: &[Vehicle],
+    cfg: &InputConfig,
+) -> RoutingAlnsProblem {
+    let problem = RoutingAlnsProblem::new(customers.to_vec(), dm.clone(), 0).with_fleet(vehicles);
+    match cfg.unassigned_penalty {
+        Some(penalty) => problem.with_unassigned_penalty(penalty),
+        None => problem,
     }
 }
 
+/// Fleet index of the vehicle of each route of a constructed solution.
+fn solution_vehicles(solution: &Solution, vehicles: &[Vehicle]) -> Result<Vec<usize>, String> {
+    solution
+        .routes()
+        .iter()
+        .map(|r| {
+            fleet_index(vehicles, r.vehicle_id())
+                .ok_or_else(|| format!("route uses unknown vehicle {}", r.vehicle_id()))
+        })
+        .collect()
+}
+
+/// Total cost of `routes` (internal indices), route `i` driven by the
+/// vehicle type `route_vehicles[i]`, with the unassigned penalty of every
+/// customer they leave out.
+fn total_cost(
+    problem: &RoutingAlnsProblem,
+    routes: &[Vec<usize>],
+    route_vehicles: Vec<usize>,
+    customers: &[Customer],
+    dm: &DistanceMatrix,
+) -> f64 {
+    let mut served = vec![false; customers.len()];
+    for &cid in routes.iter().flatten() {
+        served[cid] = true;
+    }
+    let unassigned = stop_ids(customers).filter(|&cid| !served[cid]).collect();
+    let solution = RoutingSolution::new(routes.to_vec(), unassigned, customers, dm)
+        .with_vehicles(route_vehicles);
+    problem.cost(&solution)
+}
+
 // ============================================================================
 // Solver methods
 // ============================================================================
 
-fn solve_nn(
+/// Output of a construction heuristic's solution.
+fn constructed_output(
+    solution: &Solution,
     customers: &[Customer],
     dm: &DistanceMatrix,
     vehicles: &[Vehicle],
     id_map: &[usize],
-) -> VrpOutput {
-    let solution = nearest_neighbor(customers, dm, vehicles);
+    cfg: &InputConfig,
+    method: &str,
+) -> Result<VrpOutput, String> {
     let routes: Vec<Vec<usize>> = solution.routes().iter().map(|r| r.customer_ids()).collect();
+    let problem = routing_problem(customers, dm, vehicles, cfg);
+    let route_vehicles = solution_vehicles(solution, vehicles)?;
+    let total_cost = total_cost(&problem, &routes, route_vehicles, customers, dm);
     let mapped = map_routes(&routes, id_map);
-    VrpOutput {
+    Ok(VrpOutput {
         total_distance: solution.total_distance(),
+        total_cost,
         num_vehicles: mapped.len(),
         routes: mapped,
-        method_used: "nn".to_string(),
+        method_used: method.to_string(),
         computation_time_ms: 0.0,
-    }
+    })
+}
+
+fn solve_nn(
+    customers: &[Customer],
+    dm: &DistanceMatrix,
+    vehicles: &[Vehicle],
+    id_map: &[usize],
+    cfg: &InputConfig,
+) -> Result<VrpOutput, String> {
+    let solution = nearest_neighbor(customers, dm, vehicles);
+    constructed_output(&solution, customers, dm, vehicles, id_map, cfg, "nn")
 }
 
 fn solve_savings(
@@ -284,28 +424,20 @@ fn solve_savings(
     dm: &DistanceMatrix,
     vehicles: &[Vehicle],
     id_map: &[usize],
-) -> VrpOutput {
-    let vehicle_template = &vehicles[0];
-    let solution = clarke_wright_savings(customers, dm, vehicle_template);
-    let routes: Vec<Vec<usize>> = solution.routes().iter().map(|r| r.customer_ids()).collect();
-    let mapped = map_routes(&routes, id_map);
-    VrpOutput {
-        total_distance: solution.total_distance(),
-        num_vehicles: mapped.len(),
-        routes: mapped,
-        method_used: "savings".to_string(),
-        computation_time_ms: 0.0,
-    }
+    cfg: &InputConfig,
+) -> Result<VrpOutput, String> {
+    let solution = clarke_wright_savings(customers, dm, vehicles);
+    constructed_output(&solution, customers, dm, vehicles, id_map, cfg, "savings")
 }
 
 fn solve_ga(
     customers: &[Customer],
     dm: &DistanceMatrix,
-    capacity: i32,
+    fleet: &[Vehicle],
     id_map: &[usize],
     cfg: &InputConfig,
 ) -> Result<VrpOutput, String> {
-    let problem = RoutingGaProblem::new(customers.to_vec(), dm.clone(), capacity);
+    let problem = RoutingGaProblem::new(customers.to_vec(), dm.clone(), 0).with_fleet(fleet);
 
     let mut ga_config = GaConfig::default()
         .with_population_size(cfg.population_size.unwrap_or(50))
@@ -330,15 +462,23 @@ fn solve_ga(
         GaRunner::run(&problem, &ga_config).map_err(|e| format!("GA execution error: {}", e))?;
 
     // Split the best individual to get routes
-    let split_result = split(ga_result.best.customers(), customers, dm, capacity);
+    let split_result = split_fleet(ga_result.best.customers(), customers, dm, fleet);
 
     // Apply local search to improve routes
-    let (improved_routes, total_distance) = apply_local_search(&split_result.routes, dm);
+    let (improved_routes, total_distance) = apply_local_search(&split_result.routes, customers, dm);
+    let total_cost = total_cost(
+        &routing_problem(customers, dm, fleet, cfg),
+        &improved_routes,
+        split_result.vehicles,
+        customers,
+        dm,
+    );
     let mapped = map_routes(&improved_routes, id_map);
 
     Ok(VrpOutput {
         num_vehicles: mapped.len(),
         total_distance,
+        total_cost,
         routes: mapped,
         method_used: "ga".to_string(),
         computation_time_ms: 0.0,
@@ -348,18 +488,15 @@ fn solve_ga(
 fn solve_alns(
     customers: &[Customer],
     dm: &DistanceMatrix,
-    capacity: i32,
+    fleet: &[Vehicle],
     id_map: &[usize],
     cfg: &InputConfig,
 ) -> Result<VrpOutput, String> {
-    let problem = RoutingAlnsProblem::new(customers.to_vec(), dm.clone(), capacity);
+    let problem = routing_problem(customers, dm, fleet, cfg);
 
     let destroy_ops = vec![RandomRemoval];
-    let repair_ops = vec![GreedyInsertion::new(
-        dm.clone(),
-        customers.to_vec(),
-        capacity,
-    )];
+    let repair_ops =
+        vec![GreedyInsertion::new(dm.clone(), customers.to_vec(), 0).with_fleet(fleet)];
 
     let mut alns_config =
         AlnsConfig::default().with_max_iterations(cfg.max_iterations.unwrap_or(500));
@@ -377,12 +514,15 @@ fn solve_alns(
 
     // Apply local search to improve ALNS result
     let alns_routes: Vec<Vec<usize>> = result.best.routes().to_vec();
-    let (improved_routes, total_distance) = apply_local_search(&alns_routes, dm);
+    let (improved_routes, total_distance) = apply_local_search(&alns_routes, customers, dm);
+    let route_vehicles = result.best.vehicles().to_vec();
+    let total_cost = total_cost(&problem, &improved_routes, route_vehicles, customers, dm);
     let mapped = map_routes(&improved_routes, id_map);
 
     Ok(VrpOutput {
         num_vehicles: mapped.len(),
         total_distance,
+        total_cost,
         routes: mapped,
         method_used: "alns".to_string(),
         computation_time_ms: 0.0,
@@ -405,14 +545,15 @@ fn solve_alns(
 /// - `"alns"` — Adaptive Large Neighborhood Search + local search
 ///
 /// # Returns
-/// A JS object with `routes`, `total_distance`, `num_vehicles`,
-/// `method_used`, and `computation_time_ms`.
+/// A JS object with `routes`, `total_distance`, `total_cost`,
+/// `num_vehicles`, `method_used`, and `computation_time_ms`.
 ///
 /// # Errors
 /// Returns a `JsValue` string describing the error if input is invalid.
 #[wasm_bindgen]
 pub fn solve_vrp(problem: JsValue) -> Result<JsValue, JsValue> {
     let input: VrpInput = from_js(problem, "problem")?;
+    let metric: Metric = input.metric.parse().map_err(js_err)?;
 
     let (customers, id_map) = build_customers(&input.depot, &input.customers);
 
@@ -420,6 +561,7 @@ pub fn solve_vrp(problem: JsValue) -> Result<JsValue, JsValue> {
         let output = VrpOutput {
             routes: vec![],
             total_distance: 0.0,
+            total_cost: 0.0,
             num_vehicles: 0,
             method_used: input.method.clone(),
             computation_time_ms: 0.0,
@@ -427,18 +569,17 @@ pub fn solve_vrp(problem: JsValue) -> Result<JsValue, JsValue> {
         return serde_wasm_bindgen::to_value(&output).map_err(js_err);
     }
 
-    let dm = DistanceMatrix::from_customers(&customers);
+    let dm = DistanceMatrix::from_customers_with(&customers, metric);
     let vehicles = build_vehicles(&input.vehicles);
-    let capacity = vehicle_capacity(&input.vehicles);
     let config = input.config.unwrap_or_default();
 
     let start = web_time();
 
     let mut output = match input.method.as_str() {
-        "nn" => solve_nn(&customers, &dm, &vehicles, &id_map),
-        "savings" => solve_savings(&customers, &dm, &vehicles, &id_map),
-        "ga" => solve_ga(&customers, &dm, capacity, &id_map, &config).map_err(js_err)?,
-        "alns" => solve_alns(&customers, &dm, capacity, &id_map, &config).map_err(js_err)?,
+        "nn" => solve_nn(&customers, &dm, &vehicles, &id_map, &config).map_err(js_err)?,
+        "savings" => solve_savings(&customers, &dm, &vehicles, &id_map, &config).map_err(js_err)?,
+        "ga" => solve_ga(&customers, &dm, &vehicles, &id_map, &config).map_err(js_err)?,
+        "alns" => solve_alns(&customers, &dm, &vehicles, &id_map, &config).map_err(js_err)?,
         other => {
             return Err(js_err(format!(
                 "unknown method '{}'. Supported: \"nn\", \"savings\", \"ga\", \"alns\"",
@@ -519,7 +660,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "GA with valid config should succeed");
         let output = result.unwrap();
         assert_eq!(output.method_used, "ga");
@@ -538,7 +679,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_err(), "population_size=1 should fail validation");
         let err = result.unwrap_err();
         assert!(
@@ -557,7 +698,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_err(), "population_size=0 should fail validation");
     }
 
@@ -572,7 +713,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_err(), "max_generations=0 should fail validation");
         let err = result.unwrap_err();
         assert!(
@@ -595,7 +736,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(
             result.is_err(),
             "elite_ratio filling entire population should fail"
@@ -613,7 +754,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "GA with 1 customer should succeed");
         let output = result.unwrap();
         assert_eq!(output.routes.len(), 1);
@@ -632,7 +773,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(
             result.is_ok(),
             "clamped mutation_rate should not cause error"
@@ -649,13 +790,104 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_alns(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_alns(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "ALNS with valid config should succeed");
         let output = result.unwrap();
         assert_eq!(output.method_used, "alns");
         assert!(!output.routes.is_empty());
     }
 
+    // ---- Vehicle costs ----
+
+    #[test]
+    fn vehicle_costs_priced() {
+        let (customers, dm, id_map) = test_customers(4);
+        let vehicles = build_vehicles(&[InputVehicle {
+            capacity: 100.0,
+            fixed_cost: 50.0,
+            cost_per_distance: 2.0,
+            cost_per_time: 1.0,
+            overtime: None,
+        }]);
+        let cfg = InputConfig {
+            max_iterations: Some(20),
+            seed: Some(42),
+            ..InputConfig::default()
+        };
+        let output = solve_alns(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        // Without service times the duration equals the distance
+        let expected = 50.0 * output.num_vehicles as f64 + 3.0 * output.total_distance;
+        assert!((output.total_cost - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn whole_fleet_used() {
+        let (customers, dm, id_map) = test_customers(4);
+        // A costly van for one customer and a truck for all of them
+        let vehicles = vec![
+            Vehicle::new(0, 5).with_fixed_cost(100.0),
+            Vehicle::new(1, 100),
+        ];
+        let cfg = InputConfig {
+            population_size: Some(10),
+            max_generations: Some(10),
+            max_iterations: Some(50),
+            seed: Some(42),
+            ..InputConfig::default()
+        };
+        let ga = solve_ga(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        let alns = solve_alns(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        let savings = solve_savings(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        for output in [ga, alns, savings] {
+            // Only the truck is used
+            assert!(output.num_vehicles < 4, "{}", output.method_used);
+            assert!((output.total_cost - output.total_distance).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn input_vehicles_limit_routes() {
+        let (customers, dm, id_map) = test_customers(6);
+        // Two vehicles of capacity 10 hold four of the six customers
+        let input = || InputVehicle {
+            capacity: 10.0,
+            fixed_cost: 0.0,
+            cost_per_distance: 1.0,
+            cost_per_time: 0.0,
+            overtime: None,
+        };
+        let vehicles = build_vehicles(&[input(), input()]);
+        let cfg = InputConfig {
+            population_size: Some(10),
+            max_generations: Some(10),
+            max_iterations: Some(50),
+            seed: Some(42),
+            ..InputConfig::default()
+        };
+        let ga = solve_ga(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        let alns = solve_alns(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        let nn = solve_nn(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        let savings = solve_savings(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        for output in [ga, alns, nn, savings] {
+            assert!(output.num_vehicles <= 2, "{}", output.method_used);
+            assert!(output.total_cost > 10_000.0, "{}", output.method_used);
+        }
+    }
+
+    #[test]
+    fn unassigned_penalty_in_total_cost() {
+        let (mut customers, dm, id_map) = test_customers(3);
+        customers[3] = Customer::new(3, customers[3].x(), customers[3].y(), 500, 0.0);
+        let vehicles = vec![Vehicle::new(0, 100)];
+        let cfg = InputConfig {
+            unassigned_penalty: Some(1000.0),
+            ..InputConfig::default()
+        };
+        let output = solve_nn(&customers, &dm, &vehicles, &id_map, &cfg).expect("valid");
+        assert!(output.routes.iter().all(|r| !r.contains(&3)));
+        assert!((output.total_cost - (output.total_distance + 1000.0)).abs() < 1e-6);
+    }
+
     // ---- ALNS: zero iterations ----
 
     #[test]
@@ -666,7 +898,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_alns(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_alns(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_err(), "max_iterations=0 should fail validation");
         let err = result.unwrap_err();
         assert!(
@@ -682,7 +914,7 @@ mod tests {
     fn ga_default_config() {
         let (customers, dm, id_map) = test_customers(3);
         let cfg = InputConfig::default();
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "GA with default config should succeed");
     }
 
@@ -690,7 +922,7 @@ mod tests {
     fn alns_default_config() {
         let (customers, dm, id_map) = test_customers(3);
         let cfg = InputConfig::default();
-        let result = solve_alns(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_alns(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "ALNS with default config should succeed");
     }
 
@@ -705,7 +937,7 @@ mod tests {
             seed: Some(123),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 1000, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 1000)], &id_map, &cfg);
         assert!(
             result.is_ok(),
             "GA with 20 customers should succeed: {:?}",
@@ -728,7 +960,7 @@ mod tests {
             seed: Some(99),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 5, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 5)], &id_map, &cfg);
         assert!(
             result.is_ok(),
             "GA with tight capacity should succeed: {:?}",
@@ -749,7 +981,7 @@ mod tests {
             seed: Some(42),
             ..InputConfig::default()
         };
-        let result = solve_ga(&customers, &dm, 100, &id_map, &cfg);
+        let result = solve_ga(&customers, &dm, &[Vehicle::new(0, 100)], &id_map, &cfg);
         assert!(result.is_ok(), "GA with 2 customers should succeed");
     }
 }